@@ -0,0 +1,143 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+use tracing::{debug, info, warn};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Logo copied next to HTML reports that don't embed it
+const LOGO_FILE: &str = "pressr-logo.png";
+
+/// Pack the generated report files into `report_<run_id>.zip` in `dir`
+///
+/// Files are stored under their file names, next to a `manifest.json` listing them.
+/// The logo is added if an HTML report references it from `dir`. Missing files are
+/// skipped with a warning. Returns the path of the bundle.
+pub fn write_bundle(dir: &Path, run_id: &str, files: &[PathBuf]) -> io::Result<PathBuf> {
+    let mut files: Vec<&PathBuf> = files.iter()
+        .filter(|path| {
+            let exists = path.is_file();
+            if !exists {
+                warn!("Not bundling missing report file {}", path.display());
+            }
+            exists
+        })
+        .collect();
+    // The same report can be listed more than once, not necessarily next to itself
+    let mut seen = HashSet::new();
+    files.retain(|path| seen.insert(*path));
+    
+    let logo = dir.join(LOGO_FILE);
+    let has_html = files.iter().any(|path| path.extension().is_some_and(|ext| ext == "html"));
+    let logo = (has_html && logo.is_file()).then_some(logo);
+    
+    let path = dir.join(format!("report_{}.zip", run_id));
+    info!("Bundling {} report files into {}", files.len(), path.display());
+    let mut zip = ZipWriter::new(BufWriter::new(File::create(&path)?));
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    
+    let mut names = Vec::new();
+    for file in files.into_iter().chain(logo.as_ref()) {
+        let name = file.file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        debug!("Adding {} to the bundle", name);
+        zip.start_file(name.as_str(), options)?;
+        io::copy(&mut File::open(file)?, &mut zip)?;
+        names.push(name);
+    }
+    
+    let manifest = json!({
+        "run_id": run_id,
+        "files": names,
+    });
+    zip.start_file("manifest.json", options)?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+    
+    zip.finish()?.flush()?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use zip::ZipArchive;
+    
+    #[test]
+    fn repeated_files_are_bundled_once() {
+        let dir = std::env::temp_dir().join(format!("pressr-bundle-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let text = dir.join("report.txt");
+        let json = dir.join("report.json");
+        fs::write(&text, "text").unwrap();
+        fs::write(&json, "{}").unwrap();
+        
+        let bundle = write_bundle(&dir, "run", &[text.clone(), json.clone(), text]).unwrap();
+        let mut archive = ZipArchive::new(File::open(&bundle).unwrap()).unwrap();
+        let mut names: Vec<&str> = archive.file_names().collect();
+        names.sort_unstable();
+        assert_eq!(names, ["manifest.json", "report.json", "report.txt"]);
+        
+        let manifest: serde_json::Value = serde_json::from_reader(archive.by_name("manifest.json").unwrap()).unwrap();
+        assert_eq!(manifest["files"], json!(["report.txt", "report.json"]));
+        fs::remove_dir_all(dir).unwrap();
+    }
+    
+    #[test]
+    fn every_artifact_of_a_run_shares_its_id() {
+        use pressr_core::{LoadTestResults, ReportFormat, ReportOptions, RequestResult, write_report};
+        
+        let dir = std::env::temp_dir().join(format!("pressr-run-id-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let request = RequestResult { status: Some(200), success: true, response_time: 10, ..Default::default() };
+        let mut results = LoadTestResults::new(vec![request], std::time::Duration::from_secs(1));
+        results.run_id = "20261018-abc".to_string();
+        
+        let files: Vec<PathBuf> = [ReportFormat::Text, ReportFormat::Json, ReportFormat::Markdown].into_iter()
+            .map(|format| {
+                let options = ReportOptions { format, output_dir: Some(dir.to_string_lossy().to_string()), ..Default::default() };
+                PathBuf::from(write_report(&results, &options).unwrap().path)
+            })
+            .collect();
+        for file in &files {
+            let name = file.file_name().unwrap().to_string_lossy();
+            assert!(name.starts_with("report_20261018-abc."), "{}", name);
+            assert!(fs::read_to_string(file).unwrap().contains("20261018-abc"), "{}", name);
+        }
+        
+        let bundle = write_bundle(&dir, &results.run_id, &files).unwrap();
+        assert_eq!(bundle.file_name().unwrap(), "report_20261018-abc.zip");
+        let mut archive = ZipArchive::new(File::open(&bundle).unwrap()).unwrap();
+        let manifest: serde_json::Value = serde_json::from_reader(archive.by_name("manifest.json").unwrap()).unwrap();
+        assert_eq!(manifest["run_id"], "20261018-abc");
+        fs::remove_dir_all(dir).unwrap();
+    }
+    
+    #[test]
+    fn html_bundles_carry_the_logo_and_skip_missing_files() {
+        let dir = std::env::temp_dir().join(format!("pressr-bundle-logo-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let files: Vec<PathBuf> = ["report_r.html", "report_r.svg", "report_r.json", "timeseries.csv"].into_iter()
+            .map(|name| dir.join(name))
+            .collect();
+        for file in &files {
+            fs::write(file, "content").unwrap();
+        }
+        fs::write(dir.join(LOGO_FILE), [0x89, b'P', b'N', b'G']).unwrap();
+        let missing = dir.join("report_r.md");
+        
+        let bundle = write_bundle(&dir, "r", &[files.clone(), vec![missing]].concat()).unwrap();
+        let mut archive = ZipArchive::new(File::open(&bundle).unwrap()).unwrap();
+        let mut names: Vec<&str> = archive.file_names().collect();
+        names.sort_unstable();
+        assert_eq!(names, ["manifest.json", LOGO_FILE, "report_r.html", "report_r.json", "report_r.svg", "timeseries.csv"]);
+        
+        let manifest: serde_json::Value = serde_json::from_reader(archive.by_name("manifest.json").unwrap()).unwrap();
+        assert_eq!(manifest["files"], json!(["report_r.html", "report_r.svg", "report_r.json", "timeseries.csv", LOGO_FILE]));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,247 @@
+use clap::{parser::ValueSource, Arg, ArgAction, ArgMatches, Command, CommandFactory, FromArgMatches};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use crate::error::{err_msg, Result};
+
+/// Id of the option pointing at the config file
+const CONFIG_FILE_OPTION: &str = "config_file";
+
+/// Options of a config file, by long option name (with `-` or `_`)
+#[derive(Debug, Deserialize)]
+#[serde(transparent)]
+pub struct ConfigFile {
+    options: BTreeMap<String, OptionValue>,
+}
+
+/// Value of an option in a config file
+#[derive(Debug, Deserialize)]
+#[serde(untagged, expecting = "a string, number, boolean or list of them")]
+enum OptionValue {
+    /// A single value, e.g. `requests = 500` or `detailed = true`
+    Scalar(Scalar),
+    
+    /// The values of a repeatable option, e.g. `header = ["Accept: */*"]`
+    List(Vec<Scalar>),
+}
+
+/// A single value of an option
+#[derive(Debug, Deserialize)]
+#[serde(untagged, expecting = "a string, number or boolean")]
+enum Scalar {
+    Bool(bool),
+    Number(serde_json::Number),
+    Text(String),
+}
+
+impl Scalar {
+    /// The value as given on the command line
+    fn into_string(self) -> String {
+        match self {
+            Scalar::Bool(value) => value.to_string(),
+            Scalar::Number(value) => value.to_string(),
+            Scalar::Text(value) => value,
+        }
+    }
+}
+
+impl ConfigFile {
+    /// Load a config file (TOML, YAML or JSON, by extension)
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| err_msg(format!("Failed to read config file {}: {}", path.display(), e)))?;
+        
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase();
+        match extension.as_str() {
+            "toml" => toml::from_str(&content).map_err(|e| e.to_string()),
+            "yaml" | "yml" => serde_yaml::from_str(&content).map_err(|e| e.to_string()),
+            "json" => serde_json::from_str(&content).map_err(|e| e.to_string()),
+            _ => Err("expected a .toml, .yaml, .yml or .json file".to_string()),
+        }.map_err(|e| err_msg(format!("Invalid config file {}: {}", path.display(), e)))
+    }
+}
+
+/// Parse the command line arguments, with the options of `--config-file` (if given) filling in
+/// the options not given on the command line
+///
+/// The file maps option names to values, e.g. `url = "https://example.com"`, `requests = 500`
+/// or `header = ["Accept: */*"]`. Its options become the defaults of the command line, so a
+/// command line option overrides the file. Flags take `true` or `false`, and can be turned off
+/// on the command line with `--flag=false`. Repeatable options take lists, and values given on
+/// the command line are added to the file's.
+///
+/// Invalid command line arguments print the usage and exit, like `Parser::parse_from`.
+pub fn parse_args<T: CommandFactory + FromArgMatches>(args: Vec<OsString>) -> Result<T> {
+    let flags: Vec<String> = T::command().get_arguments()
+        .filter(|arg| matches!(arg.get_action(), ArgAction::SetTrue))
+        .map(|arg| arg.get_id().to_string())
+        .collect();
+    let command = overridable_flags(T::command());
+    // Errors (e.g. a required option that is only in the file) are reported by the final parse,
+    // only --help and --version end the parse here
+    let cli = command.clone().ignore_errors(true).try_get_matches_from(&args).unwrap_or_else(|e| e.exit());
+    let Some(path) = cli.get_raw(CONFIG_FILE_OPTION).and_then(|mut values| values.next()).map(PathBuf::from) else {
+        return parse_matches(command, args);
+    };
+    let file = ConfigFile::load(&path)?;
+    
+    let mut command = command;
+    let mut lists = BTreeMap::new();
+    let mut from_file = Vec::new();
+    for (key, value) in file.options {
+        let long = key.replace('_', "-");
+        let arg = command.get_arguments()
+            .find(|arg| arg.get_long() == Some(long.as_str()) && arg.get_id() != CONFIG_FILE_OPTION)
+            .ok_or_else(|| err_msg(format!("Unknown option '{}' in config file {}", key, path.display())))?;
+        let id = arg.get_id().to_string();
+        let repeatable = matches!(arg.get_action(), ArgAction::Append);
+        let flag = flags.contains(&id);
+        
+        let values: Vec<String> = match value {
+            OptionValue::Scalar(value) => vec![value.into_string()],
+            OptionValue::List(values) if repeatable => values.into_iter().map(Scalar::into_string).collect(),
+            OptionValue::List(_) => return Err(err_msg(format!("Option '{}' in config file {} takes a single value",
+                                                               key, path.display()))),
+        };
+        // The command line values of a repeatable option are added to the file's
+        if repeatable && cli.value_source(&id) == Some(ValueSource::CommandLine) {
+            let given = cli.get_raw(&id).into_iter().flatten().map(|value| value.to_string_lossy().into_owned());
+            lists.insert(id.clone(), values.iter().cloned().chain(given).collect::<Vec<_>>());
+        }
+        // A flag turned off is as good as left out
+        let set = !(flag && values == ["false"]);
+        command = command.mut_arg(&id, |arg| arg.required(false).default_values(values));
+        if set {
+            from_file.push(id);
+        }
+    }
+    
+    // Defaults are not checked for conflicts, so the file's options are checked here
+    for id in &from_file {
+        let arg = command.get_arguments().find(|arg| arg.get_id() == id.as_str()).expect("option of the config file");
+        let conflict = command.get_arg_conflicts_with(arg).into_iter().find(|other| {
+            from_file.iter().any(|id| other.get_id() == id.as_str())
+                || cli.value_source(other.get_id().as_str()) == Some(ValueSource::CommandLine)
+        });
+        if let Some(other) = conflict {
+            return Err(err_msg(format!("Option '{}' in config file {} cannot be used with '{}'",
+                                       id.replace('_', "-"), path.display(), other.get_long().unwrap_or(other.get_id().as_str()))));
+        }
+    }
+    
+    let mut parsed: T = parse_matches(command, args)?;
+    if !lists.is_empty() {
+        // Only the merged lists have values, so only their fields are updated
+        let command = overridable_flags(T::command())
+            .mut_args(|arg| match lists.get(arg.get_id().as_str()) {
+                Some(values) => arg.required(false).default_values(values.clone()),
+                None => arg.required(false).default_value(None),
+            });
+        let matches = command.ignore_errors(true).try_get_matches_from(["pressr"])
+            .map_err(|e| err_msg(e.to_string()))?;
+        parsed.update_from_arg_matches(&matches).map_err(|e| err_msg(e.to_string()))?;
+    }
+    Ok(parsed)
+}
+
+/// Parse the arguments with the command, exiting with the usage on invalid arguments
+fn parse_matches<T: FromArgMatches>(command: Command, args: Vec<OsString>) -> Result<T> {
+    let matches: ArgMatches = command.try_get_matches_from(args).unwrap_or_else(|e| e.exit());
+    T::from_arg_matches(&matches).map_err(|e| err_msg(e.to_string()))
+}
+
+/// Let every flag take an optional value (`--flag` or `--flag=false`), so that the command line
+/// can turn off a flag the config file turns on
+fn overridable_flags(command: Command) -> Command {
+    command.mut_args(|arg: Arg| if matches!(arg.get_action(), ArgAction::SetTrue) {
+        arg.action(ArgAction::Set)
+            .value_parser(clap::value_parser!(bool))
+            .num_args(0..=1)
+            .require_equals(true)
+            .default_missing_value("true")
+            .default_value("false")
+            .hide_default_value(true)
+            .hide_possible_values(true)
+    } else {
+        arg
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    
+    #[derive(Parser, Debug)]
+    struct Options {
+        #[arg(long)]
+        config_file: Option<PathBuf>,
+        
+        #[arg(long)]
+        url: String,
+        
+        #[arg(long, default_value_t = 10)]
+        requests: usize,
+        
+        #[arg(long)]
+        detailed: bool,
+        
+        #[arg(long, conflicts_with = "verbose")]
+        quiet: bool,
+        
+        #[arg(long)]
+        verbose: bool,
+        
+        #[arg(long = "header")]
+        headers: Vec<String>,
+    }
+    
+    fn parse(file: &str, extension: &str, args: &[&str]) -> Result<Options> {
+        let path = std::env::temp_dir().join(format!("pressr-config-{}-{}.{}", std::process::id(), args.len(), extension));
+        std::fs::write(&path, file).unwrap();
+        let mut argv: Vec<OsString> = vec!["pressr".into(), "--config-file".into(), path.clone().into()];
+        argv.extend(args.iter().map(OsString::from));
+        
+        let options = parse_args(argv);
+        std::fs::remove_file(&path).unwrap();
+        options
+    }
+    
+    #[test]
+    fn config_file_options_fill_in_the_command_line() {
+        let file = "url = \"http://localhost\"\nrequests = 500\ndetailed = true\nheader = [\"Accept: */*\"]\n";
+        let options = parse(file, "toml", &[]).unwrap();
+        
+        assert_eq!(options.url, "http://localhost");
+        assert_eq!(options.requests, 500);
+        assert!(options.detailed && !options.quiet);
+        assert_eq!(options.headers, ["Accept: */*"]);
+    }
+    
+    #[test]
+    fn command_line_options_override_the_config_file() {
+        let file = r#"{ "url": "http://localhost", "requests": 500, "detailed": true, "header": ["Accept: */*"] }"#;
+        let options = parse(file, "json", &["--requests", "7", "--detailed=false", "--quiet", "--header", "X-Run: 1"]).unwrap();
+        
+        assert_eq!(options.url, "http://localhost");
+        assert_eq!(options.requests, 7);
+        assert!(!options.detailed && options.quiet);
+        assert_eq!(options.headers, ["Accept: */*", "X-Run: 1"]);
+    }
+    
+    #[test]
+    fn config_files_reject_unknown_and_nested_options() {
+        assert!(parse("url: http://localhost\nbogus: 1\n", "yaml", &[]).is_err());
+        assert!(parse("url: http://localhost\nrequests: { count: 1 }\n", "yaml", &[]).is_err());
+        assert!(parse("url: http://localhost\nrequests: [1, 2]\n", "yml", &["--quiet"]).is_err());
+    }
+    
+    #[test]
+    fn config_file_options_are_checked_for_conflicts() {
+        assert!(parse("url = \"http://localhost\"\nquiet = true\n", "toml", &["--verbose"]).is_err());
+        assert!(parse("url = \"http://localhost\"\nquiet = true\nverbose = true\n", "toml", &[]).is_err());
+        assert!(parse("url = \"http://localhost\"\nquiet = false\n", "toml", &["--verbose"]).is_ok());
+    }
+}
@@ -28,6 +28,10 @@ pub enum AppError {
     #[error("Core error: {0}")]
     Core(#[from] pressr_core::Error),
     
+    /// The run violated pass/fail thresholds
+    #[error("{0} threshold(s) violated")]
+    ThresholdsFailed(usize),
+    
     /// Generic error with message
     #[error("{0}")]
     Generic(String),
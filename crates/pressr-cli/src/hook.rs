@@ -0,0 +1,115 @@
+use std::process::ExitStatus;
+
+use pressr_core::LoadTestResults;
+use tokio::process::Command;
+use tracing::{debug, info, warn};
+
+/// Environment variables describing a finished run, passed to the post-run hook
+///
+/// Response times are in milliseconds; percentiles are the results' percentiles of the
+/// successful requests, as in the reports (0 if none succeeded). `PRESSR_PASSED` is
+/// `results.passed`, so it agrees with the exit code.
+pub fn hook_env(results: &LoadTestResults, report_path: &str) -> Vec<(&'static str, String)> {
+    let percentile = |p: f64| format!("{:.0}", results.percentiles.and_then(|percentiles| percentiles.get(p)).unwrap_or(0.0));
+    
+    vec![
+        ("PRESSR_RUN_ID", results.run_id.clone()),
+        ("PRESSR_TOTAL_REQUESTS", results.total_requests.to_string()),
+        ("PRESSR_SUCCESSFUL_REQUESTS", results.successful_requests.to_string()),
+        ("PRESSR_FAILED_REQUESTS", results.failed_requests.to_string()),
+        ("PRESSR_SUCCESS_RATE", format!("{:.2}", results.success_rate())),
+        ("PRESSR_AVG", format!("{:.2}", results.average_response_time)),
+        ("PRESSR_P50", percentile(50.0)),
+        ("PRESSR_P95", percentile(95.0)),
+        ("PRESSR_P99", percentile(99.0)),
+        ("PRESSR_THROUGHPUT", format!("{:.2}", results.throughput)),
+        ("PRESSR_DURATION", format!("{:.2}", results.duration_secs)),
+        ("PRESSR_REPORT_PATH", report_path.to_string()),
+        ("PRESSR_PASSED", results.passed.to_string()),
+        ("PRESSR_SUMMARY", results.summary()),
+    ]
+}
+
+/// Run the post-run hook through the shell with the given environment variables
+///
+/// The hook inherits stdout and stderr. Returns its exit status, or `None` if it
+/// could not be started.
+pub async fn run_post_hook(command: &str, env: Vec<(&'static str, String)>) -> Option<ExitStatus> {
+    info!("Running post-run hook: {}", command);
+    
+    let mut hook = if cfg!(windows) {
+        let mut hook = Command::new("cmd");
+        hook.arg("/C").arg(command);
+        hook
+    } else {
+        let mut hook = Command::new("sh");
+        hook.arg("-c").arg(command);
+        hook
+    };
+    for (key, value) in env {
+        debug!("Hook environment: {}={}", key, value);
+        hook.env(key, value);
+    }
+    
+    match hook.status().await {
+        Ok(status) => {
+            if status.success() {
+                info!("Post-run hook exited with {}", status);
+            } else {
+                warn!("Post-run hook exited with {}", status);
+            }
+            Some(status)
+        },
+        Err(e) => {
+            warn!("Failed to run post-run hook: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pressr_core::{RequestResult, Thresholds};
+    use std::time::Duration;
+    
+    fn results() -> LoadTestResults {
+        let requests = (1..=10)
+            .map(|response_time| RequestResult { status: Some(200), response_time, success: true, ..Default::default() })
+            .collect();
+        LoadTestResults::new(requests, Duration::from_secs(1))
+    }
+    
+    fn env_value(env: &[(&'static str, String)], key: &str) -> String {
+        env.iter().find(|(name, _)| *name == key).map(|(_, value)| value.clone()).unwrap()
+    }
+    
+    #[test]
+    fn passed_follows_the_thresholds() {
+        let passing = results();
+        assert_eq!(env_value(&hook_env(&passing, "report.html"), "PRESSR_PASSED"), "true");
+        
+        let failing = results().with_thresholds(Thresholds { min_throughput: Some(1000.0), ..Default::default() });
+        assert_eq!(failing.failed_requests, 0);
+        assert_eq!(env_value(&hook_env(&failing, "report.html"), "PRESSR_PASSED"), "false");
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn hook_runs_with_the_result_variables() {
+        let dir = std::env::temp_dir().join(format!("pressr-hook-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let output = dir.join("env.txt");
+        let command = format!(
+            "echo \"$PRESSR_SUCCESS_RATE $PRESSR_P95 $PRESSR_REPORT_PATH $PRESSR_PASSED\" > {}",
+            output.display()
+        );
+        let results = results();
+        
+        let status = run_post_hook(&command, hook_env(&results, "reports/run.html")).await.unwrap();
+        assert!(status.success());
+        let expected = format!("100.00 {:.0} reports/run.html true\n", results.percentiles.unwrap().p95);
+        assert_eq!(std::fs::read_to_string(&output).unwrap(), expected);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
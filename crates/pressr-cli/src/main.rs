@@ -1,37 +1,74 @@
 use clap::{Parser, ValueEnum};
 use reqwest::{Method, header::{HeaderMap, HeaderName, HeaderValue}};
-use std::{path::PathBuf, str::FromStr};
+use std::{io::{self, IsTerminal}, path::PathBuf, str::FromStr, sync::Arc, time::Duration};
 use tracing::{debug, error, info, warn};
 use tracing_subscriber::{fmt, EnvFilter};
 
 // Import pressr-core
-use pressr_core::{Result, Error, RequestData, Runner, Config, ReportFormat as CoreReportFormat, ReportOptions};
+use pressr_core::{Result, Error, RequestData, Runner, Config, DispatchMode, HttpVersion, DEFAULT_MAX_REDIRECTS, Auth, BurstProfile, RetryMatcher, StopHandle, Target, Checkpoint, LoadTestResults, Thresholds, DEFAULT_REGRESSION_THRESHOLD, compare_results, generate_run_id, ReportFormat as CoreReportFormat, ReportOptions, DetailSelection, EstimatorKind, HistogramSettings, PercentileMethod, VariableStrategy, FanoutExporter, GeneratedReport};
+use summary::{SummaryExporter, SummaryOutput, SummaryStream};
 
+mod bundle;
+mod config_file;
 mod error;
+mod hook;
+mod progress;
+mod summary;
+#[cfg(feature = "statsd")]
+mod statsd;
+#[cfg(feature = "otlp")]
+mod otlp;
 
 use error::AppError;
 
+/// Fewest requests for which percentiles and the standard deviation are meaningful
+const MIN_RELIABLE_REQUESTS: usize = 10;
+
 /// pressr - A load testing tool for APIs and applications
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
+    /// Load options from a TOML, YAML or JSON file (keys are long option names, e.g.
+    /// `requests = 500`); options given on the command line override the file
+    #[arg(long)]
+    config_file: Option<PathBuf>,
+    
     /// URL to send requests to
     #[arg(short, long)]
     url: String,
+    
+    /// Additional target as "[METHOD] URL [weight=N]"; requests are spread across --url and all
+    /// targets in proportion to their weights (round-robin by default), with per-host and
+    /// per-target statistics (can be repeated)
+    #[arg(long = "target", value_parser = parse_target)]
+    targets: Vec<Target>,
+    
+    /// Weight of --url among the targets (0 to only send requests to the targets)
+    #[arg(long, default_value = "1", requires = "targets")]
+    url_weight: u32,
 
     /// HTTP method to use
     #[arg(short, long, value_enum, default_value_t = HttpMethod::Get)]
     method: HttpMethod,
 
-    /// Number of requests to send
-    #[arg(short, long, default_value_t = 100)]
-    requests: usize,
+    /// Number of requests to send (accepts k/M suffixes, e.g. "500k" or "1M");
+    /// defaults to 100, or to no limit with --duration
+    #[arg(short, long, value_parser = parse_count)]
+    requests: Option<usize>,
+    
+    /// Keep sending requests for this long (e.g. "60s", "5m"), with --requests as an optional upper bound
+    #[arg(long, value_parser = parse_duration, conflicts_with_all = ["checkpoint", "resume", "until_success"])]
+    duration: Option<Duration>,
 
-    /// Number of concurrent requests
-    #[arg(short, long, default_value_t = 10)]
+    /// Number of concurrent requests (accepts k/M suffixes)
+    #[arg(short, long, value_parser = parse_count, default_value = "10")]
     concurrency: usize,
 
-    /// Path to data file (JSON or YAML) containing request data
+    /// Grow the concurrency linearly from 1 to --concurrency over this period (e.g. "30s")
+    #[arg(long, value_parser = parse_duration, conflicts_with = "burst_size")]
+    ramp_up: Option<Duration>,
+
+    /// Path to data file (JSON, YAML or CSV) containing request data
     #[arg(short, long)]
     data_file: Option<PathBuf>,
 
@@ -39,10 +76,39 @@ struct Args {
     #[arg(short = 'H', long = "header")]
     headers: Vec<String>,
 
-    /// Request timeout in seconds
-    #[arg(short, long, default_value_t = 30)]
+    /// Request timeout in seconds, or a duration with a unit suffix (e.g. "30s", "2m")
+    #[arg(short, long, value_parser = parse_timeout_secs, default_value = "30")]
     timeout: u64,
 
+    /// Close pooled connections after they have been idle this long (e.g. "30s")
+    #[arg(long, value_parser = parse_duration)]
+    idle_timeout: Option<Duration>,
+
+    /// Keep cookies set by responses (shared by all concurrent requests, so e.g. the
+    /// --auth-request login or the test request establishes one session for the run)
+    #[arg(long)]
+    cookie_store: bool,
+
+    /// Send all requests through this proxy (e.g. "http://proxy:3128" or "socks5://proxy:1080")
+    #[arg(long)]
+    proxy: Option<String>,
+
+    /// Don't follow redirects: 3xx responses are recorded with their own status code
+    #[arg(long)]
+    no_follow_redirects: bool,
+
+    /// Maximum number of redirects followed per request
+    #[arg(long, default_value_t = DEFAULT_MAX_REDIRECTS, conflicts_with = "no_follow_redirects")]
+    max_redirects: usize,
+
+    /// HTTP version to speak ("http2" uses HTTP/2 without negotiation, also over plain HTTP)
+    #[arg(long, value_enum, default_value_t = HttpVersionArg::Auto)]
+    http_version: HttpVersionArg,
+
+    /// User-Agent sent with every request (default "pressr/<version>"; a User-Agent header takes precedence)
+    #[arg(long)]
+    user_agent: Option<String>,
+
     /// Output format
     #[arg(short, long, value_enum, default_value_t = OutputFormat::Html)]
     output: OutputFormat,
@@ -51,6 +117,11 @@ struct Args {
     #[arg(short, long)]
     verbose: bool,
     
+    /// Suppress warnings about the run configuration (e.g. too few requests for reliable statistics)
+    /// and the live progress bar
+    #[arg(short, long)]
+    quiet: bool,
+    
     /// Output file for the report (if not specified, auto-generates filename in reports directory)
     #[arg(short = 'f', long)]
     output_file: Option<String>,
@@ -63,13 +134,317 @@ struct Args {
     #[arg(long)]
     detailed: bool,
     
+    /// List at most this many requests in the detailed section
+    #[arg(long, value_parser = parse_count)]
+    detail_limit: Option<usize>,
+    
+    /// Which requests the detailed section lists when --detail-limit is set
+    #[arg(long, value_enum, default_value_t = DetailSelectionArg::First, requires = "detail_limit")]
+    detail_selection: DetailSelectionArg,
+    
     /// Generate multiple report formats at once (comma-separated list, e.g., "html,json")
     #[arg(long)]
     report_formats: Option<String>,
     
+    /// Pack all generated reports (and the time series CSV) into report_<run id>.zip with a manifest
+    #[arg(long)]
+    bundle: bool,
+    
     /// Save report to custom output directory instead of 'reports/'
     #[arg(long)]
     output_dir: Option<String>,
+    
+    /// Embed the logo into HTML reports so they are a single standalone file
+    #[arg(long)]
+    inline_assets: bool,
+    
+    /// Maximum number of failed requests listed in JSON reports
+    #[arg(long, default_value_t = 100)]
+    max_failures: usize,
+    
+    /// Show only the N most frequent status codes and errors in reports, grouping the rest as "other"
+    #[arg(long)]
+    max_categories: Option<usize>,
+    
+    /// Retry requests failing with a transport error or 5xx response (or a --retry-status) up to this many times
+    #[arg(long, default_value_t = 0)]
+    retries: u32,
+    
+    /// Retry failed responses with this status code instead of any 5xx (can be repeated)
+    #[arg(long, requires = "retries")]
+    retry_status: Vec<u16>,
+    
+    /// Wait this long before the first retry, doubling for every further retry (e.g. "100ms")
+    #[arg(long, value_parser = parse_duration, requires = "retries")]
+    retry_backoff: Option<Duration>,
+    
+    /// Randomize each retry delay between half and all of its backoff
+    #[arg(long, requires = "retry_backoff")]
+    retry_jitter: bool,
+    
+    /// Also retry failed requests whose error message or response body contains this text (can be repeated)
+    #[arg(long, requires = "retries")]
+    retry_on: Vec<String>,
+    
+    /// Also retry failed requests whose error message or response body matches this regular expression (can be repeated)
+    #[arg(long, value_parser = parse_retry_regex, requires = "retries")]
+    retry_on_regex: Vec<RetryMatcher>,
+    
+    /// Cap the retries of the whole run at this percentage of the request count (e.g. 10)
+    #[arg(long, requires = "retries")]
+    retry_budget: Option<f64>,
+    
+    /// On Ctrl-C, time requests in flight get to complete before they are cancelled (e.g. "5s")
+    #[arg(long, value_parser = parse_duration, default_value = "5s")]
+    shutdown_grace: Duration,
+    
+    /// Add a section to text reports showing how each headline number was computed
+    #[arg(long)]
+    explain: bool,
+    
+    /// Print where the status class changed between consecutive requests, to tell
+    /// clustered from spread-out intermittent failures
+    #[arg(long)]
+    only_changed_status: bool,
+    
+    /// Compute percentiles exactly from the raw response times (uses more memory on large runs)
+    #[arg(long)]
+    exact_percentiles: bool,
+    
+    /// Estimator used for percentiles (t-digest needs no bounds, suiting very wide latency ranges)
+    #[arg(long, value_enum, default_value_t = EstimatorArg::Hdr, conflicts_with = "exact_percentiles")]
+    percentile_estimator: EstimatorArg,
+    
+    /// Lowest response time the percentile histogram tells apart (e.g. "1ms"); setting any
+    /// histogram option replaces the bounds and precision fitted to the run
+    #[arg(long, value_parser = parse_duration, conflicts_with = "exact_percentiles")]
+    histogram_min: Option<Duration>,
+    
+    /// Highest response time the percentile histogram tracks (e.g. "2m"); slower responses
+    /// are clamped to it [default with other histogram options: 1h]
+    #[arg(long, value_parser = parse_duration, conflicts_with = "exact_percentiles")]
+    histogram_max: Option<Duration>,
+    
+    /// Significant figures the percentile histogram keeps (0-5) [default with other
+    /// histogram options: 3]
+    #[arg(long, value_parser = clap::value_parser!(u8).range(0..=5), conflicts_with = "exact_percentiles")]
+    histogram_precision: Option<u8>,
+    
+    /// Size of the buffer the report file is written through (in bytes)
+    #[arg(long, default_value_t = 64 * 1024)]
+    write_buffer_size: usize,
+    
+    /// Results of an earlier run (saved with --save-results) to compare against: the
+    /// differences are printed after the run and overlaid in the HTML report
+    #[arg(long)]
+    baseline: Option<PathBuf>,
+    
+    /// Relative change in percent beyond which a metric worse than the --baseline (or the
+    /// --url run in A/B mode) counts as a regression
+    #[arg(long, default_value_t = DEFAULT_REGRESSION_THRESHOLD)]
+    regression_threshold: f64,
+    
+    /// A/B mode: run the same load test against this URL after --url, print both side by side
+    /// and report it with the --url run as the baseline
+    #[arg(long, conflicts_with_all = ["baseline", "targets", "checkpoint", "resume"])]
+    compare_url: Option<String>,
+    
+    /// Save the raw results as JSON (usable as a --baseline for later runs)
+    #[arg(long)]
+    save_results: Option<PathBuf>,
+
+    /// Write a per-second time series (started, completed, rps, avg_latency, p95, error_count) as CSV
+    #[arg(long)]
+    timeseries_csv: Option<PathBuf>,
+
+    /// Shell command run after the reports are generated, with the results in PRESSR_* environment
+    /// variables (e.g. PRESSR_SUCCESS_RATE, PRESSR_P95, PRESSR_REPORT_PATH, PRESSR_PASSED)
+    #[arg(long)]
+    post_hook: Option<String>,
+    
+    /// Apdex satisfied threshold (e.g. "500ms", "1s"); tolerating threshold is 4x this value
+    #[arg(long, value_parser = parse_duration)]
+    apdex_threshold: Option<Duration>,
+    
+    /// Latency band boundaries in ms (comma-separated, e.g. "100,300,1000") to report
+    /// the share of successful requests per band
+    #[arg(long, value_delimiter = ',')]
+    latency_bands: Vec<u64>,
+    
+    /// Fail the run (non-zero exit code) when the 95th percentile response time exceeds this (e.g. "500ms")
+    #[arg(long, value_parser = parse_duration)]
+    max_p95: Option<Duration>,
+    
+    /// Fail the run (non-zero exit code) when the throughput is below this many requests per second
+    #[arg(long)]
+    min_throughput: Option<f64>,
+    
+    /// Fail the run (non-zero exit code) when more than this percentage of requests failed (0-100)
+    #[arg(long)]
+    max_error_rate: Option<f64>,
+    
+    /// Stop the run early once more than this percentage of the completed requests failed (0-100)
+    #[arg(long)]
+    abort_on_error_rate: Option<f64>,
+    
+    /// Requests that must complete before --abort-on-error-rate is checked
+    #[arg(long, value_parser = parse_count, default_value = "20", requires = "abort_on_error_rate")]
+    abort_min_requests: usize,
+    
+    /// Save a checkpoint of the partial results to this file so the run can be resumed
+    #[arg(long)]
+    checkpoint: Option<PathBuf>,
+    
+    /// Number of requests to send between checkpoints
+    #[arg(long, value_parser = parse_count, default_value = "1000", requires = "checkpoint")]
+    checkpoint_every: usize,
+    
+    /// Resume an interrupted run from a checkpoint file, sending only the remaining requests.
+    /// The combined duration only counts time spent running, not the gap between runs.
+    #[arg(long)]
+    resume: Option<PathBuf>,
+    
+    /// URL of an authentication request sent once before the load test
+    #[arg(long)]
+    auth_request: Option<String>,
+    
+    /// HTTP method for the authentication request
+    #[arg(long, value_enum, default_value_t = HttpMethod::Post, requires = "auth_request")]
+    auth_method: HttpMethod,
+    
+    /// JSON body for the authentication request
+    #[arg(long, requires = "auth_request")]
+    auth_body: Option<String>,
+    
+    /// Extract the token from this response header of the authentication request (e.g. "Set-Cookie")
+    #[arg(long, requires = "auth_request", conflicts_with = "auth_extract_json")]
+    auth_extract_header: Option<String>,
+    
+    /// Extract the token from the authentication response body using a JSON pointer (e.g. "/data/token")
+    #[arg(long, requires = "auth_request")]
+    auth_extract_json: Option<String>,
+    
+    /// Header added to every load test request, with "{token}" replaced by the extracted value
+    /// (defaults to "Cookie: {token}" for Set-Cookie and "Authorization: Bearer {token}" otherwise)
+    #[arg(long, requires = "auth_request")]
+    auth_set_header: Option<String>,
+    
+    /// Send every request with "Authorization: Bearer <TOKEN>"
+    #[arg(long, conflicts_with_all = ["basic_auth", "auth_request"])]
+    bearer_token: Option<String>,
+    
+    /// Send every request with basic authentication, given as "username:password"
+    #[arg(long, value_parser = parse_basic_auth, conflicts_with = "auth_request")]
+    basic_auth: Option<Auth>,
+    
+    /// Send requests in bursts of this many requests at once
+    #[arg(long, value_parser = parse_count, requires = "burst_interval")]
+    burst_size: Option<usize>,
+    
+    /// Pause of each concurrent user between its requests (e.g. "500ms"), lowering the
+    /// throughput to at most concurrency / (response time + think time)
+    #[arg(long, value_parser = parse_duration, conflicts_with = "burst_size")]
+    think_time: Option<Duration>,
+    
+    /// Pick each think time at random within this much of --think-time, either way
+    #[arg(long, value_parser = parse_duration, default_value = "0s", requires = "think_time")]
+    think_time_jitter: Duration,
+    
+    /// Time between the start of consecutive bursts (e.g. "5s")
+    #[arg(long, value_parser = parse_duration, requires = "burst_size")]
+    burst_interval: Option<Duration>,
+    
+    /// Count requests of a burst that start more than this after their scheduled time as late (e.g. "10ms")
+    #[arg(long, value_parser = parse_duration, default_value = "10ms", requires = "burst_size")]
+    late_tolerance: Duration,
+    
+    /// How request indices are split across the concurrent workers ("partitioned" gives each worker a contiguous slice)
+    #[arg(long, value_enum, default_value_t = DispatchArg::Interleaved, conflicts_with = "burst_size")]
+    dispatch: DispatchArg,
+    
+    /// Status codes counted as successful in addition to 2xx (comma-separated, e.g. "301,404")
+    #[arg(long, value_delimiter = ',')]
+    expect_status: Vec<u16>,
+
+    /// Status codes counted as expected failures, neither successful nor failed (comma-separated, e.g. "404")
+    #[arg(long, value_delimiter = ',')]
+    expect_failure: Vec<u16>,
+    
+    /// Fetch the target's ETag first, then send all requests with If-None-Match and report the 304 hit rate
+    #[arg(long)]
+    etag_validation: bool,
+    
+    /// Don't read response bodies (measures time to headers; transfer rate stats become unavailable)
+    #[arg(long)]
+    ignore_body: bool,
+    
+    /// Maximum number of response body bytes read per request, counted after gzip or deflate
+    /// decoding; larger bodies are truncated
+    #[arg(long)]
+    max_body_read: Option<usize>,
+    
+    /// Only count response body bytes, never keeping bodies in memory (--retry-on then
+    /// only matches error messages)
+    #[arg(long, conflicts_with = "ignore_body")]
+    stream_body: bool,
+    
+    /// Health-probe mode: send requests one at a time (with backoff) until one succeeds,
+    /// up to --requests attempts; exits with an error if none succeeds
+    #[arg(long)]
+    until_success: bool,
+    
+    /// Give up probing after this long (e.g. "60s"; --until-success only)
+    #[arg(long, value_parser = parse_duration, default_value = "60s", requires = "until_success")]
+    probe_timeout: Duration,
+    
+    /// Idle-connection stability mode: each of the --concurrency workers holds one connection
+    /// and pings the URL over it at this interval (e.g. "30s"), up to --requests pings or for
+    /// --duration, reporting the connections dropped while idle and how long they lived
+    #[arg(long, value_parser = parse_duration, conflicts_with_all = ["until_success", "burst_size", "checkpoint", "resume", "baseline"])]
+    keepalive_interval: Option<Duration>,
+    
+    /// Seed for reproducible randomness (variable picks, generated UUIDs, body picks, retry and think
+    /// time jitter, random detail samples)
+    #[arg(long)]
+    seed: Option<u64>,
+    
+    /// How values are picked from the data file's variable sets (overrides the data file)
+    #[arg(long, value_enum)]
+    variable_strategy: Option<VariableStrategyArg>,
+    
+    /// Emit a rolling summary (windowed p50/p95, RPS, error rate, bytes/sec) as a JSON line at this interval (e.g. "5s")
+    #[arg(long, value_parser = parse_duration)]
+    summary_interval: Option<Duration>,
+    
+    /// Write the rolling summaries to this NDJSON file instead of stdout
+    #[arg(long, requires = "summary_interval")]
+    summary_out: Option<PathBuf>,
+    
+    /// Send live per-request metrics to a StatsD agent (e.g. "127.0.0.1:8125")
+    #[cfg(feature = "statsd")]
+    #[arg(long)]
+    statsd: Option<String>,
+    
+    /// Prefix for StatsD metric names
+    #[cfg(feature = "statsd")]
+    #[arg(long, default_value = "pressr", requires = "statsd")]
+    statsd_prefix: String,
+    
+    /// Export every request as a span to an OTLP/HTTP collector (e.g. "http://localhost:4318/v1/traces")
+    #[cfg(feature = "otlp")]
+    #[arg(long)]
+    otlp_endpoint: Option<String>,
+    
+    /// Service name reported with the exported spans
+    #[cfg(feature = "otlp")]
+    #[arg(long, default_value = "pressr", requires = "otlp_endpoint")]
+    otlp_service_name: String,
+    
+    /// Present this TLS server name (SNI) instead of the URL's host, still connecting to the URL's host
+    /// and sending its Host header (unless one is set with -H)
+    #[cfg(feature = "sni")]
+    #[arg(long, conflicts_with = "targets")]
+    sni: Option<String>,
 }
 
 /// Supported HTTP methods
@@ -104,6 +479,9 @@ enum OutputFormat {
     Json,
     Html,
     Svg,
+    Markdown,
+    Junit,
+    Prometheus,
     All,
 }
 
@@ -115,6 +493,9 @@ impl OutputFormat {
             OutputFormat::Json => CoreReportFormat::Json,
             OutputFormat::Html => CoreReportFormat::Html,
             OutputFormat::Svg => CoreReportFormat::Svg,
+            OutputFormat::Markdown => CoreReportFormat::Markdown,
+            OutputFormat::Junit => CoreReportFormat::Junit,
+            OutputFormat::Prometheus => CoreReportFormat::Prometheus,
             OutputFormat::All => CoreReportFormat::Html, // Default to HTML if 'All' is selected
         }
     }
@@ -127,6 +508,9 @@ impl OutputFormat {
                 "json" => Some(OutputFormat::Json),
                 "html" => Some(OutputFormat::Html),
                 "svg" => Some(OutputFormat::Svg),
+                "markdown" | "md" => Some(OutputFormat::Markdown),
+                "junit" => Some(OutputFormat::Junit),
+                "prometheus" => Some(OutputFormat::Prometheus),
                 "all" => Some(OutputFormat::All),
                 _ => None,
             })
@@ -134,6 +518,99 @@ impl OutputFormat {
     }
 }
 
+/// Selections of the requests listed in the detailed report section
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+enum DetailSelectionArg {
+    First,
+    Random,
+    Errors,
+    Slowest,
+}
+
+impl DetailSelectionArg {
+    /// Convert DetailSelectionArg to the core DetailSelection
+    fn to_core_selection(self) -> DetailSelection {
+        match self {
+            DetailSelectionArg::First => DetailSelection::First,
+            DetailSelectionArg::Random => DetailSelection::Random,
+            DetailSelectionArg::Errors => DetailSelection::Errors,
+            DetailSelectionArg::Slowest => DetailSelection::Slowest,
+        }
+    }
+}
+
+/// Streaming percentile estimators for reports
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+enum EstimatorArg {
+    Hdr,
+    Tdigest,
+}
+
+impl EstimatorArg {
+    /// Convert EstimatorArg to the core EstimatorKind
+    fn to_core_estimator(self) -> EstimatorKind {
+        match self {
+            EstimatorArg::Hdr => EstimatorKind::HdrHistogram,
+            EstimatorArg::Tdigest => EstimatorKind::TDigest,
+        }
+    }
+}
+
+/// Assignments of request indices to workers
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+enum DispatchArg {
+    Interleaved,
+    Partitioned,
+}
+
+impl DispatchArg {
+    /// Convert DispatchArg to the core DispatchMode
+    fn to_core_dispatch(self) -> DispatchMode {
+        match self {
+            DispatchArg::Interleaved => DispatchMode::Interleaved,
+            DispatchArg::Partitioned => DispatchMode::Partitioned,
+        }
+    }
+}
+
+/// HTTP versions to speak
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+enum HttpVersionArg {
+    Auto,
+    Http1,
+    Http2,
+}
+
+impl HttpVersionArg {
+    /// Convert HttpVersionArg to the core HttpVersion
+    fn to_core_http_version(self) -> HttpVersion {
+        match self {
+            HttpVersionArg::Auto => HttpVersion::Auto,
+            HttpVersionArg::Http1 => HttpVersion::Http1,
+            HttpVersionArg::Http2 => HttpVersion::Http2PriorKnowledge,
+        }
+    }
+}
+
+/// Strategies for picking values from variable sets
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+enum VariableStrategyArg {
+    Random,
+    Sequential,
+    Once,
+}
+
+impl VariableStrategyArg {
+    /// Convert VariableStrategyArg to the core VariableStrategy
+    fn to_core_strategy(self) -> VariableStrategy {
+        match self {
+            VariableStrategyArg::Random => VariableStrategy::Random,
+            VariableStrategyArg::Sequential => VariableStrategy::Sequential,
+            VariableStrategyArg::Once => VariableStrategy::Once,
+        }
+    }
+}
+
 /// Parse headers from command line strings (format: "key:value")
 fn parse_headers(header_strings: &[String]) -> Result<HeaderMap> {
     let mut headers = HeaderMap::new();
@@ -167,6 +644,276 @@ fn parse_headers(header_strings: &[String]) -> Result<HeaderMap> {
     Ok(headers)
 }
 
+/// Parse a duration with a unit suffix (e.g. "500ms", "2s", "1m", "1h")
+fn parse_duration(s: &str) -> std::result::Result<Duration, String> {
+    let s = s.trim();
+    let (value, unit) = match s.find(|c: char| !c.is_ascii_digit() && c != '.') {
+        Some(pos) => s.split_at(pos),
+        None => return Err(format!("Missing unit in duration '{}'. Use ms, s, m or h (e.g. \"500ms\")", s)),
+    };
+    
+    let value: f64 = value.parse()
+        .map_err(|_| format!("Invalid duration '{}'", s))?;
+    
+    let millis = match unit {
+        "ms" => value,
+        "s" => value * 1000.0,
+        "m" => value * 60_000.0,
+        "h" => value * 3_600_000.0,
+        _ => return Err(format!("Unknown duration unit '{}' in '{}'. Use ms, s, m or h", unit, s)),
+    };
+    
+    Ok(Duration::from_millis(millis.round() as u64))
+}
+
+/// Parse a timeout as whole seconds, either a bare number ("30") or a duration ("30s", "2m")
+///
+/// Durations that are not a whole number of seconds are rounded up.
+fn parse_timeout_secs(s: &str) -> std::result::Result<u64, String> {
+    let s = s.trim();
+    if let Ok(secs) = s.parse::<u64>() {
+        return Ok(secs);
+    }
+    
+    let duration = parse_duration(s)?;
+    let secs = duration.as_secs() + u64::from(duration.subsec_nanos() > 0);
+    Ok(secs)
+}
+
+/// Parse basic authentication credentials in the format "username:password"
+fn parse_basic_auth(s: &str) -> std::result::Result<Auth, String> {
+    let (username, password) = s.split_once(':')
+        .ok_or_else(|| "expected \"username:password\"".to_string())?;
+    Ok(Auth::Basic {
+        username: username.to_string(),
+        password: password.to_string(),
+    })
+}
+
+/// Parse a target as "[METHOD] URL [weight=N]", e.g. "POST https://example.com/orders weight=3"
+fn parse_target(s: &str) -> std::result::Result<Target, String> {
+    let mut parts = s.split_whitespace().peekable();
+    let method = match parts.peek() {
+        Some(part) if !part.contains("://") && !part.starts_with("weight=") => {
+            let method = Method::from_str(&part.to_uppercase())
+                .map_err(|_| format!("Invalid HTTP method '{}'", part))?;
+            parts.next();
+            Some(method)
+        },
+        _ => None,
+    };
+    let url = parts.next()
+        .filter(|url| !url.starts_with("weight="))
+        .ok_or_else(|| "expected \"[METHOD] URL [weight=N]\"".to_string())?;
+    
+    let mut target = Target::new(url);
+    target.method = method;
+    if let Some(part) = parts.next() {
+        target.weight = part.strip_prefix("weight=")
+            .and_then(|weight| weight.parse().ok())
+            .ok_or_else(|| format!("Invalid weight '{}'. Expected weight=N with a whole number N", part))?;
+    }
+    if let Some(part) = parts.next() {
+        return Err(format!("Unexpected '{}' after the target", part));
+    }
+    Ok(target)
+}
+
+/// Parse a regular expression marking failed requests as retryable
+fn parse_retry_regex(s: &str) -> std::result::Result<RetryMatcher, String> {
+    RetryMatcher::regex(s).map_err(|e| e.to_string())
+}
+
+/// Parse a count with an optional k (thousand), M (million) or G (billion) suffix (e.g. "500k", "2.5k", "1M")
+fn parse_count(s: &str) -> std::result::Result<usize, String> {
+    let s = s.trim().replace('_', "");
+    let (value, multiplier) = match s.chars().last() {
+        Some('k') | Some('K') => (&s[..s.len() - 1], 1_000.0),
+        Some('M') => (&s[..s.len() - 1], 1_000_000.0),
+        Some('G') => (&s[..s.len() - 1], 1_000_000_000.0),
+        Some(c) if c.is_ascii_digit() => (s.as_str(), 1.0),
+        Some(c) => return Err(format!("Unknown suffix '{}' in count '{}'. Use k, M or G (e.g. \"500k\")", c, s)),
+        None => return Err("Count must not be empty".to_string()),
+    };
+    
+    // Plain integers are parsed exactly; fractional values need a suffix
+    if multiplier == 1.0 {
+        return value.parse()
+            .map_err(|_| format!("Invalid count '{}'. Expected a whole number, optionally with a k, M or G suffix", s));
+    }
+    
+    if !value.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return Err(format!("Invalid count '{}'. Expected a number followed by k, M or G", s));
+    }
+    let value: f64 = value.parse()
+        .map_err(|_| format!("Invalid count '{}'. Expected a number followed by k, M or G", s))?;
+    let count = value * multiplier;
+    if !count.is_finite() || count < 0.0 || count.fract() != 0.0 {
+        return Err(format!("Count '{}' must be a non-negative whole number", s));
+    }
+    if count > usize::MAX as f64 {
+        return Err(format!("Count '{}' is too large", s));
+    }
+    
+    Ok(count as usize)
+}
+
+/// Percentile histogram settings from the histogram options, or `None` to fit them to the run
+fn histogram_settings(args: &Args) -> std::result::Result<Option<HistogramSettings>, AppError> {
+    if args.histogram_min.is_none() && args.histogram_max.is_none() && args.histogram_precision.is_none() {
+        return Ok(None);
+    }
+    if args.percentile_estimator != EstimatorArg::Hdr {
+        return Err(AppError::Generic("The histogram options need --percentile-estimator hdr".to_string()));
+    }
+    
+    let defaults = HistogramSettings::default();
+    let settings = HistogramSettings {
+        lowest: args.histogram_min.map_or(defaults.lowest, |min| min.as_millis() as u64),
+        highest: args.histogram_max.map_or(defaults.highest, |max| max.as_millis() as u64),
+        significant_figures: args.histogram_precision.unwrap_or(defaults.significant_figures),
+    };
+    if settings.lowest == 0 {
+        return Err(AppError::Generic("--histogram-min must be at least 1ms".to_string()));
+    }
+    if settings.highest < settings.lowest * 2 {
+        return Err(AppError::Generic(format!("--histogram-max must be at least twice the histogram minimum ({} ms)",
+                                             settings.lowest)));
+    }
+    Ok(Some(settings))
+}
+
+/// Warning for fixed-count runs too small for reliable statistics
+fn small_run_warning(requests: usize, duration: Option<Duration>) -> Option<String> {
+    (duration.is_none() && requests < MIN_RELIABLE_REQUESTS).then(|| format!(
+        "Warning: only {} request{}: percentiles, histograms and the standard deviation are \
+         not reliable below {} requests (use --quiet to hide this warning)",
+        requests, if requests == 1 { "" } else { "s" }, MIN_RELIABLE_REQUESTS
+    ))
+}
+
+/// Describe the amount of load of a run (e.g. "100 requests", "60s", "60s (up to 500 requests)")
+fn describe_workload(requests: usize, duration: Option<Duration>) -> String {
+    match (duration, requests) {
+        (None, requests) => format!("{} requests", requests),
+        (Some(duration), usize::MAX) => format!("{:?}", duration),
+        (Some(duration), requests) => format!("{:?} (up to {} requests)", duration, requests),
+    }
+}
+
+/// Print the offending line of a data file with a marker under the given column
+fn print_error_snippet(path: &std::path::Path, line: usize, column: usize) {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return;
+    };
+    let Some(text) = content.lines().nth(line.saturating_sub(1)) else {
+        return;
+    };
+    
+    let gutter = line.to_string().len();
+    eprintln!("{:>width$}--> {}:{}:{}", "", path.display(), line, column, width = gutter);
+    eprintln!("{:>width$} |", "", width = gutter);
+    eprintln!("{} | {}", line, text);
+    eprintln!("{:>width$} | {:>column$}", "", "^", width = gutter, column = column.max(1));
+}
+
+/// Print the runs of consecutive requests with the same status class
+fn print_status_changes(results: &LoadTestResults) {
+    let Some(intermittency) = &results.intermittency else {
+        println!("\nNo status changes: every request had the same status class");
+        return;
+    };
+    
+    println!("\nStatus changes ({} runs, failures {}):", intermittency.run_count,
+             if intermittency.clustered { "clustered" } else { "spread out" });
+    for run in &intermittency.runs {
+        println!("  #{}-#{} {} ({} {}{})", run.first + 1, run.last + 1, run.class, run.requests,
+                 if run.requests == 1 { "request" } else { "requests" },
+                 if run.failed { ", failed" } else { "" });
+    }
+    if intermittency.run_count > intermittency.runs.len() {
+        println!("  ... and {} more runs", intermittency.run_count - intermittency.runs.len());
+    }
+}
+
+/// Send the authentication request and build the header carrying the extracted token
+async fn resolve_auth_header(client: &reqwest::Client, args: &Args, auth_url: &str) -> std::result::Result<(HeaderName, HeaderValue), AppError> {
+    info!("Sending authentication request to {}", auth_url);
+    
+    let mut builder = client.request(args.auth_method.to_reqwest_method(), auth_url);
+    if let Some(body) = &args.auth_body {
+        let body: serde_json::Value = serde_json::from_str(body)?;
+        builder = builder.json(&body);
+    }
+    
+    let response = builder.send().await?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(AppError::Generic(format!("Authentication request failed with status {}", status)));
+    }
+    
+    // Extract the token from a response header or the JSON body
+    let (token, is_cookie) = if let Some(header) = &args.auth_extract_header {
+        let value = response.headers()
+            .get(header.as_str())
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| AppError::Generic(format!("Authentication response has no '{}' header", header)))?;
+        
+        let is_cookie = header.eq_ignore_ascii_case("set-cookie");
+        let token = if is_cookie {
+            // Only keep the "name=value" pair, not the cookie attributes
+            value.split(';').next().unwrap_or(value).trim().to_string()
+        } else {
+            value.to_string()
+        };
+        (token, is_cookie)
+    } else if let Some(pointer) = &args.auth_extract_json {
+        let body: serde_json::Value = response.json().await?;
+        let token = match body.pointer(pointer) {
+            Some(serde_json::Value::String(s)) => s.clone(),
+            Some(serde_json::Value::Null) | None => {
+                return Err(AppError::Generic(format!("Authentication response has no value at '{}'", pointer)));
+            },
+            Some(other) => other.to_string(),
+        };
+        (token, false)
+    } else {
+        return Err(AppError::Generic("--auth-request requires --auth-extract-header or --auth-extract-json".to_string()));
+    };
+    
+    let template = args.auth_set_header.clone().unwrap_or_else(|| {
+        if is_cookie {
+            "Cookie: {token}".to_string()
+        } else {
+            "Authorization: Bearer {token}".to_string()
+        }
+    });
+    
+    let (name, value) = template.split_once(':')
+        .ok_or_else(|| AppError::Generic(format!("Invalid auth header format: {}. Expected 'key:value'", template)))?;
+    let value = value.trim().replace("{token}", &token);
+    
+    let name = HeaderName::from_str(name.trim())
+        .map_err(|_| AppError::Generic(format!("Invalid auth header name: {}", name)))?;
+    let value = HeaderValue::from_str(&value)
+        .map_err(|_| AppError::Generic("Extracted auth token is not a valid header value".to_string()))?;
+    
+    debug!("Resolved auth header: {}", name);
+    Ok((name, value))
+}
+
+/// Run the second load test of an A/B comparison: the same test as `config` against `compare_url`
+async fn run_comparison(client: &reqwest::Client, config: &Config, request_data: Option<RequestData>, compare_url: &str, stop: StopHandle) -> Result<LoadTestResults> {
+    let compare_config = Config {
+        url: compare_url.to_string(),
+        ..config.clone()
+    };
+    Runner::new(client.clone(), compare_config, request_data)?
+        .with_stop(stop)
+        .run()
+        .await
+}
+
 /// Initialize the logger
 fn init_logger(verbose: bool) {
     let filter = if verbose {
@@ -189,32 +936,85 @@ fn init_logger(verbose: bool) {
 
 #[tokio::main]
 async fn main() -> std::result::Result<(), AppError> {
-    let args = Args::parse();
+    let args: Args = config_file::parse_args(std::env::args_os().collect())?;
     
     // Initialize the logger based on verbosity
     init_logger(args.verbose);
     
+    // Timed runs are only limited by --requests when it is given
+    let requests = args.requests.unwrap_or(if args.duration.is_some() { usize::MAX } else { 100 });
+    let workload = describe_workload(requests, args.duration);
+    let histogram = histogram_settings(&args)?;
+    
     info!("Starting pressr with URL: {}, Method: {:?}", args.url, args.method);
-    debug!("Configuration: {} requests, {} concurrent, timeout: {}s", 
-           args.requests, args.concurrency, args.timeout);
+    debug!("Configuration: {}, {} concurrent, timeout: {}s", 
+           workload, args.concurrency, args.timeout);
     
     println!("Starting pressr with the following configuration:");
     println!("URL: {}", args.url);
     println!("Method: {:?}", args.method);
-    println!("Requests: {}", args.requests);
+    println!("Requests: {}", workload);
     println!("Concurrency: {}", args.concurrency);
     
+    // Probes stop at the first success, so their statistics don't matter
+    if let Some(warning) = small_run_warning(requests, args.duration).filter(|_| !args.quiet && !args.until_success) {
+        eprintln!("{}", warning);
+    }
+    
+    // Load the baseline results to compare against if specified
+    let baseline = match &args.baseline {
+        Some(path) => {
+            let baseline = LoadTestResults::load_json(path).await.map_err(|e| {
+                error!("Failed to load baseline: {}", e);
+                AppError::Core(e)
+            })?;
+            println!("Baseline: {} ({} requests)", path.display(), baseline.total_requests);
+            Some(baseline)
+        },
+        None => None,
+    };
+    
+    // Load the checkpoint to resume from if specified
+    let checkpoint = match &args.resume {
+        Some(path) => {
+            let checkpoint = Checkpoint::load(path).await.map_err(|e| {
+                error!("Failed to load checkpoint: {}", e);
+                AppError::Core(e)
+            })?;
+            println!("Resuming from checkpoint: {} ({} completed, {} remaining)",
+                     path.display(), checkpoint.results.total_requests, checkpoint.remaining_requests);
+            Some(checkpoint)
+        },
+        None => None,
+    };
+    
     // Load data file if specified
     let request_data = match &args.data_file {
         Some(path) => {
             println!("Data file: {}", path.display());
-            match RequestData::from_json_file(path).await {
-                Ok(data) => {
+            match RequestData::from_file(path).await {
+                Ok(mut data) => {
                     println!("Successfully loaded data file");
                     
+                    if let Some(strategy) = args.variable_strategy {
+                        data.variable_strategy = strategy.to_core_strategy();
+                    }
+                    
                     // Print a summary of what was loaded
                     if data.body.is_some() {
-                        println!("  Request body defined in data file");
+                        println!("  Request body defined in data file ({:?})", data.body_type);
+                    }
+                    if let Some(body_file) = &data.body_file {
+                        println!("  Request body read from {}", body_file.display());
+                    }
+                    if !data.files.is_empty() {
+                        println!("  {} file(s) sent as multipart parts", data.files.len());
+                    }
+                    if !data.body_pool.is_empty() {
+                        println!("  {} weighted bodies in the body pool", data.body_pool.len());
+                    }
+                    if !data.scenario.is_empty() {
+                        println!("  Scenario of {} step(s), each iteration counts as one request", data.scenario.len());
                     }
                     
                     if !data.headers.is_empty() {
@@ -233,11 +1033,18 @@ async fn main() -> std::result::Result<(), AppError> {
                         println!("  {} variable set(s) defined for randomization", data.variables.len());
                     }
                     
+                    if !data.rows.is_empty() {
+                        println!("  {} CSV row(s) available as template tokens", data.rows.len());
+                    }
+                    
                     Some(data)
                 },
                 Err(err) => {
                     error!("Failed to load data file: {}", err);
                     eprintln!("Error loading data file: {}", err);
+                    if let Some((path, line, column)) = err.data_location() {
+                        print_error_snippet(path, line, column);
+                    }
                     None
                 }
             }
@@ -253,6 +1060,27 @@ async fn main() -> std::result::Result<(), AppError> {
     }
     
     println!("Timeout: {} seconds", args.timeout);
+    
+    if let (Some(size), Some(interval)) = (args.burst_size, args.burst_interval) {
+        println!("Burst mode: {} requests every {} ms", size, interval.as_millis());
+    }
+    
+    if let Some(ramp_up) = args.ramp_up {
+        println!("Ramp-up: {} ms", ramp_up.as_millis());
+    }
+    
+    if let Some(threshold) = args.apdex_threshold {
+        println!("Apdex threshold: {} ms", threshold.as_millis());
+    }
+    
+    let thresholds = Thresholds {
+        max_p95_ms: args.max_p95.map(|p95| p95.as_secs_f64() * 1000.0),
+        min_throughput: args.min_throughput,
+        max_error_rate: args.max_error_rate,
+    };
+    if !thresholds.is_empty() {
+        println!("Thresholds: {}", serde_json::to_string(&thresholds)?);
+    }
     println!("Output format: {:?}", args.output);
     
     if args.no_histograms {
@@ -267,47 +1095,95 @@ async fn main() -> std::result::Result<(), AppError> {
         println!("Output file: {}", file);
     }
     
+    #[cfg(feature = "sni")]
+    if let Some(sni) = &args.sni {
+        println!("SNI: {}", sni);
+    }
+    
+    // Connection settings shared by the client and every run
+    let client_config = Config {
+        url: args.url.clone(),
+        timeout: args.timeout,
+        idle_timeout: args.idle_timeout,
+        #[cfg(feature = "sni")]
+        sni: args.sni.clone(),
+        cookie_store: args.cookie_store,
+        proxy: args.proxy.clone(),
+        follow_redirects: !args.no_follow_redirects,
+        max_redirects: args.max_redirects,
+        http_version: args.http_version.to_core_http_version(),
+        user_agent: args.user_agent.clone(),
+        ..Default::default()
+    };
+    
     // Create a client with the specified timeout
-    debug!("Creating HTTP client with timeout: {}s", args.timeout);
-    let client = Runner::create_client(args.timeout)
+    let client = Runner::create_client(&client_config)
         .map_err(|e| {
             error!("Failed to create HTTP client: {}", e);
             AppError::Core(e)
         })?;
     
-    // Parse command-line headers
+    // Parse command-line headers (the runner merges them over the data file headers)
     debug!("Parsing command-line headers");
     let mut headers = parse_headers(&args.headers).map_err(AppError::Core)?;
     
-    // Add headers from data file if available
-    if let Some(data) = &request_data {
-        debug!("Adding headers from data file");
-        for (key, value) in &data.headers {
-            match (
-                HeaderName::from_str(key),
-                HeaderValue::from_str(value)
-            ) {
-                (Ok(key), Ok(value)) => {
-                    debug!("Added header from data file: {}: {}", key, value.to_str().unwrap_or("<binary>"));
-                    headers.insert(key, value);
-                },
-                _ => {
-                    warn!("Invalid header in data file: {}: {}", key, value);
-                }
-            }
-        }
+    // Log in once and reuse the extracted token for all load test requests
+    if let Some(auth_url) = &args.auth_request {
+        let (name, value) = resolve_auth_header(&client, &args, auth_url).await
+            .map_err(|e| {
+                error!("Authentication request failed: {}", e);
+                e
+            })?;
+        println!("Authenticated via {} (adding '{}' header)", auth_url, name);
+        headers.insert(name, value);
+    }
+    
+    // In probe mode, stop at the first success instead of running a load test
+    if args.until_success {
+        println!("\nProbing {} until it succeeds (up to {} attempts)...", args.url, requests);
+        let config = Config {
+            method: args.method.to_reqwest_method(),
+            headers: headers.clone(),
+            request_count: requests,
+            expected_statuses: args.expect_status.clone(),
+            ..client_config.clone()
+        };
+        let runner = Runner::new(client.clone(), config, request_data.clone()).map_err(AppError::Core)?;
+        let results = runner.run_until_success(args.probe_timeout).await.map_err(AppError::Core)?;
+        
+        return if results.successful_requests > 0 {
+            println!("Target is up: succeeded after {} attempt(s) in {:.2} seconds",
+                     results.total_requests, results.duration_secs);
+            Ok(())
+        } else {
+            Err(AppError::Generic(format!("No successful response after {} attempt(s) in {:.2} seconds",
+                                          results.total_requests, results.duration_secs)))
+        };
     }
     
     // Send a single request as a test first
     println!("\nSending a test request to {}", args.url);
     info!("Sending test request to {}", args.url);
     
+    let test_url = match &request_data {
+        Some(data) => data.apply_path_variables(&args.url).map_err(AppError::Core)?,
+        None => args.url.clone(),
+    };
+    let auth = args.bearer_token.clone().map(Auth::Bearer).or_else(|| args.basic_auth.clone());
+    let test_headers = match &request_data {
+        Some(data) => data.merge_headers(&headers),
+        None => headers.clone(),
+    };
     let mut test_request_builder = client
-        .request(args.method.to_reqwest_method(), &args.url)
-        .headers(headers.clone());
+        .request(args.method.to_reqwest_method(), &test_url)
+        .headers(test_headers);
+    if let Some(auth) = &auth {
+        test_request_builder = auth.apply(test_request_builder);
+    }
     
-    // Add body from data file if available and method is appropriate
+    // Add query parameters and body from data file if available (body only if method is appropriate)
     if let Some(data) = &request_data {
+        test_request_builder = test_request_builder.query(&data.params);
         if matches!(args.method, HttpMethod::Post | HttpMethod::Put | HttpMethod::Patch) {
             if let Some(body) = &data.body {
                 debug!("Adding JSON body to test request");
@@ -346,46 +1222,278 @@ async fn main() -> std::result::Result<(), AppError> {
             }
             
             // Now proceed with the actual load test
-            println!("\nStarting load test with {} requests ({} concurrent)...", args.requests, args.concurrency);
+            println!("\nStarting load test with {} ({} concurrent)...", workload, args.concurrency);
+            
+            // Spread the requests across --url and the additional targets
+            let targets = if args.targets.is_empty() {
+                Vec::new()
+            } else {
+                let url = Target {
+                    weight: args.url_weight,
+                    ..Target::new(args.url.clone())
+                };
+                std::iter::once(url).chain(args.targets.iter().cloned()).collect()
+            };
+            
+            // One run ID for every batch and artifact, kept across resumes
+            let run_id = checkpoint.as_ref()
+                .map(|c| c.results.run_id.clone())
+                .filter(|id| !id.is_empty())
+                .unwrap_or_else(generate_run_id);
+            println!("Run ID: {}", run_id);
             
             // Create the runner config
             let config = Config {
-                url: args.url,
                 method: args.method.to_reqwest_method(),
                 headers,
-                request_count: args.requests,
+                request_count: requests,
                 concurrency: args.concurrency,
-                timeout: args.timeout,
+                apdex_threshold: args.apdex_threshold,
+                latency_bands: args.latency_bands.clone(),
+                thresholds: Some(thresholds).filter(|thresholds| !thresholds.is_empty()),
+                percentiles: PercentileMethod {
+                    estimator: args.percentile_estimator.to_core_estimator(),
+                    histogram,
+                    exact: args.exact_percentiles,
+                },
+                burst: match (args.burst_size, args.burst_interval) {
+                    (Some(burst_size), Some(burst_interval)) => Some(BurstProfile {
+                        burst_size,
+                        burst_interval,
+                        late_tolerance: args.late_tolerance,
+                    }),
+                    _ => None,
+                },
+                expected_statuses: args.expect_status.clone(),
+                expected_failures: args.expect_failure.clone(),
+                etag_validation: args.etag_validation,
+                read_body: !args.ignore_body,
+                seed: args.seed,
+                first_index: 0,
+                max_body_read: args.max_body_read,
+                stream_body: args.stream_body,
+                record_headers: args.detailed,
+                targets,
+                run_id: Some(run_id.clone()),
+                retries: args.retries,
+                retry_statuses: args.retry_status.clone(),
+                retry_on: args.retry_on.iter()
+                    .cloned()
+                    .map(RetryMatcher::Substring)
+                    .chain(args.retry_on_regex.iter().cloned())
+                    .collect(),
+                retry_backoff: args.retry_backoff.unwrap_or_default(),
+                retry_jitter: args.retry_jitter,
+                retry_budget: args.retry_budget,
+                think_time: args.think_time,
+                think_time_jitter: args.think_time_jitter,
+                abort_on_error_rate: args.abort_on_error_rate,
+                abort_min_requests: args.abort_min_requests,
+                shutdown_grace: args.shutdown_grace,
+                duration: args.duration,
+                ramp_up: args.ramp_up,
+                auth: auth.clone(),
+                dispatch: args.dispatch.to_core_dispatch(),
+                ..client_config.clone()
             };
             
-            // Create and run the load test
-            let runner = Runner::new(client, config, request_data);
+            // When resuming, only the remaining requests are sent
+            let configured_requests = checkpoint.as_ref()
+                .map(|c| c.configured_requests)
+                .unwrap_or(requests);
+            let mut remaining = checkpoint.as_ref()
+                .map(|c| c.remaining_requests)
+                .unwrap_or(requests);
+            let mut results = checkpoint.map(|c| c.results);
+            
+            // Split the run into batches when checkpointing so partial results are saved regularly
+            let batch_size = if args.checkpoint.is_some() {
+                args.checkpoint_every.max(1)
+            } else {
+                remaining.max(1)
+            };
+            
+            // Live exporters shared by all batches
+            let mut exporters = FanoutExporter::new();
+            #[cfg(feature = "statsd")]
+            if let Some(address) = &args.statsd {
+                exporters.push(Arc::new(statsd::StatsdExporter::new(address, &args.statsd_prefix)?));
+            }
+            #[cfg(feature = "otlp")]
+            let otlp_stream = match &args.otlp_endpoint {
+                Some(endpoint) => {
+                    let (exporter, spans) = otlp::OtlpExporter::new(config.method.as_str(), &config.url);
+                    exporters.push(Arc::new(exporter));
+                    Some(otlp::OtlpStream::spawn(endpoint.clone(), &args.otlp_service_name, &run_id, spans)?)
+                },
+                None => None,
+            };
+            let summary_stream = match args.summary_interval {
+                Some(interval) => {
+                    let output = SummaryOutput::open(args.summary_out.as_deref())?;
+                    let exporter = Arc::new(SummaryExporter::new(run_id.clone()));
+                    exporters.push(exporter.clone());
+                    Some(SummaryStream::spawn(exporter, interval, output))
+                },
+                None => None,
+            };
+            // Only drawn on a terminal, and not between summary lines printed to stdout
+            let show_progress = !args.quiet && io::stderr().is_terminal()
+                && !(args.summary_interval.is_some() && args.summary_out.is_none());
+            let progress_bar = if show_progress {
+                let exporter = Arc::new(progress::ProgressExporter::default());
+                exporters.push(exporter.clone());
+                let total = (remaining != usize::MAX).then_some(remaining);
+                Some(progress::ProgressBar::spawn(exporter, total, args.duration))
+            } else {
+                None
+            };
+            
+            // Stop dispatching on Ctrl-C, letting requests in flight finish; a second Ctrl-C exits immediately
+            let stop = StopHandle::new();
+            {
+                let stop = stop.clone();
+                let grace = args.shutdown_grace;
+                tokio::spawn(async move {
+                    if tokio::signal::ctrl_c().await.is_ok() {
+                        println!("\nStopping: no new requests are sent, requests in flight get {:?} to finish (Ctrl-C again to exit)", grace);
+                        stop.stop();
+                    }
+                    if tokio::signal::ctrl_c().await.is_ok() {
+                        std::process::exit(130);
+                    }
+                });
+            }
             
             let test_start = std::time::Instant::now();
-            let results = runner.run().await.map_err(AppError::Core)?;
+            while remaining > 0 {
+                let batch = remaining.min(batch_size);
+                // Continue the request numbering so templates and seeded randomness match an unbatched run
+                let batch_config = Config {
+                    request_count: batch,
+                    first_index: configured_requests - remaining,
+                    ..config.clone()
+                };
+                
+                // Create and run the load test
+                let mut runner = Runner::new(client.clone(), batch_config, request_data.clone())
+                    .map_err(AppError::Core)?
+                    .with_stop(stop.clone());
+                if !exporters.is_empty() {
+                    runner = runner.with_exporter(Arc::new(exporters.clone()));
+                }
+                let exhausted = runner.request_count() < batch;
+                let batch_results = match args.keepalive_interval {
+                    Some(interval) => runner.run_keepalive(interval).await,
+                    None => runner.run().await,
+                }.map_err(AppError::Core)?;
+                
+                results = Some(match results {
+                    Some(previous) => previous.merge(&batch_results),
+                    None => batch_results,
+                });
+                remaining -= batch;
+                
+                if let (Some(path), Some(results)) = (&args.checkpoint, &results) {
+                    let checkpoint = Checkpoint::new(configured_requests, results.clone());
+                    checkpoint.save(path).await.map_err(AppError::Core)?;
+                    info!("Checkpoint saved to {} ({} requests remaining)", path.display(), checkpoint.remaining_requests);
+                }
+                
+                // With the `once` variable strategy the run ends when a variable set runs out
+                if exhausted || stop.is_stopped() {
+                    break;
+                }
+            }
             let test_duration = test_start.elapsed();
             
+            if let Some(progress_bar) = progress_bar {
+                progress_bar.finish().await;
+            }
+            if let Some(summary_stream) = summary_stream {
+                summary_stream.finish().await;
+            }
+            #[cfg(feature = "otlp")]
+            if let Some(otlp_stream) = otlp_stream {
+                otlp_stream.finish().await;
+            }
+            
+            let results = results.unwrap_or_else(|| {
+                let mut results = LoadTestResults::new(Vec::new(), Duration::ZERO);
+                results.run_id = run_id.clone();
+                results
+            });
+            
             println!("\nLoad test completed in {:.2} seconds", test_duration.as_secs_f64());
+            if let Some(reason) = &results.aborted {
+                println!("Aborted early: {}", reason);
+            }
+            println!("{}", results.summary());
+            if let Some(baseline) = &baseline {
+                println!("\nCompared to the baseline:");
+                println!("{}", compare_results(baseline, &results, args.regression_threshold));
+            }
+            if args.only_changed_status {
+                print_status_changes(&results);
+            }
             info!("Load test completed in {:.2} seconds", test_duration.as_secs_f64());
             
+            if let Some(path) = &args.save_results {
+                results.save_json(path).await.map_err(AppError::Core)?;
+                println!("Results saved to {}", path.display());
+            }
+            
+            if let Some(path) = &args.timeseries_csv {
+                results.save_timeseries_csv(path).await.map_err(AppError::Core)?;
+                println!("Time series saved to {}", path.display());
+            }
+            
+            // In A/B mode, run the identical test against the second URL and report it
+            // against the first run
+            let (results, baseline) = match &args.compare_url {
+                Some(compare_url) if !stop.is_stopped() => {
+                    println!("\nStarting comparison run against {} ({}, {} concurrent)...",
+                             compare_url, workload, args.concurrency);
+                    let b_results = run_comparison(&client, &config, request_data.clone(), compare_url, stop.clone())
+                        .await
+                        .map_err(AppError::Core)?;
+                    
+                    println!("\nA: {}", config.url);
+                    println!("B: {}", compare_url);
+                    println!("{}", compare_results(&results, &b_results, args.regression_threshold).table("A", "B"));
+                    (b_results, Some(results))
+                },
+                _ => (results, baseline),
+            };
+            
             // Create the report options
             let report_options = ReportOptions {
                 format: args.output.to_core_report_format(),
                 output_file: args.output_file.clone(),
                 include_histograms: !args.no_histograms,
                 include_details: args.detailed,
+                detail_limit: args.detail_limit,
+                detail_selection: args.detail_selection.to_core_selection(),
+                seed: args.seed,
                 output_dir: args.output_dir.clone(),
+                inline_assets: args.inline_assets,
+                max_failures: args.max_failures,
+                max_categories: args.max_categories,
+                baseline: baseline.clone(),
+                regression_threshold: args.regression_threshold,
+                explain: args.explain,
+                write_buffer_size: args.write_buffer_size,
             };
             
             // Generate the report
             info!("Generating report with format: {:?}", args.output);
-            let report = pressr_core::generate_report(&results, &report_options)
+            let GeneratedReport { summary: report, path: report_path } = pressr_core::write_report(&results, &report_options)
                 .map_err(AppError::Core)?;
             
             // Only print the report to stdout if no output file was specified AND the format is not HTML or SVG
             if args.output_file.is_none() {
                 match args.output {
-                    OutputFormat::Text | OutputFormat::Json => {
+                    OutputFormat::Text | OutputFormat::Json | OutputFormat::Markdown | OutputFormat::Junit | OutputFormat::Prometheus => {
                         println!("\n{}", report);
                     }
                     OutputFormat::Html | OutputFormat::Svg => {
@@ -410,6 +1518,12 @@ async fn main() -> std::result::Result<(), AppError> {
             // The report has been saved to a file (path is logged by the core library)
             println!("\nReport generated successfully.");
             
+            // Files packed into the bundle
+            let mut bundle_files = vec![PathBuf::from(&report_path)];
+            if let Some(path) = &args.timeseries_csv {
+                bundle_files.push(path.clone());
+            }
+            
             // Generate additional report formats if specified
             if let Some(formats_str) = &args.report_formats {
                 let formats = OutputFormat::from_comma_separated(formats_str);
@@ -428,9 +1542,12 @@ async fn main() -> std::result::Result<(), AppError> {
                             OutputFormat::Json => "JSON",
                             OutputFormat::Html => "HTML",
                             OutputFormat::Svg => "SVG",
+                            OutputFormat::Markdown => "Markdown",
+                            OutputFormat::Junit => "JUnit",
+                            OutputFormat::Prometheus => "Prometheus",
                             OutputFormat::All => {
                                 // Generate all formats except the primary one
-                                for f in [OutputFormat::Text, OutputFormat::Json, OutputFormat::Html, OutputFormat::Svg] {
+                                for f in [OutputFormat::Text, OutputFormat::Json, OutputFormat::Html, OutputFormat::Svg, OutputFormat::Markdown, OutputFormat::Junit, OutputFormat::Prometheus] {
                                     if f != args.output {
                                         // Generate this format
                                         let format_options = ReportOptions {
@@ -438,12 +1555,23 @@ async fn main() -> std::result::Result<(), AppError> {
                                             output_file: None, // Auto-generate filename
                                             include_histograms: !args.no_histograms,
                                             include_details: args.detailed,
+                                            detail_limit: args.detail_limit,
+                                            detail_selection: args.detail_selection.to_core_selection(),
+                                            seed: args.seed,
                                             output_dir: args.output_dir.clone(),
+                                            inline_assets: args.inline_assets,
+                                            max_failures: args.max_failures,
+                                            max_categories: args.max_categories,
+                                            baseline: baseline.clone(),
+                regression_threshold: args.regression_threshold,
+                                            explain: args.explain,
+                                            write_buffer_size: args.write_buffer_size,
                                         };
                                         
-                                        match pressr_core::generate_report(&results, &format_options) {
-                                            Ok(_) => {
+                                        match pressr_core::write_report(&results, &format_options) {
+                                            Ok(generated) => {
                                                 info!("Successfully generated {:?} report", f);
+                                                bundle_files.push(PathBuf::from(generated.path));
                                             },
                                             Err(e) => {
                                                 warn!("Failed to generate {:?} report: {}", f, e);
@@ -466,6 +1594,9 @@ async fn main() -> std::result::Result<(), AppError> {
                                 OutputFormat::Json => "json",
                                 OutputFormat::Html => "html",
                                 OutputFormat::Svg => "svg",
+                                OutputFormat::Markdown => "md",
+                                OutputFormat::Junit => "xml",
+                                OutputFormat::Prometheus => "prom",
                                 OutputFormat::All => unreachable!(),
                             };
                             Some(format!("{}.{}", stem.to_string_lossy(), extension))
@@ -479,12 +1610,23 @@ async fn main() -> std::result::Result<(), AppError> {
                             output_file: filename,
                             include_histograms: !args.no_histograms,
                             include_details: args.detailed,
+                            detail_limit: args.detail_limit,
+                            detail_selection: args.detail_selection.to_core_selection(),
+                            seed: args.seed,
                             output_dir: args.output_dir.clone(),
+                            inline_assets: args.inline_assets,
+                            max_failures: args.max_failures,
+                            max_categories: args.max_categories,
+                            baseline: baseline.clone(),
+                regression_threshold: args.regression_threshold,
+                            explain: args.explain,
+                            write_buffer_size: args.write_buffer_size,
                         };
                         
-                        match pressr_core::generate_report(&results, &format_options) {
-                            Ok(_) => {
+                        match pressr_core::write_report(&results, &format_options) {
+                            Ok(generated) => {
                                 println!("Successfully generated {} report", format_name);
+                                bundle_files.push(PathBuf::from(generated.path));
                             },
                             Err(e) => {
                                 warn!("Failed to generate {} report: {}", format_name, e);
@@ -494,6 +1636,31 @@ async fn main() -> std::result::Result<(), AppError> {
                     }
                 }
             }
+            
+            if args.bundle {
+                let report_dir = std::path::Path::new(&report_path).parent()
+                    .unwrap_or_else(|| std::path::Path::new("."));
+                let bundle_path = bundle::write_bundle(report_dir, &results.run_id, &bundle_files)?;
+                println!("\nReport bundle written to {}", bundle_path.display());
+            }
+            
+            if let Some(command) = &args.post_hook {
+                println!("\nRunning post-run hook...");
+                match hook::run_post_hook(command, hook::hook_env(&results, &report_path)).await {
+                    Some(status) if status.success() => println!("Post-run hook completed successfully"),
+                    Some(status) => eprintln!("Warning: Post-run hook exited with {}", status),
+                    None => eprintln!("Warning: Post-run hook could not be started"),
+                }
+            }
+            
+            // Fail after the reports are written so they are still available
+            if !results.passed {
+                eprintln!("\nThresholds failed:");
+                for violation in &results.threshold_violations {
+                    eprintln!("  {}", violation);
+                }
+                return Err(AppError::ThresholdsFailed(results.threshold_violations.len()));
+            }
         },
         Err(e) => {
             error!("Test request failed: {}", e);
@@ -505,3 +1672,109 @@ async fn main() -> std::result::Result<(), AppError> {
     
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::Mutex;
+    
+    /// Answer every request on a connection of its own with the body `respond` builds from
+    /// the request head, recording the heads
+    fn serve(respond: impl Fn(&str) -> String + Send + 'static) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let heads = Arc::new(Mutex::new(Vec::new()));
+        let recorded = heads.clone();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut head = String::new();
+                while reader.read_line(&mut head).unwrap_or(0) > 0 && !head.ends_with("\r\n\r\n") {}
+                let body = respond(&head);
+                recorded.lock().unwrap().push(head);
+                let _ = write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body);
+            }
+        });
+        (url, heads)
+    }
+    
+    #[tokio::test]
+    async fn auth_tokens_are_sent_with_every_request() {
+        let (url, heads) = serve(|head| {
+            if head.starts_with("POST /login") { r#"{"data":{"token":"abc123"}}"# } else { "ok" }.to_string()
+        });
+        let login = format!("{}/login", url);
+        let args = Args::parse_from(["pressr", "--url", &url, "--auth-request", &login, "--auth-extract-json", "/data/token"]);
+        
+        let (name, value) = resolve_auth_header(&reqwest::Client::new(), &args, &login).await.unwrap();
+        let config = Config {
+            url: url.clone(),
+            request_count: 3,
+            headers: HeaderMap::from_iter([(name, value)]),
+            ..Default::default()
+        };
+        let results = Runner::new(reqwest::Client::new(), config, None).unwrap().run().await.unwrap();
+        
+        assert_eq!(results.successful_requests, 3);
+        let heads = heads.lock().unwrap();
+        assert_eq!(heads.len(), 4);
+        assert!(heads[1..].iter().all(|head| head.to_lowercase().contains("authorization: bearer abc123\r\n")));
+    }
+    
+    #[test]
+    fn counts_and_durations_accept_suffixes() {
+        assert_eq!(parse_count("1M"), Ok(1_000_000));
+        assert_eq!(parse_count("2.5k"), Ok(2_500));
+        assert_eq!(parse_count("1_000"), Ok(1_000));
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("5m"), Ok(Duration::from_secs(300)));
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+
+        assert!(parse_count("1.5").is_err());
+        assert!(parse_count("10x").unwrap_err().contains("Unknown suffix"));
+        assert!(parse_count("0.0001k").is_err());
+        assert!(parse_count("").is_err());
+        assert!(parse_duration("30").unwrap_err().contains("Missing unit"));
+        assert!(parse_duration("3d").unwrap_err().contains("Unknown duration unit"));
+    }
+    
+    #[tokio::test]
+    async fn comparison_runs_reflect_the_slower_url() {
+        let (fast, _) = serve(|_| "ok".to_string());
+        let (slow, slow_heads) = serve(|_| {
+            std::thread::sleep(Duration::from_millis(40));
+            "ok".to_string()
+        });
+        let config = Config {
+            url: fast,
+            request_count: 5,
+            concurrency: 1,
+            ..Default::default()
+        };
+        let client = reqwest::Client::new();
+        
+        let a = Runner::new(client.clone(), config.clone(), None).unwrap().run().await.unwrap();
+        let b = run_comparison(&client, &config, None, &slow, StopHandle::new()).await.unwrap();
+        
+        assert_eq!(slow_heads.lock().unwrap().len(), 5);
+        assert_eq!(b.total_requests, a.total_requests);
+        let comparison = compare_results(&a, &b, DEFAULT_REGRESSION_THRESHOLD);
+        let regressed: Vec<&str> = comparison.regressions().map(|metric| metric.metric.as_str()).collect();
+        assert!(regressed.contains(&"p50 (ms)"), "{}", comparison);
+        assert!(regressed.contains(&"Throughput (req/s)"), "{}", comparison);
+        assert!(comparison.table("A", "B").contains("REGRESSED"));
+    }
+    
+    #[test]
+    fn only_small_fixed_count_runs_are_warned_about() {
+        let warning = small_run_warning(1, None).unwrap();
+        assert!(warning.starts_with("Warning: only 1 request: percentiles"), "{}", warning);
+        assert!(small_run_warning(MIN_RELIABLE_REQUESTS - 1, None).unwrap().contains("9 requests"));
+        assert_eq!(small_run_warning(MIN_RELIABLE_REQUESTS, None), None);
+        assert_eq!(small_run_warning(10_000, None), None);
+        // Timed runs are not warned about, whatever their request limit
+        assert_eq!(small_run_warning(1, Some(Duration::from_secs(30))), None);
+    }
+}
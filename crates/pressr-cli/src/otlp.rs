@@ -0,0 +1,337 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use pressr_core::{MetricsExporter, RequestResult, DEFAULT_USER_AGENT};
+use serde_json::{json, Value};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// Maximum number of spans sent in one export request
+const MAX_BATCH_SIZE: usize = 512;
+
+/// Interval at which a partial batch is sent
+const EXPORT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Timeout of an export request to the collector
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// OTLP span kind of an outgoing request
+const SPAN_KIND_CLIENT: u8 = 3;
+
+/// OTLP status codes
+const STATUS_CODE_UNSET: u8 = 0;
+const STATUS_CODE_OK: u8 = 1;
+const STATUS_CODE_ERROR: u8 = 2;
+
+/// Exporter turning every request result into an OpenTelemetry span
+///
+/// Spans are handed to an `OtlpStream`, which sends them to the collector in batches.
+#[derive(Debug)]
+pub struct OtlpExporter {
+    /// Channel to the stream sending the spans
+    spans: mpsc::UnboundedSender<Value>,
+    
+    /// Configured HTTP method of the requests (span name), unless a result has its own
+    method: String,
+    
+    /// Configured URL of the requests, unless a result has its own
+    url: String,
+}
+
+impl OtlpExporter {
+    /// Create an exporter for requests to `url`, along with the receiving end of its spans
+    pub fn new(method: &str, url: &str) -> (Self, mpsc::UnboundedReceiver<Value>) {
+        let (spans, receiver) = mpsc::unbounded_channel();
+        let exporter = Self {
+            spans,
+            method: method.to_string(),
+            url: url.to_string(),
+        };
+        
+        (exporter, receiver)
+    }
+    
+    /// Build the OTLP/JSON span of a request that completed at `end` (Unix time in nanoseconds)
+    ///
+    /// Targets and scenario steps carry their own method and URL; other requests were sent
+    /// to the configured ones.
+    fn span(&self, result: &RequestResult, end: u128) -> Value {
+        let method = result.method.as_deref().unwrap_or(&self.method);
+        let start = end.saturating_sub(result.response_time * 1_000_000);
+        
+        // Timing phases of the response as span events, offset from the start
+        let mut events = Vec::new();
+        let mut event = |name: &str, offset_ms: u128| events.push(json!({
+            "name": name,
+            "timeUnixNano": (start + offset_ms * 1_000_000).min(end).to_string(),
+        }));
+        if let Some(ttfb) = result.ttfb {
+            event("http.response.headers", ttfb);
+        }
+        if let Some(first_chunk) = result.first_chunk {
+            event("http.response.first_chunk", first_chunk);
+            if let Some(stream_duration) = result.stream_duration {
+                event("http.response.body_end", first_chunk.saturating_add(stream_duration));
+            }
+        }
+        
+        let mut attributes = vec![
+            string_attribute("http.request.method", method),
+            string_attribute("url.full", result.url.as_deref().unwrap_or(&self.url)),
+        ];
+        if let Some(status) = result.status {
+            attributes.push(int_attribute("http.response.status_code", status as u64));
+        }
+        if let Some(size) = result.response_size {
+            attributes.push(int_attribute("http.response.body.size", size as u64));
+        }
+        if let Some(host) = &result.host {
+            attributes.push(string_attribute("server.address", host));
+        }
+        if result.retries > 0 {
+            attributes.push(int_attribute("http.request.resend_count", result.retries as u64));
+        }
+        
+        let status = match (&result.error, result.success) {
+            (_, true) => json!({ "code": STATUS_CODE_OK }),
+            (_, false) if result.expected_failure => json!({ "code": STATUS_CODE_UNSET }),
+            (Some(error), false) => json!({ "code": STATUS_CODE_ERROR, "message": error }),
+            (None, false) => json!({ "code": STATUS_CODE_ERROR }),
+        };
+        if let Some(error) = result.error_tier() {
+            attributes.push(string_attribute("error.type", &format!("{:?}", error).to_lowercase()));
+        }
+        
+        json!({
+            "traceId": format!("{:032x}", rand::random::<u128>()),
+            "spanId": format!("{:016x}", rand::random::<u64>()),
+            "name": method,
+            "kind": SPAN_KIND_CLIENT,
+            "startTimeUnixNano": start.to_string(),
+            "endTimeUnixNano": end.to_string(),
+            "attributes": attributes,
+            "events": events,
+            "status": status,
+        })
+    }
+}
+
+impl MetricsExporter for OtlpExporter {
+    fn record(&self, result: &RequestResult) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        
+        // A scenario iteration is recorded as a span per step, each ending as long before
+        // the iteration completed as the step did
+        let spans: Vec<Value> = if result.steps.is_empty() {
+            vec![self.span(result, now)]
+        } else {
+            result.steps.iter()
+                .map(|step| {
+                    let before = result.completed_at.zip(step.completed_at)
+                        .map_or(0, |(iteration, step)| iteration.saturating_sub(step));
+                    self.span(step, now.saturating_sub(before * 1_000_000))
+                })
+                .collect()
+        };
+        
+        for span in spans {
+            // The stream only stops after the run, so a closed channel means it failed
+            if self.spans.send(span).is_err() {
+                debug!("OTLP stream stopped, dropping span");
+                return;
+            }
+        }
+    }
+}
+
+/// Background task sending the spans of an `OtlpExporter` to an OTLP/HTTP collector
+pub struct OtlpStream {
+    /// Signals the task to send the remaining spans and stop
+    stop: oneshot::Sender<()>,
+    
+    /// The sender task
+    task: JoinHandle<()>,
+}
+
+impl OtlpStream {
+    /// Start sending spans to `endpoint` (e.g. "http://localhost:4318/v1/traces")
+    ///
+    /// Spans are sent with a client of their own, so the load test's connection settings
+    /// (proxy, SNI, HTTP version, cookies) and connection pool are left alone.
+    pub fn spawn(endpoint: String, service_name: &str, run_id: &str, mut spans: mpsc::UnboundedReceiver<Value>) -> reqwest::Result<Self> {
+        debug!("Exporting request spans to {}", endpoint);
+        let client = reqwest::Client::builder()
+            .timeout(EXPORT_TIMEOUT)
+            .user_agent(DEFAULT_USER_AGENT)
+            .build()?;
+        let (stop, mut stopped) = oneshot::channel();
+        let resource = json!({
+            "attributes": [
+                string_attribute("service.name", service_name),
+                string_attribute("pressr.run_id", run_id),
+            ]
+        });
+        
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(EXPORT_INTERVAL);
+            let mut batch = Vec::new();
+            
+            loop {
+                tokio::select! {
+                    Some(span) = spans.recv() => {
+                        batch.push(span);
+                        if batch.len() < MAX_BATCH_SIZE {
+                            continue;
+                        }
+                    },
+                    _ = ticker.tick() => {},
+                    _ = &mut stopped => {
+                        // Send everything recorded before the stop
+                        while let Ok(span) = spans.try_recv() {
+                            batch.push(span);
+                            if batch.len() >= MAX_BATCH_SIZE {
+                                export(&client, &endpoint, &resource, &mut batch).await;
+                            }
+                        }
+                        export(&client, &endpoint, &resource, &mut batch).await;
+                        break;
+                    },
+                }
+                
+                export(&client, &endpoint, &resource, &mut batch).await;
+            }
+        });
+        
+        Ok(Self { stop, task })
+    }
+    
+    /// Send the remaining spans and stop the task
+    pub async fn finish(self) {
+        let _ = self.stop.send(());
+        if let Err(e) = self.task.await {
+            warn!("OTLP export task failed: {}", e);
+        }
+    }
+}
+
+/// Send a batch of spans to the collector and clear it
+async fn export(client: &reqwest::Client, endpoint: &str, resource: &Value, batch: &mut Vec<Value>) {
+    if batch.is_empty() {
+        return;
+    }
+    
+    let payload = json!({
+        "resourceSpans": [{
+            "resource": resource,
+            "scopeSpans": [{
+                "scope": { "name": "pressr", "version": env!("CARGO_PKG_VERSION") },
+                "spans": std::mem::take(batch),
+            }]
+        }]
+    });
+    
+    match client.post(endpoint).json(&payload).send().await {
+        Ok(response) if !response.status().is_success() => {
+            warn!("OTLP collector rejected spans with status {}", response.status());
+        },
+        Ok(_) => {},
+        Err(e) => warn!("Failed to export spans: {}", e),
+    }
+}
+
+/// OTLP string attribute
+fn string_attribute(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+/// OTLP integer attribute (64-bit integers are encoded as strings in OTLP/JSON)
+fn int_attribute(key: &str, value: u64) -> Value {
+    json!({ "key": key, "value": { "intValue": value.to_string() } })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn records_one_span_per_request_with_phase_events() {
+        let (exporter, mut spans) = OtlpExporter::new("GET", "http://localhost/");
+        let streamed = RequestResult {
+            status: Some(200),
+            success: true,
+            response_time: 30,
+            ttfb: Some(10),
+            first_chunk: Some(12),
+            stream_duration: Some(15),
+            ..Default::default()
+        };
+        let failed = RequestResult {
+            response_time: 5,
+            error: Some("connection refused".to_string()),
+            ..Default::default()
+        };
+        exporter.record(&streamed);
+        exporter.record(&failed);
+        
+        let first = spans.try_recv().unwrap();
+        let second = spans.try_recv().unwrap();
+        assert!(spans.try_recv().is_err());
+        
+        let events: Vec<_> = first["events"].as_array().unwrap().iter()
+            .map(|event| event["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(events, ["http.response.headers", "http.response.first_chunk", "http.response.body_end"]);
+        assert_eq!(first["status"]["code"], STATUS_CODE_OK);
+        
+        assert!(second["events"].as_array().unwrap().is_empty());
+        assert_eq!(second["status"]["code"], STATUS_CODE_ERROR);
+        assert_eq!(second["status"]["message"], "connection refused");
+    }
+    
+    #[test]
+    fn spans_use_the_method_and_url_of_targets_and_steps() {
+        let (exporter, mut spans) = OtlpExporter::new("GET", "http://localhost/");
+        let step = |method: &str, url: &str, completed_at| RequestResult {
+            status: Some(200),
+            success: true,
+            method: Some(method.to_string()),
+            url: Some(url.to_string()),
+            completed_at: Some(completed_at),
+            ..Default::default()
+        };
+        let iteration = RequestResult {
+            status: Some(200),
+            success: true,
+            completed_at: Some(100),
+            steps: vec![step("POST", "http://localhost/login", 40), step("GET", "http://localhost/cart", 100)],
+            ..Default::default()
+        };
+        exporter.record(&RequestResult { status: Some(200), success: true, ..Default::default() });
+        exporter.record(&step("PUT", "http://other.test/items/1", 10));
+        exporter.record(&iteration);
+        
+        let spans: Vec<Value> = std::iter::from_fn(|| spans.try_recv().ok()).collect();
+        let requests: Vec<(&str, &str)> = spans.iter()
+            .map(|span| {
+                let url = span["attributes"].as_array().unwrap().iter()
+                    .find(|attribute| attribute["key"] == "url.full")
+                    .and_then(|attribute| attribute["value"]["stringValue"].as_str())
+                    .unwrap();
+                (span["name"].as_str().unwrap(), url)
+            })
+            .collect();
+        assert_eq!(requests, [
+            ("GET", "http://localhost/"),
+            ("PUT", "http://other.test/items/1"),
+            ("POST", "http://localhost/login"),
+            ("GET", "http://localhost/cart"),
+        ]);
+        
+        // The login step ended 60 ms before the iteration
+        let end = |span: &Value| span["endTimeUnixNano"].as_str().unwrap().parse::<u128>().unwrap();
+        assert_eq!(end(&spans[3]) - end(&spans[2]), 60_000_000);
+    }
+}
@@ -0,0 +1,151 @@
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use pressr_core::{MetricsExporter, RequestResult};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// Width of the bar in characters
+const BAR_WIDTH: usize = 30;
+
+/// Time between redraws of the bar
+const REDRAW_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Exporter counting completed requests for the progress bar
+#[derive(Debug, Default)]
+pub struct ProgressExporter {
+    /// Requests completed so far
+    completed: AtomicUsize,
+
+    /// Requests failed so far
+    failed: AtomicUsize,
+
+    /// Response body bytes transferred so far
+    bytes: AtomicU64,
+}
+
+impl MetricsExporter for ProgressExporter {
+    fn record(&self, result: &RequestResult) {
+        self.completed.fetch_add(1, Ordering::Relaxed);
+        if result.is_failure() {
+            self.failed.fetch_add(1, Ordering::Relaxed);
+        }
+        if let Some(bytes) = result.transferred_bytes() {
+            self.bytes.fetch_add(bytes, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Background task redrawing a progress bar on stderr as requests complete
+pub struct ProgressBar {
+    /// Signals the task to draw the final state and stop
+    stop: oneshot::Sender<()>,
+
+    /// The drawing task
+    task: JoinHandle<()>,
+}
+
+impl ProgressBar {
+    /// Start drawing the progress towards `total` requests, or towards the end of `duration`
+    /// for timed runs
+    pub fn spawn(exporter: Arc<ProgressExporter>, total: Option<usize>, duration: Option<Duration>) -> Self {
+        debug!("Showing a progress bar");
+        let (stop, mut stopped) = oneshot::channel();
+        let started = Instant::now();
+
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(REDRAW_INTERVAL);
+            loop {
+                let last = tokio::select! {
+                    _ = ticker.tick() => false,
+                    _ = &mut stopped => true,
+                };
+
+                let line = render(&exporter, total, duration, started.elapsed());
+                let mut stderr = io::stderr().lock();
+                // Clear the rest of the line in case the previous one was longer
+                let drawn = write!(stderr, "\r{}\x1b[K", line)
+                    .and_then(|_| if last { writeln!(stderr) } else { Ok(()) })
+                    .and_then(|_| stderr.flush());
+                if let Err(e) = drawn {
+                    warn!("Failed to draw the progress bar: {}", e);
+                    break;
+                }
+                if last {
+                    break;
+                }
+            }
+        });
+
+        Self { stop, task }
+    }
+
+    /// Draw the final state and end the line, so later output starts on a fresh line
+    pub async fn finish(self) {
+        let _ = self.stop.send(());
+        if let Err(e) = self.task.await {
+            warn!("Progress bar task failed: {}", e);
+        }
+    }
+}
+
+/// One line of progress: bar, completed requests, throughput (in requests and response bytes)
+/// and estimated time left
+fn render(exporter: &ProgressExporter, total: Option<usize>, duration: Option<Duration>, elapsed: Duration) -> String {
+    let completed = exporter.completed.load(Ordering::Relaxed);
+    let failed = exporter.failed.load(Ordering::Relaxed);
+    let kilobytes = exporter.bytes.load(Ordering::Relaxed) as f64 / 1024.0;
+    let secs = elapsed.as_secs_f64();
+    let rps = if secs > 0.0 { completed as f64 / secs } else { 0.0 };
+    let kbps = if secs > 0.0 { kilobytes / secs } else { 0.0 };
+
+    // Timed runs end at the deadline, unless the request limit is reached first
+    let by_count = total.map(|total| {
+        let fraction = if total > 0 { completed as f64 / total as f64 } else { 1.0 };
+        let eta = (rps > 0.0).then(|| Duration::from_secs_f64(total.saturating_sub(completed) as f64 / rps));
+        (fraction, eta)
+    });
+    let by_time = duration.map(|duration| {
+        (secs / duration.as_secs_f64().max(f64::EPSILON), Some(duration.saturating_sub(elapsed)))
+    });
+    let (fraction, eta) = match (by_count, by_time) {
+        (Some(count), Some(time)) => if count.0 >= time.0 { count } else { time },
+        (Some(progress), None) | (None, Some(progress)) => progress,
+        (None, None) => (0.0, None),
+    };
+    let fraction = fraction.clamp(0.0, 1.0);
+
+    let filled = (fraction * BAR_WIDTH as f64).round() as usize;
+    let count = match total {
+        Some(total) => format!("{}/{}", completed, total),
+        None => completed.to_string(),
+    };
+    let eta = match eta {
+        Some(eta) => format!("ETA {}s", eta.as_secs()),
+        None => "ETA -".to_string(),
+    };
+
+    format!("[{}{}] {:>3.0}% {} requests, {} failed, {:.1} req/s, {:.1} KB at {:.1} KB/s, {}",
+        "#".repeat(filled), "-".repeat(BAR_WIDTH - filled), fraction * 100.0, count, failed, rps, kilobytes, kbps, eta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_counts_the_transferred_bytes() {
+        let exporter = ProgressExporter::default();
+        let sizes = [(Some(1024), None), (None, Some(2048)), (Some(512), Some(4096)), (None, None)];
+        for (response_size, content_length) in sizes {
+            exporter.record(&RequestResult { response_size, content_length, success: true, ..Default::default() });
+        }
+
+        assert_eq!(exporter.bytes.load(Ordering::Relaxed), 1024 + 2048 + 512);
+        let line = render(&exporter, Some(8), None, Duration::from_secs(2));
+        assert!(line.contains("4/8 requests, 0 failed, 2.0 req/s, 3.5 KB at 1.8 KB/s"), "{}", line);
+    }
+}
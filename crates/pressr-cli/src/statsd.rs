@@ -0,0 +1,112 @@
+use std::io::{Error, ErrorKind};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::Mutex;
+
+use pressr_core::{MetricsExporter, RequestResult};
+use tracing::{debug, warn};
+
+/// Maximum number of metric lines buffered before they are sent
+const MAX_BUFFERED_LINES: usize = 20;
+
+/// Exporter sending per-request metrics to a StatsD/DogStatsD agent over UDP
+#[derive(Debug)]
+pub struct StatsdExporter {
+    /// Socket bound to a local ephemeral port and connected to the StatsD agent
+    socket: UdpSocket,
+    
+    /// Prefix for all metric names
+    prefix: String,
+    
+    /// Metric lines waiting to be sent
+    buffer: Mutex<Vec<String>>,
+}
+
+impl StatsdExporter {
+    /// Create a new exporter for the StatsD agent at `address` (e.g. "127.0.0.1:8125")
+    pub fn new(address: &str, prefix: &str) -> std::io::Result<Self> {
+        // Resolve the agent once, so flushes don't look the address up again
+        let agent = address.to_socket_addrs()?
+            .next()
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, format!("No address found for {}", address)))?;
+        let local: SocketAddr = match agent {
+            SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+            SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+        };
+        let socket = UdpSocket::bind(local)?;
+        socket.connect(agent)?;
+        debug!("Sending StatsD metrics to {} ({})", address, agent);
+        
+        Ok(Self {
+            socket,
+            prefix: prefix.to_string(),
+            buffer: Mutex::new(Vec::new()),
+        })
+    }
+    
+    /// Send the buffered lines as a single datagram
+    fn send(&self, lines: &mut Vec<String>) {
+        if lines.is_empty() {
+            return;
+        }
+        
+        let payload = lines.join("\n");
+        if let Err(e) = self.socket.send(payload.as_bytes()) {
+            warn!("Failed to send StatsD metrics: {}", e);
+        }
+        lines.clear();
+    }
+}
+
+impl MetricsExporter for StatsdExporter {
+    fn record(&self, result: &RequestResult) {
+        let outcome = match (result.success, result.expected_failure) {
+            (true, _) => "success",
+            (false, true) => "expected_failure",
+            (false, false) => "failure",
+        };
+        let mut buffer = self.buffer.lock().unwrap_or_else(|e| e.into_inner());
+        
+        buffer.push(format!("{}.response_time:{}|ms", self.prefix, result.response_time));
+        buffer.push(format!("{}.requests.{}:1|c", self.prefix, outcome));
+        if let Some(status) = result.status {
+            buffer.push(format!("{}.status.{}:1|c", self.prefix, status));
+        }
+        
+        if buffer.len() >= MAX_BUFFERED_LINES {
+            self.send(&mut buffer);
+        }
+    }
+    
+    fn flush(&self) {
+        let mut buffer = self.buffer.lock().unwrap_or_else(|e| e.into_inner());
+        self.send(&mut buffer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn flushed_metrics_reach_the_agent() {
+        let agent = UdpSocket::bind("127.0.0.1:0").unwrap();
+        agent.set_read_timeout(Some(std::time::Duration::from_secs(5))).unwrap();
+        let exporter = StatsdExporter::new(&agent.local_addr().unwrap().to_string(), "pressr").unwrap();
+        
+        exporter.record(&RequestResult { status: Some(200), response_time: 42, success: true, ..Default::default() });
+        exporter.record(&RequestResult { status: Some(404), response_time: 7, expected_failure: true, ..Default::default() });
+        exporter.flush();
+        
+        let mut datagram = [0; 1024];
+        let len = agent.recv(&mut datagram).unwrap();
+        let lines: Vec<&str> = std::str::from_utf8(&datagram[..len]).unwrap().lines().collect();
+        assert_eq!(lines, [
+            "pressr.response_time:42|ms",
+            "pressr.requests.success:1|c",
+            "pressr.status.200:1|c",
+            "pressr.response_time:7|ms",
+            "pressr.requests.expected_failure:1|c",
+            "pressr.status.404:1|c",
+        ]);
+    }
+}
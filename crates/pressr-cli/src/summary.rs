@@ -0,0 +1,242 @@
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use pressr_core::{MetricsExporter, RequestResult};
+use serde::Serialize;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// Rolling summary of the requests completed within one interval
+#[derive(Debug, Serialize)]
+pub struct SummarySnapshot {
+    /// Identifier of the run the snapshot belongs to
+    pub run_id: String,
+    
+    /// Time of the snapshot (milliseconds since the Unix epoch)
+    pub timestamp_ms: u128,
+    
+    /// Seconds since the summary stream was started
+    pub elapsed_secs: f64,
+    
+    /// Requests completed in the window
+    pub requests: usize,
+    
+    /// Requests per second in the window
+    pub rps: f64,
+    
+    /// Percentage of failed requests in the window
+    pub error_rate: f64,
+    
+    /// Response body bytes transferred in the window
+    pub bytes: u64,
+    
+    /// Response body bytes per second in the window
+    pub bytes_per_sec: f64,
+    
+    /// Response body bytes transferred since the summary stream was started
+    pub total_bytes: u64,
+    
+    /// Median response time in the window (milliseconds)
+    pub p50: Option<u128>,
+    
+    /// 95th percentile response time in the window (milliseconds)
+    pub p95: Option<u128>,
+}
+
+/// Exporter collecting results into a window that is summarized at a fixed interval
+#[derive(Debug)]
+pub struct SummaryExporter {
+    /// Identifier of the run, repeated in every snapshot
+    run_id: String,
+    
+    /// Response times and outcomes (false if failed) of the requests in the current window
+    window: Mutex<Vec<(u128, bool)>>,
+    
+    /// Response body bytes transferred in the current window
+    window_bytes: AtomicU64,
+    
+    /// Response body bytes transferred since the stream was started
+    total_bytes: AtomicU64,
+    
+    /// Start of the current window
+    window_start: Mutex<Instant>,
+    
+    /// Start of the summary stream
+    started: Instant,
+}
+
+impl SummaryExporter {
+    /// Create an exporter with an empty window for the given run
+    pub fn new(run_id: String) -> Self {
+        let now = Instant::now();
+        Self {
+            run_id,
+            window: Mutex::new(Vec::new()),
+            window_bytes: AtomicU64::new(0),
+            total_bytes: AtomicU64::new(0),
+            window_start: Mutex::new(now),
+            started: now,
+        }
+    }
+    
+    /// Summarize the current window and start a new one
+    pub fn snapshot(&self) -> SummarySnapshot {
+        let mut window = std::mem::take(&mut *self.window.lock().unwrap());
+        let bytes = self.window_bytes.swap(0, Ordering::Relaxed);
+        let window_secs = {
+            let mut window_start = self.window_start.lock().unwrap();
+            let secs = window_start.elapsed().as_secs_f64();
+            *window_start = Instant::now();
+            secs
+        };
+        
+        window.sort_unstable_by_key(|(response_time, _)| *response_time);
+        let failed = window.iter().filter(|(_, success)| !success).count();
+        let percentile = |p: f64| {
+            let rank = ((p / 100.0) * window.len() as f64).ceil() as usize;
+            window.get(rank.max(1) - 1).map(|(response_time, _)| *response_time)
+        };
+        
+        SummarySnapshot {
+            run_id: self.run_id.clone(),
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis(),
+            elapsed_secs: self.started.elapsed().as_secs_f64(),
+            requests: window.len(),
+            rps: if window_secs > 0.0 { window.len() as f64 / window_secs } else { 0.0 },
+            bytes,
+            bytes_per_sec: if window_secs > 0.0 { bytes as f64 / window_secs } else { 0.0 },
+            total_bytes: self.total_bytes.load(Ordering::Relaxed),
+            error_rate: if window.is_empty() { 0.0 } else { failed as f64 / window.len() as f64 * 100.0 },
+            p50: percentile(50.0),
+            p95: percentile(95.0),
+        }
+    }
+}
+
+impl MetricsExporter for SummaryExporter {
+    fn record(&self, result: &RequestResult) {
+        self.window.lock().unwrap().push((result.response_time, !result.is_failure()));
+        if let Some(bytes) = result.transferred_bytes() {
+            self.window_bytes.fetch_add(bytes, Ordering::Relaxed);
+            self.total_bytes.fetch_add(bytes, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Destination of the summary stream
+pub enum SummaryOutput {
+    /// Print each snapshot to stdout
+    Stdout,
+    
+    /// Append each snapshot to an NDJSON file
+    File(File),
+}
+
+impl SummaryOutput {
+    /// Open the output, creating (or truncating) the file if a path is given
+    pub fn open(path: Option<&Path>) -> io::Result<Self> {
+        match path {
+            Some(path) => Ok(Self::File(File::create(path)?)),
+            None => Ok(Self::Stdout),
+        }
+    }
+    
+    /// Write a snapshot as a single JSON line
+    pub fn write(&mut self, snapshot: &SummarySnapshot) -> io::Result<()> {
+        let line = serde_json::to_string(snapshot)?;
+        match self {
+            Self::Stdout => {
+                println!("{}", line);
+                Ok(())
+            },
+            Self::File(file) => {
+                writeln!(file, "{}", line)?;
+                file.flush()
+            }
+        }
+    }
+}
+
+/// Background task writing a snapshot of the exporter's window at a fixed interval
+pub struct SummaryStream {
+    /// Signals the task to write a final snapshot and stop
+    stop: oneshot::Sender<()>,
+    
+    /// The writer task
+    task: JoinHandle<()>,
+}
+
+impl SummaryStream {
+    /// Start writing a snapshot every `interval`
+    pub fn spawn(exporter: Arc<SummaryExporter>, interval: Duration, mut output: SummaryOutput) -> Self {
+        debug!("Streaming rolling summaries every {:?}", interval);
+        let (stop, mut stopped) = oneshot::channel();
+        
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            // The first tick completes immediately
+            ticker.tick().await;
+            
+            loop {
+                let last = tokio::select! {
+                    _ = ticker.tick() => false,
+                    _ = &mut stopped => true,
+                };
+                
+                if let Err(e) = output.write(&exporter.snapshot()) {
+                    warn!("Failed to write summary snapshot: {}", e);
+                }
+                if last {
+                    break;
+                }
+            }
+        });
+        
+        Self { stop, task }
+    }
+    
+    /// Write a final snapshot for the requests since the last one and stop the task
+    pub async fn finish(self) {
+        let _ = self.stop.send(());
+        if let Err(e) = self.task.await {
+            warn!("Summary stream task failed: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[tokio::test]
+    async fn snapshots_are_written_throughout_the_run() {
+        let path = std::env::temp_dir().join(format!("pressr-summary-{}.ndjson", std::process::id()));
+        let exporter = Arc::new(SummaryExporter::new("run-1".to_string()));
+        let stream = SummaryStream::spawn(exporter.clone(), Duration::from_millis(50), SummaryOutput::open(Some(&path)).unwrap());
+        
+        let result = RequestResult { status: Some(200), success: true, response_time: 5, response_size: Some(10), ..Default::default() };
+        for _ in 0..10 {
+            exporter.record(&result);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        stream.finish().await;
+        
+        let snapshots: Vec<serde_json::Value> = std::fs::read_to_string(&path).unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        std::fs::remove_file(&path).unwrap();
+        assert!(snapshots.len() >= 3, "{} snapshots", snapshots.len());
+        assert!(snapshots.iter().all(|snapshot| snapshot["run_id"] == "run-1"));
+        assert_eq!(snapshots.iter().map(|snapshot| snapshot["requests"].as_u64().unwrap()).sum::<u64>(), 10);
+        assert_eq!(snapshots.last().unwrap()["total_bytes"], 100);
+    }
+}
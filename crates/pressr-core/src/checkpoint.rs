@@ -0,0 +1,161 @@
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{debug, instrument};
+use tokio::fs;
+
+use crate::error::{Error, Result};
+use crate::result::LoadTestResults;
+
+/// Snapshot of a partially completed load test that can be resumed later
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Total number of requests configured for the whole run
+    pub configured_requests: usize,
+    
+    /// Number of requests still to be sent when the checkpoint was taken
+    pub remaining_requests: usize,
+    
+    /// Aggregated results of the requests completed so far
+    pub results: LoadTestResults,
+}
+
+impl Checkpoint {
+    /// Create a new checkpoint from the results completed so far
+    pub fn new(configured_requests: usize, results: LoadTestResults) -> Self {
+        let remaining_requests = configured_requests.saturating_sub(results.total_requests);
+        
+        Self {
+            configured_requests,
+            remaining_requests,
+            results,
+        }
+    }
+    
+    /// Whether all configured requests have been completed
+    pub fn is_complete(&self) -> bool {
+        self.remaining_requests == 0
+    }
+    
+    /// Load a checkpoint from a JSON file
+    #[instrument(skip_all, fields(path = %path.as_ref().display()))]
+    pub async fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path_ref = path.as_ref();
+        debug!("Loading checkpoint from file: {}", path_ref.display());
+        
+        let content = fs::read_to_string(path_ref).await
+            .map_err(|e| Error::DataLoad {
+                path: path_ref.to_path_buf(),
+                source: Box::new(e),
+            })?;
+        
+        let checkpoint = Self::from_json_str(&content)
+            .map_err(|e| Error::DataLoad {
+                path: path_ref.to_path_buf(),
+                source: Box::new(e),
+            })?;
+        
+        debug!("Loaded checkpoint with {} completed and {} remaining requests",
+               checkpoint.results.total_requests, checkpoint.remaining_requests);
+        Ok(checkpoint)
+    }
+    
+    /// Parse a checkpoint from JSON, migrating results saved with an older schema
+    fn from_json_str(json: &str) -> Result<Self> {
+        let mut value: Value = serde_json::from_str(json)?;
+        
+        let results = match value.get_mut("results").map(Value::take) {
+            Some(mut results) => {
+                LoadTestResults::migrate(&mut results);
+                LoadTestResults::from_json_value(results)?
+            },
+            None => return Err(Error::MissingData("checkpoint has no results".to_string())),
+        };
+        
+        Ok(Self {
+            configured_requests: serde_json::from_value(value["configured_requests"].take())?,
+            remaining_requests: serde_json::from_value(value["remaining_requests"].take())?,
+            results,
+        })
+    }
+    
+    /// Save the checkpoint to a JSON file
+    #[instrument(skip_all, fields(path = %path.as_ref().display()))]
+    pub async fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path_ref = path.as_ref();
+        debug!("Saving checkpoint to file: {}", path_ref.display());
+        
+        let json = serde_json::to_string(self)
+            .map_err(Error::Serialization)?;
+        fs::write(path_ref, json).await?;
+        
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::Client;
+    use crate::result::RESULTS_SCHEMA_VERSION;
+    use crate::runner::{Config, Runner};
+    use crate::test_server::{response, TestServer};
+    
+    #[tokio::test]
+    async fn resumed_runs_combine_with_the_checkpoint() {
+        let server = TestServer::start(|_| response(200, &[], "ok"));
+        let path = std::env::temp_dir().join(format!("pressr-checkpoint-{}.json", std::process::id()));
+        let batch = |request_count, first_index| Config {
+            url: server.url.clone(),
+            request_count,
+            first_index,
+            ..Default::default()
+        };
+        
+        // Interrupted after the first 3 of 5 requests
+        let first = Runner::new(Client::new(), batch(3, 0), None).unwrap().run().await.unwrap();
+        Checkpoint::new(5, first).save(&path).await.unwrap();
+        
+        let checkpoint = Checkpoint::load(&path).await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(checkpoint.remaining_requests, 2);
+        assert!(!checkpoint.is_complete());
+        
+        let rest = Runner::new(Client::new(), batch(checkpoint.remaining_requests, 3), None).unwrap().run().await.unwrap();
+        let combined = checkpoint.results.merge(&rest);
+        assert_eq!(combined.total_requests, 5);
+        assert_eq!(combined.successful_requests, 5);
+        assert_eq!(combined.configured_requests, 5);
+        assert_eq!(server.requests().len(), 5);
+        assert!(Checkpoint::new(5, combined).is_complete());
+    }
+    
+    #[test]
+    fn v1_checkpoints_are_migrated_on_load() {
+        let v1 = serde_json::json!({
+            "configured_requests": 10,
+            "remaining_requests": 7,
+            "results": {
+                "total_requests": 3,
+                "successful_requests": 3,
+                "failed_requests": 0,
+                "average_response_time": 10.0,
+                "min_response_time": 10,
+                "max_response_time": 10,
+                "duration": 0.5,
+                "status_codes": { "200": 3 },
+                "errors": {},
+                "requests": [],
+                "throughput": 6.0,
+                "total_data_transferred": null,
+                "response_time_std_dev": 0.0,
+                "transfer_rate": null,
+            },
+        });
+        let checkpoint = Checkpoint::from_json_str(&v1.to_string()).unwrap();
+        
+        assert_eq!(checkpoint.remaining_requests, 7);
+        assert_eq!(checkpoint.results.attempted_requests, 3);
+        assert_eq!(checkpoint.results.schema_version, RESULTS_SCHEMA_VERSION);
+    }
+}
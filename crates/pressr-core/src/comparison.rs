@@ -0,0 +1,202 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::result::LoadTestResults;
+
+/// Default relative change (in percent) beyond which a worse metric counts as a regression
+pub const DEFAULT_REGRESSION_THRESHOLD: f64 = 5.0;
+
+/// Direction of a metric's change between a baseline and a current run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Change {
+    /// Better by more than the regression threshold
+    Improved,
+    
+    /// Worse by more than the regression threshold
+    Regressed,
+    
+    /// Within the regression threshold (or not measurable in either run)
+    Unchanged,
+}
+
+/// One metric of a baseline and a current run, with the relative change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricDelta {
+    /// Metric name, e.g. "p95 (ms)"
+    pub metric: String,
+    
+    /// Value in the baseline run (None if it could not be measured)
+    pub baseline: Option<f64>,
+    
+    /// Value in the current run (None if it could not be measured)
+    pub current: Option<f64>,
+    
+    /// Relative change from the baseline in percent (None without a non-zero baseline)
+    pub delta_percent: Option<f64>,
+    
+    /// Whether the change is an improvement, a regression or neither
+    pub change: Change,
+}
+
+/// Comparison of a run against a baseline run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComparisonReport {
+    /// Relative change (in percent) beyond which a metric counts as improved or regressed
+    pub regression_threshold: f64,
+    
+    /// Compared metrics: throughput, p50, p95, p99 and error rate
+    pub metrics: Vec<MetricDelta>,
+}
+
+impl ComparisonReport {
+    /// Metrics that regressed beyond the threshold
+    pub fn regressions(&self) -> impl Iterator<Item = &MetricDelta> {
+        self.metrics.iter().filter(|metric| metric.change == Change::Regressed)
+    }
+    
+    /// Whether any metric regressed beyond the threshold
+    pub fn has_regressions(&self) -> bool {
+        self.regressions().next().is_some()
+    }
+    
+    /// Text table of the comparison with custom column headings for the two runs
+    /// (the `Display` output uses "Baseline" and "Current")
+    pub fn table(&self, baseline_label: &str, current_label: &str) -> String {
+        Table { report: self, baseline_label, current_label }.to_string()
+    }
+}
+
+impl fmt::Display for ComparisonReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Table { report: self, baseline_label: "Baseline", current_label: "Current" }.fmt(f)
+    }
+}
+
+/// Text table of a comparison with the column headings of the two runs
+struct Table<'a> {
+    report: &'a ComparisonReport,
+    baseline_label: &'a str,
+    current_label: &'a str,
+}
+
+impl fmt::Display for Table<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = |value: Option<f64>| value.map(|value| format!("{:.2}", value)).unwrap_or_else(|| "-".to_string());
+        
+        writeln!(f, "{:<20} {:>12} {:>12} {:>10}", "", self.baseline_label, self.current_label, "Delta")?;
+        for metric in &self.report.metrics {
+            let delta = metric.delta_percent.map(|delta| format!("{:+.1}%", delta)).unwrap_or_else(|| "-".to_string());
+            let marker = match metric.change {
+                Change::Improved => "  improved",
+                Change::Regressed => "  REGRESSED",
+                Change::Unchanged => "",
+            };
+            writeln!(f, "{:<20} {:>12} {:>12} {:>10}{}",
+                metric.metric, value(metric.baseline), value(metric.current), delta, marker)?;
+        }
+        
+        let regressions = self.report.regressions().count();
+        if regressions > 0 {
+            write!(f, "{} regression(s) beyond {:.1}%", regressions, self.report.regression_threshold)
+        } else {
+            write!(f, "No regressions beyond {:.1}%", self.report.regression_threshold)
+        }
+    }
+}
+
+/// Compare a run against a baseline run
+///
+/// Throughput, the p50, p95 and p99 response times and the error rate are compared; a
+/// metric that got worse by more than `regression_threshold` percent is a regression, and
+/// one that got better by as much an improvement. An error rate rising from zero is always
+/// a regression.
+pub fn compare_results(baseline: &LoadTestResults, current: &LoadTestResults, regression_threshold: f64) -> ComparisonReport {
+    let error_rate = |results: &LoadTestResults| if results.total_requests > 0 {
+        results.failed_requests as f64 / results.total_requests as f64 * 100.0
+    } else {
+        0.0
+    };
+    let percentile = |results: &LoadTestResults, p: f64| results.percentiles.and_then(|percentiles| percentiles.get(p));
+    
+    // (metric, baseline, current, whether higher values are better)
+    let metrics = [
+        ("Throughput (req/s)", Some(baseline.throughput), Some(current.throughput), true),
+        ("p50 (ms)", percentile(baseline, 50.0), percentile(current, 50.0), false),
+        ("p95 (ms)", percentile(baseline, 95.0), percentile(current, 95.0), false),
+        ("p99 (ms)", percentile(baseline, 99.0), percentile(current, 99.0), false),
+        ("Error rate (%)", Some(error_rate(baseline)), Some(error_rate(current)), false),
+    ];
+    
+    ComparisonReport {
+        regression_threshold,
+        metrics: metrics.into_iter()
+            .map(|(metric, baseline, current, higher_is_better)| {
+                let delta_percent = match (baseline, current) {
+                    (Some(baseline), Some(current)) if baseline != 0.0 => Some((current - baseline) / baseline * 100.0),
+                    _ => None,
+                };
+                // Positive when the metric got better
+                let improvement = match (baseline, current, delta_percent) {
+                    (_, _, Some(delta)) => Some(if higher_is_better { delta } else { -delta }),
+                    (Some(_), Some(current), None) if current > 0.0 => Some(if higher_is_better { f64::INFINITY } else { f64::NEG_INFINITY }),
+                    _ => None,
+                };
+                let change = match improvement {
+                    Some(improvement) if improvement > regression_threshold => Change::Improved,
+                    Some(improvement) if improvement < -regression_threshold => Change::Regressed,
+                    _ => Change::Unchanged,
+                };
+                
+                MetricDelta {
+                    metric: metric.to_string(),
+                    baseline,
+                    current,
+                    delta_percent,
+                    change,
+                }
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::result::RequestResult;
+    use std::time::Duration;
+    
+    fn results(scale: u128) -> LoadTestResults {
+        let requests = (1..=100)
+            .map(|i| RequestResult { status: Some(200), response_time: i * scale, success: true, ..Default::default() })
+            .collect();
+        LoadTestResults::new(requests, Duration::from_secs(1))
+    }
+    
+    #[test]
+    fn slower_percentiles_are_regressions() {
+        let report = compare_results(&results(1), &results(2), DEFAULT_REGRESSION_THRESHOLD);
+        let changes: Vec<(&str, Change)> = report.metrics.iter()
+            .map(|metric| (metric.metric.as_str(), metric.change))
+            .collect();
+        
+        assert_eq!(changes, [
+            ("Throughput (req/s)", Change::Unchanged),
+            ("p50 (ms)", Change::Regressed),
+            ("p95 (ms)", Change::Regressed),
+            ("p99 (ms)", Change::Regressed),
+            ("Error rate (%)", Change::Unchanged),
+        ]);
+        assert!(report.has_regressions());
+    }
+    
+    #[test]
+    fn table_uses_the_given_labels() {
+        let report = compare_results(&results(1), &results(1), DEFAULT_REGRESSION_THRESHOLD);
+        let header = |table: String| table.lines().next().unwrap().split_whitespace().map(String::from).collect::<Vec<_>>();
+        
+        assert_eq!(header(report.table("A", "B")), ["A", "B", "Delta"]);
+        assert_eq!(header(report.to_string()), ["Baseline", "Current", "Delta"]);
+        assert!(report.to_string().ends_with("No regressions beyond 5.0%"));
+    }
+}
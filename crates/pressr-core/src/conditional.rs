@@ -0,0 +1,122 @@
+use reqwest::{Client, header::ETAG};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, instrument};
+
+use crate::error::{Error, Result};
+use crate::result::RequestResult;
+use crate::runner::Config;
+
+/// Summary of a conditional-request (`If-None-Match`) run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConditionalStats {
+    /// ETag captured from the priming request
+    pub etag: String,
+    
+    /// Number of `304 Not Modified` responses
+    pub not_modified: usize,
+    
+    /// Number of full `200` responses where a `304` was expected
+    pub unexpected_full: usize,
+    
+    /// Fraction of requests answered with `304` (0.0 - 1.0)
+    pub hit_rate: f64,
+    
+    /// Average response time of `304` responses in milliseconds
+    pub avg_not_modified_time: Option<f64>,
+    
+    /// Average response time of full `200` responses in milliseconds
+    pub avg_full_time: Option<f64>,
+}
+
+impl ConditionalStats {
+    /// Calculate conditional-request statistics from individual results
+    pub fn from_requests(etag: &str, requests: &[RequestResult]) -> Self {
+        let not_modified: Vec<u128> = requests.iter()
+            .filter(|r| r.status == Some(304))
+            .map(|r| r.response_time)
+            .collect();
+        let full: Vec<u128> = requests.iter()
+            .filter(|r| r.status == Some(200))
+            .map(|r| r.response_time)
+            .collect();
+        
+        let average = |times: &[u128]| {
+            if times.is_empty() {
+                None
+            } else {
+                Some(times.iter().sum::<u128>() as f64 / times.len() as f64)
+            }
+        };
+        
+        Self {
+            etag: etag.to_string(),
+            not_modified: not_modified.len(),
+            unexpected_full: full.len(),
+            hit_rate: if requests.is_empty() {
+                0.0
+            } else {
+                not_modified.len() as f64 / requests.len() as f64
+            },
+            avg_not_modified_time: average(&not_modified),
+            avg_full_time: average(&full),
+        }
+    }
+}
+
+/// Fetch the target once and return the `ETag` of the response
+#[instrument(skip_all, fields(url = %config.url))]
+pub async fn fetch_etag(client: &Client, config: &Config) -> Result<String> {
+    info!("Fetching ETag from {}", config.url);
+    
+    let response = config.request(client, config.method.clone(), &config.url, config.headers.clone())
+        .send()
+        .await?;
+    
+    let etag = response.headers()
+        .get(ETAG)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_string())
+        .ok_or_else(|| Error::MissingData(format!("Response from {} has no ETag header", config.url)))?;
+    
+    debug!("Captured ETag: {}", etag);
+    Ok(etag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use crate::runner::Runner;
+    use crate::test_server::{header, response, TestServer};
+    
+    #[tokio::test]
+    async fn not_modified_responses_make_the_hit_rate() {
+        let validated = Arc::new(AtomicUsize::new(0));
+        let server = TestServer::start(move |request| {
+            // One of the validated requests gets the full response anyway
+            match header(request, "If-None-Match") {
+                Some("\"v1\"") if validated.fetch_add(1, Ordering::SeqCst) != 1 => response(304, &[("ETag", "\"v1\"")], ""),
+                _ => response(200, &[("ETag", "\"v1\"")], "full body"),
+            }
+        });
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 4,
+            concurrency: 1,
+            etag_validation: true,
+            ..Default::default()
+        };
+        let results = Runner::new(Client::new(), config, None).unwrap().run().await.unwrap();
+        let conditional = results.conditional.unwrap();
+        
+        assert_eq!(conditional.etag, "\"v1\"");
+        assert_eq!(conditional.not_modified, 3);
+        assert_eq!(conditional.unexpected_full, 1);
+        assert_eq!(conditional.hit_rate, 0.75);
+        assert_eq!(results.successful_requests, 4);
+        // The priming request is sent without the ETag
+        assert_eq!(server.requests().len(), 5);
+        assert_eq!(header(&server.requests()[0], "If-None-Match"), None);
+    }
+}
@@ -1,9 +1,16 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use reqwest::Method;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use rand::Rng;
+use rand::distributions::{Distribution, WeightedIndex};
 use rand::seq::SliceRandom;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tracing::{debug, instrument};
+use tracing::{debug, instrument, warn};
 use tokio::fs;
 
 use crate::error::{Error, Result};
@@ -15,6 +22,20 @@ pub struct RequestData {
     #[serde(default)]
     pub body: Option<Value>,
     
+    /// File the request body is read from once per run, instead of `body` (relative to the
+    /// working directory); `.json` files are used like `body`, other files are sent as raw bytes
+    #[serde(default)]
+    pub body_file: Option<PathBuf>,
+    
+    /// Encoding of `body`, of the bodies of the body pool and of target bodies
+    #[serde(default)]
+    pub body_type: BodyType,
+    
+    /// Files sent as parts of a multipart body, as part names mapped to file paths
+    /// (relative to the working directory)
+    #[serde(default)]
+    pub files: HashMap<String, PathBuf>,
+    
     /// HTTP request headers
     #[serde(default)]
     pub headers: HashMap<String, String>,
@@ -30,6 +51,166 @@ pub struct RequestData {
     /// Variable sets for templating/randomization
     #[serde(default)]
     pub variables: HashMap<String, Vec<String>>,
+    
+    /// Transforms applied to the JSON body of every request
+    #[serde(default)]
+    pub transforms: Vec<BodyTransform>,
+    
+    /// How values are picked from the variable sets (and CSV rows)
+    #[serde(default)]
+    pub variable_strategy: VariableStrategy,
+    
+    /// Rows of a CSV data source, keyed by column name
+    ///
+    /// Each request uses one row, whose columns are available as `{{column}}` tokens.
+    #[serde(default)]
+    pub rows: Vec<HashMap<String, String>>,
+    
+    /// Request bodies picked per request in proportion to their weights
+    /// (used instead of `body` when not empty)
+    #[serde(default)]
+    pub body_pool: Vec<WeightedBody>,
+    
+    /// Steps every iteration runs in order, instead of a single request to the configured URL
+    #[serde(default)]
+    pub scenario: Vec<ScenarioStep>,
+}
+
+/// A step of a scenario
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioStep {
+    /// Name of the step in reports (defaults to its position, e.g. "step 1")
+    #[serde(default)]
+    pub name: Option<String>,
+    
+    /// HTTP method of the step (defaults to the configured method)
+    #[serde(default)]
+    pub method: Option<String>,
+    
+    /// URL of the step, templated; values extracted by earlier steps are `{{name}}` tokens
+    pub url: String,
+    
+    /// Headers sent in addition to the configured ones, templated like the URL
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    
+    /// Body, templated like the URL
+    #[serde(default)]
+    pub body: Option<Value>,
+    
+    /// Encoding of the body (defaults to the `body_type` of the data file); multipart
+    /// bodies also carry the data file's `files`
+    #[serde(default)]
+    pub body_type: Option<BodyType>,
+    
+    /// Values extracted from the JSON response body for later steps, as variable names
+    /// mapped to JSON pointers (e.g. `token: /data/token`)
+    #[serde(default)]
+    pub extract: HashMap<String, String>,
+    
+    /// What happens to the iteration when the step fails or a value cannot be extracted
+    #[serde(default)]
+    pub on_failure: StepFailure,
+}
+
+impl ScenarioStep {
+    /// Name of the step in reports, given its position in the scenario
+    pub fn label(&self, position: usize) -> String {
+        self.name.clone().unwrap_or_else(|| format!("step {}", position + 1))
+    }
+    
+    /// HTTP method of the step, given the configured method
+    pub fn method(&self, default_method: &Method) -> Result<Method> {
+        match &self.method {
+            Some(method) => Method::from_str(&method.to_uppercase())
+                .map_err(|_| Error::Other(format!("Invalid HTTP method '{}' in scenario step", method))),
+            None => Ok(default_method.clone()),
+        }
+    }
+}
+
+/// What happens to a scenario iteration when one of its steps fails
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StepFailure {
+    /// Skip the remaining steps of the iteration
+    #[default]
+    Abort,
+    
+    /// Run the remaining steps anyway (tokens of values that were not extracted stay unresolved)
+    Continue,
+}
+
+/// A request body of the body pool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeightedBody {
+    /// Name of the body in reports (defaults to its position in the pool, e.g. "#1")
+    #[serde(default)]
+    pub name: Option<String>,
+    
+    /// Relative weight of the body (defaults to 1)
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+    
+    /// JSON body, templated like `body`
+    pub body: Value,
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+impl WeightedBody {
+    /// Name of the body in reports, given its position in the pool
+    pub fn label(&self, position: usize) -> String {
+        self.name.clone().unwrap_or_else(|| format!("#{}", position + 1))
+    }
+}
+
+/// Encoding of a request body
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BodyType {
+    /// JSON (`application/json`)
+    #[default]
+    Json,
+    
+    /// URL-encoded form (`application/x-www-form-urlencoded`) of the fields of a body object
+    Form,
+    
+    /// `multipart/form-data` of the fields of a body object, plus the `files` as file parts
+    Multipart,
+}
+
+/// Strategy for picking values from variable sets
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VariableStrategy {
+    /// Pick a random value for every request
+    #[default]
+    Random,
+    
+    /// Cycle through the values in order (request N uses value N modulo the set size)
+    Sequential,
+    
+    /// Use every value exactly once, in order; the run stops when the smallest set is exhausted
+    Once,
+}
+
+/// A single step of the request body transform pipeline
+///
+/// Fields are dot-separated (`user.id`) or JSON pointers (`/user/id`, with `~1` for a `/`
+/// and `~0` for a `~` in a field name). Values may contain `{{seq}}`, `{{uuid}}`, `{{now}}`
+/// or `{{variable}}` tokens.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BodyTransform {
+    /// Set a field to a templated value, parsed as JSON if it is valid JSON (so `{{seq}}` sets
+    /// a number and `"\"{{seq}}\""` a string) and a string otherwise
+    Set { set: String, value: String },
+    
+    /// Remove a field
+    Remove { remove: String },
 }
 
 impl RequestData {
@@ -46,26 +227,466 @@ impl RequestData {
             })?;
         
         debug!("Parsing JSON data");
-        let data: RequestData = serde_json::from_str(&content)
+        let mut data: RequestData = serde_json::from_str(&content)
             .map_err(|e| Error::DataLoad {
                 path: path_ref.to_path_buf(),
                 source: Box::new(e),
             })?;
         
+        data.expand_env_vars().map_err(|e| Error::DataLoad {
+            path: path_ref.to_path_buf(),
+            source: e.into(),
+        })?;
+        data.validate(path_ref)?;
         debug!("Successfully loaded request data");
         Ok(data)
     }
     
+    /// Load request data from a YAML file (same structure as the JSON format)
+    #[instrument(skip_all, fields(path = %path.as_ref().display()))]
+    pub async fn from_yaml_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path_ref = path.as_ref();
+        debug!("Loading request data from file: {}", path_ref.display());
+        
+        let content = fs::read_to_string(path_ref).await
+            .map_err(|e| Error::DataLoad {
+                path: path_ref.to_path_buf(),
+                source: Box::new(e),
+            })?;
+        
+        debug!("Parsing YAML data");
+        let mut data: RequestData = serde_yaml::from_str(&content)
+            .map_err(|e| Error::DataLoad {
+                path: path_ref.to_path_buf(),
+                source: Box::new(e),
+            })?;
+        
+        data.expand_env_vars().map_err(|e| Error::DataLoad {
+            path: path_ref.to_path_buf(),
+            source: e.into(),
+        })?;
+        data.validate(path_ref)?;
+        debug!("Successfully loaded request data");
+        Ok(data)
+    }
+    
+    /// Expand `${VAR}` and `${VAR:-default}` references to environment variables in the
+    /// header values, the query parameters, the scenario step URLs and headers and the
+    /// strings of the bodies (a JSON body file is expanded when it is read)
+    fn expand_env_vars(&mut self) -> std::result::Result<(), String> {
+        for value in self.headers.values_mut().chain(self.params.values_mut()) {
+            *value = expand_env(value)?;
+        }
+        for body in self.body.iter_mut().chain(self.body_pool.iter_mut().map(|pooled| &mut pooled.body)) {
+            expand_env_value(body)?;
+        }
+        for step in &mut self.scenario {
+            step.url = expand_env(&step.url)?;
+            for value in step.headers.values_mut() {
+                *value = expand_env(value)?;
+            }
+            if let Some(body) = &mut step.body {
+                expand_env_value(body)?;
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Reject body pools without a positive weight, scenario steps with an invalid method
+    /// and bodies (including step bodies) that cannot be encoded as their body type up front
+    fn validate(&self, path: &Path) -> Result<()> {
+        let invalid = |message: &str| Error::DataLoad {
+            path: path.to_path_buf(),
+            source: message.into(),
+        };
+        if self.body_type != BodyType::Json {
+            let mut bodies = self.body.iter().chain(self.body_pool.iter().map(|pooled| &pooled.body));
+            if bodies.any(|body| !body.is_object()) {
+                return Err(invalid("form and multipart bodies must be objects of fields"));
+            }
+        }
+        if !self.files.is_empty() && self.body_type != BodyType::Multipart {
+            return Err(invalid("files can only be sent with the multipart body type"));
+        }
+        
+        if !self.body_pool.is_empty() {
+            WeightedIndex::new(self.body_pool.iter().map(|body| body.weight))
+                .map_err(|e| Error::DataLoad {
+                    path: path.to_path_buf(),
+                    source: Box::new(e),
+                })?;
+        }
+        for step in &self.scenario {
+            step.method(&Method::GET).map_err(|e| Error::DataLoad {
+                path: path.to_path_buf(),
+                source: Box::new(e),
+            })?;
+            if step.body_type.unwrap_or(self.body_type) != BodyType::Json && step.body.as_ref().is_some_and(|body| !body.is_object()) {
+                return Err(invalid("form and multipart step bodies must be objects of fields"));
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Load request data from a CSV file with a header row
+    ///
+    /// Every record becomes a row whose columns can be used as template tokens.
+    #[instrument(skip_all, fields(path = %path.as_ref().display()))]
+    pub async fn from_csv_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path_ref = path.as_ref();
+        debug!("Loading CSV data from file: {}", path_ref.display());
+        
+        let content = fs::read_to_string(path_ref).await
+            .map_err(|e| Error::DataLoad {
+                path: path_ref.to_path_buf(),
+                source: Box::new(e),
+            })?;
+        
+        let mut reader = csv::Reader::from_reader(content.as_bytes());
+        let rows = reader.deserialize::<HashMap<String, String>>()
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| Error::DataLoad {
+                path: path_ref.to_path_buf(),
+                source: Box::new(e),
+            })?;
+        
+        debug!("Loaded {} CSV rows", rows.len());
+        Ok(Self {
+            rows,
+            ..Default::default()
+        })
+    }
+    
+    /// Load request data, choosing the format from the file extension (`.json`, `.yaml`,
+    /// `.yml` or `.csv`)
+    pub async fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let extension = path.as_ref().extension()
+            .map(|ext| ext.to_string_lossy().to_ascii_lowercase());
+        
+        match extension.as_deref() {
+            Some("json") => Self::from_json_file(path).await,
+            Some("yaml" | "yml") => Self::from_yaml_file(path).await,
+            Some("csv") => Self::from_csv_file(path).await,
+            _ => Err(Error::DataLoad {
+                path: path.as_ref().to_path_buf(),
+                source: "unrecognized file extension (expected .json, .yaml, .yml or .csv)".into(),
+            }),
+        }
+    }
+    
     /// Get a random value from a variable set
     pub fn get_random_variable(&self, name: &str) -> Option<&str> {
+        self.choose_variable(name, &mut rand::thread_rng())
+    }
+    
+    /// Get a value from a variable set using the given random number generator
+    pub fn choose_variable<R: Rng + ?Sized>(&self, name: &str, rng: &mut R) -> Option<&str> {
         self.variables.get(name)
-            .and_then(|values| {
-                if values.is_empty() {
-                    None
-                } else {
-                    let mut rng = rand::thread_rng();
-                    values.choose(&mut rng).map(|s| s.as_str())
-                }
-            })
-    }
-} 
\ No newline at end of file
+            .and_then(|values| values.choose(rng))
+            .map(|s| s.as_str())
+    }
+    
+    /// Pick a body from the body pool by weight, returning its position in the pool
+    pub fn choose_body<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<(usize, &WeightedBody)> {
+        let weights = WeightedIndex::new(self.body_pool.iter().map(|body| body.weight)).ok()?;
+        let position = weights.sample(rng);
+        Some((position, &self.body_pool[position]))
+    }
+    
+    /// Get the value of a variable set for the request with the given index,
+    /// following the configured `variable_strategy`
+    pub fn select_variable<R: Rng + ?Sized>(&self, name: &str, index: usize, rng: &mut R) -> Option<&str> {
+        let values = self.variables.get(name)?;
+        if values.is_empty() {
+            return None;
+        }
+        
+        match self.variable_strategy {
+            VariableStrategy::Random => values.choose(rng),
+            VariableStrategy::Sequential => values.get(index % values.len()),
+            VariableStrategy::Once => values.get(index),
+        }
+        .map(|s| s.as_str())
+    }
+    
+    /// Get the CSV row for the request with the given index, following the
+    /// configured `variable_strategy`
+    pub fn select_row<R: Rng + ?Sized>(&self, index: usize, rng: &mut R) -> Option<&HashMap<String, String>> {
+        if self.rows.is_empty() {
+            return None;
+        }
+        
+        match self.variable_strategy {
+            VariableStrategy::Random => self.rows.choose(rng),
+            VariableStrategy::Sequential => self.rows.get(index % self.rows.len()),
+            VariableStrategy::Once => self.rows.get(index),
+        }
+    }
+    
+    /// Number of requests that can be sent before a variable set (or the CSV rows)
+    /// is exhausted (`Once` strategy only)
+    pub fn request_limit(&self) -> Option<usize> {
+        if self.variable_strategy != VariableStrategy::Once {
+            return None;
+        }
+        
+        self.variables.values()
+            .map(|values| values.len())
+            .chain(Some(self.rows.len()))
+            .filter(|len| *len > 0)
+            .min()
+    }
+    
+    /// Merge the data file headers with `headers`, which take precedence on conflict
+    ///
+    /// Data file headers that are not valid HTTP headers are skipped with a warning.
+    pub fn merge_headers(&self, headers: &HeaderMap) -> HeaderMap {
+        let mut merged = HeaderMap::with_capacity(self.headers.len() + headers.len());
+        for (key, value) in &self.headers {
+            match (HeaderName::from_str(key), HeaderValue::from_str(value)) {
+                (Ok(key), Ok(value)) => {
+                    merged.insert(key, value);
+                },
+                _ => warn!("Invalid header in data file: {}: {}", key, value),
+            }
+        }
+        
+        // Replace (rather than append to) data file headers with the same name
+        for key in headers.keys() {
+            merged.remove(key);
+        }
+        for (key, value) in headers {
+            merged.append(key, value.clone());
+        }
+        
+        merged
+    }
+    
+    /// Replace `{name}` placeholders in a URL with the path variables
+    pub fn apply_path_variables(&self, url: &str) -> Result<String> {
+        substitute_path_variables(url, &self.path_variables)
+    }
+}
+
+/// Characters left as they are when a value is inserted into the path or query of a URL
+/// (the unreserved characters of RFC 3986)
+const URL_VALUE_SET: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'.').remove(b'_').remove(b'~');
+
+/// Offset at which the path (or the query, without a path) of a URL starts
+///
+/// Values inserted before this offset make up the scheme and host, and are inserted as they are.
+pub(crate) fn url_path_start(url: &str) -> usize {
+    let authority = url.find("://").map_or(0, |scheme| scheme + 3);
+    url[authority..].find(['/', '?', '#']).map_or(url.len(), |start| authority + start)
+}
+
+/// Percent-encode a value inserted into the path or query of a URL, so that e.g. a `/`, `?`
+/// or space in the value does not change the structure of the URL
+pub(crate) fn encode_url_value(value: &str) -> Cow<'_, str> {
+    utf8_percent_encode(value, URL_VALUE_SET).into()
+}
+
+/// Replace `{name}` placeholders in a URL with the given path variables
+///
+/// `{{token}}` template tokens are left alone. Values in the path and query are
+/// percent-encoded. A placeholder without a path variable is an `Error::MissingData`.
+pub(crate) fn substitute_path_variables(url: &str, variables: &HashMap<String, String>) -> Result<String> {
+    if !url.contains('{') {
+        return Ok(url.to_string());
+    }
+    
+    let path_start = url_path_start(url);
+    let mut result = String::with_capacity(url.len());
+    let mut rest = url;
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        
+        if rest.starts_with("{{") {
+            result.push_str("{{");
+            rest = &rest[2..];
+            continue;
+        }
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        
+        let name = &rest[1..end];
+        let value = variables.get(name)
+            .ok_or_else(|| Error::MissingData(format!("No path variable '{}' for URL {}", name, url)))?;
+        if url.len() - rest.len() >= path_start {
+            result.push_str(&encode_url_value(value));
+        } else {
+            result.push_str(value);
+        }
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+    
+    Ok(result)
+} 
+
+/// Expand `${VAR}` and `${VAR:-default}` references to environment variables
+///
+/// A variable that is not set (and has no default) is an error rather than an empty string.
+/// The default also replaces a variable set to an empty string, and `$${` is a literal `${`.
+fn expand_env(text: &str) -> std::result::Result<String, String> {
+    if !text.contains("${") {
+        return Ok(text.to_string());
+    }
+    
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('$') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if let Some(after) = rest.strip_prefix("$${") {
+            result.push_str("${");
+            rest = after;
+            continue;
+        }
+        let Some(after) = rest.strip_prefix("${") else {
+            result.push('$');
+            rest = &rest[1..];
+            continue;
+        };
+        let Some(end) = after.find('}') else {
+            return Err(format!("unterminated environment variable reference in '{}'", text));
+        };
+        
+        let (name, default) = match after[..end].split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (&after[..end], None),
+        };
+        match (std::env::var(name), default) {
+            (Ok(value), Some(default)) if value.is_empty() => result.push_str(default),
+            (Ok(value), _) => result.push_str(&value),
+            (Err(_), Some(default)) => result.push_str(default),
+            (Err(_), None) => return Err(format!("environment variable '{}' is not set", name)),
+        }
+        rest = &after[end + 1..];
+    }
+    result.push_str(rest);
+    
+    Ok(result)
+}
+
+/// Expand environment variable references in every string of a JSON value
+pub(crate) fn expand_env_value(value: &mut Value) -> std::result::Result<(), String> {
+    match value {
+        Value::String(text) => *text = expand_env(text)?,
+        Value::Array(items) => items.iter_mut().try_for_each(expand_env_value)?,
+        Value::Object(object) => object.values_mut().try_for_each(expand_env_value)?,
+        _ => {},
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    
+    #[test]
+    fn path_variables_are_percent_encoded_in_the_path_and_query() {
+        let variables = HashMap::from([
+            ("host".to_string(), "api.example.com:8080".to_string()),
+            ("name".to_string(), "a b/c?d".to_string()),
+        ]);
+        
+        assert_eq!(substitute_path_variables("http://{host}/users/{name}?q={name}", &variables).unwrap(),
+                   "http://api.example.com:8080/users/a%20b%2Fc%3Fd?q=a%20b%2Fc%3Fd");
+        assert_eq!(substitute_path_variables("{host}/{{name}}/{name}", &variables).unwrap(),
+                   "api.example.com:8080/{{name}}/a%20b%2Fc%3Fd");
+        assert_eq!(url_path_start("http://localhost"), 16);
+    }
+    
+    #[test]
+    fn env_references_expand_set_unset_and_empty_variables() {
+        std::env::set_var("PRESSR_TEST_TOKEN", "secret");
+        std::env::set_var("PRESSR_TEST_EMPTY", "");
+        std::env::remove_var("PRESSR_TEST_UNSET");
+        
+        assert_eq!(expand_env("Bearer ${PRESSR_TEST_TOKEN}").unwrap(), "Bearer secret");
+        assert_eq!(expand_env("${PRESSR_TEST_UNSET:-fallback}").unwrap(), "fallback");
+        assert_eq!(expand_env("${PRESSR_TEST_EMPTY:-fallback}").unwrap(), "fallback");
+        assert_eq!(expand_env("[${PRESSR_TEST_EMPTY}]").unwrap(), "[]");
+        assert!(expand_env("${PRESSR_TEST_UNSET}").unwrap_err().contains("PRESSR_TEST_UNSET"));
+        assert!(expand_env("${PRESSR_TEST_TOKEN").is_err());
+    }
+    
+    #[test]
+    fn escaped_env_references_stay_literal() {
+        std::env::set_var("PRESSR_TEST_PRICE", "5");
+        
+        assert_eq!(expand_env("$${PRESSR_TEST_PRICE} is ${PRESSR_TEST_PRICE}$").unwrap(), "${PRESSR_TEST_PRICE} is 5$");
+    }
+    
+    #[test]
+    fn env_references_expand_in_scenario_steps() {
+        std::env::set_var("PRESSR_TEST_HOST", "api.example.com");
+        let mut data: RequestData = serde_json::from_value(serde_json::json!({
+            "scenario": [{
+                "url": "https://${PRESSR_TEST_HOST}/login",
+                "headers": { "x-host": "${PRESSR_TEST_HOST}" },
+                "body": { "host": ["${PRESSR_TEST_HOST}"] },
+            }]
+        })).unwrap();
+        data.expand_env_vars().unwrap();
+        
+        let step = &data.scenario[0];
+        assert_eq!(step.url, "https://api.example.com/login");
+        assert_eq!(step.headers["x-host"], "api.example.com");
+        assert_eq!(step.body, Some(serde_json::json!({ "host": ["api.example.com"] })));
+    }
+    
+    fn users(strategy: VariableStrategy) -> RequestData {
+        RequestData {
+            variables: HashMap::from([("user".to_string(), vec!["a".to_string(), "b".to_string(), "c".to_string()])]),
+            variable_strategy: strategy,
+            ..Default::default()
+        }
+    }
+    
+    #[test]
+    fn sequential_and_once_strategies_visit_the_values_in_order() {
+        let picks = |data: &RequestData| (0..5)
+            .map(|index| data.select_variable("user", index, &mut rand::thread_rng()).unwrap_or("-"))
+            .collect::<String>();
+        
+        let sequential = users(VariableStrategy::Sequential);
+        assert_eq!(picks(&sequential), "abcab");
+        assert_eq!(sequential.request_limit(), None);
+        
+        let once = users(VariableStrategy::Once);
+        assert_eq!(picks(&once), "abc--");
+        assert_eq!(once.request_limit(), Some(3));
+    }
+    
+    #[test]
+    fn random_picks_follow_the_seed() {
+        let data = users(VariableStrategy::Random);
+        let picks = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..32).map(|index| data.select_variable("user", index, &mut rng).unwrap()).collect::<String>()
+        };
+        
+        assert_eq!(picks(7), picks(7));
+        assert_ne!(picks(7), picks(8));
+        assert!(picks(7).chars().all(|pick| "abc".contains(pick)));
+    }
+    
+    #[tokio::test]
+    async fn malformed_data_files_report_the_line_and_column() {
+        let path = std::env::temp_dir().join(format!("pressr-malformed-{}.json", std::process::id()));
+        std::fs::write(&path, "{\n  \"variables\": {\n    \"id\": [\"a\", \"b\",, \"c\"]\n  }\n}\n").unwrap();
+        
+        let error = RequestData::from_json_file(&path).await.unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(error.data_location(), Some((path.as_path(), 3, 21)));
+        assert!(error.to_string().contains("line 3 column 21"), "{}", error);
+    }
+}
@@ -1,5 +1,5 @@
 use thiserror::Error;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Result type for pressr-core
 pub type Result<T> = std::result::Result<T, Error>;
@@ -13,6 +13,12 @@ pub enum Error {
     #[error("HTTP client error: {0}")]
     HttpClient(#[from] reqwest::Error),
 
+    #[error("Invalid proxy URL '{url}': {source}")]
+    InvalidProxy {
+        url: String,
+        source: reqwest::Error,
+    },
+
     #[error("JSON parsing error: {0}")]
     Json(#[from] serde_json::Error),
 
@@ -41,4 +47,28 @@ pub enum Error {
 
     #[error("{0}")]
     Other(String),
-} 
\ No newline at end of file
+}
+
+impl Error {
+    /// Path, line and column (1-based) of a syntax error in a data file, if known
+    pub fn data_location(&self) -> Option<(&Path, usize, usize)> {
+        let Error::DataLoad { path, source } = self else {
+            return None;
+        };
+        
+        if let Some(e) = source.downcast_ref::<serde_json::Error>() {
+            // I/O errors have no position (line 0)
+            if e.line() > 0 {
+                return Some((path, e.line(), e.column()));
+            }
+        }
+        if let Some(location) = source.downcast_ref::<serde_yaml::Error>().and_then(|e| e.location()) {
+            return Some((path, location.line(), location.column()));
+        }
+        if let Some(position) = source.downcast_ref::<csv::Error>().and_then(|e| e.position()) {
+            return Some((path, position.line() as usize, 1));
+        }
+        
+        None
+    }
+}
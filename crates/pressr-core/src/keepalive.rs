@@ -0,0 +1,160 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::result::RequestResult;
+
+/// A connection held open by a keep-alive worker, from its first to its last ping
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionLifetime {
+    /// Worker that held the connection
+    pub worker: usize,
+
+    /// Local port of the connection
+    pub local_port: u16,
+
+    /// Start of the first ping on the connection, in milliseconds since the start of the run
+    pub opened_at: u128,
+
+    /// End of the last ping on the connection, in milliseconds since the start of the run
+    pub last_used_at: u128,
+
+    /// Time between the first and the last use of the connection in milliseconds
+    pub lifetime: u128,
+
+    /// Number of pings answered on the connection
+    pub pings: usize,
+
+    /// Start of the ping that found the connection gone, in milliseconds since the start of
+    /// the run (None if the connection was still open at the end of the run)
+    pub dropped_at: Option<u128>,
+}
+
+/// Summary of an idle-connection stability run (see `Runner::run_keepalive`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeepAliveStats {
+    /// Number of pings answered
+    pub pings: usize,
+
+    /// Number of connections dropped between two pings
+    pub drops: usize,
+
+    /// Average lifetime of the dropped connections in milliseconds (None without drops)
+    pub average_dropped_lifetime: Option<f64>,
+
+    /// Shortest lifetime of a dropped connection in milliseconds (None without drops)
+    pub min_dropped_lifetime: Option<u128>,
+
+    /// Every connection the workers held, by worker and in the order they were opened
+    pub connections: Vec<ConnectionLifetime>,
+}
+
+impl KeepAliveStats {
+    /// Calculate the connection lifetimes from the pings of a keep-alive run
+    ///
+    /// Each worker pings over a single connection, so a ping answered on a different
+    /// connection (told apart by its local port) than the worker's previous ping means the
+    /// previous connection was dropped while idle. Pings without a response are skipped.
+    pub fn from_requests(requests: &[RequestResult]) -> Self {
+        let mut workers: BTreeMap<usize, Vec<&RequestResult>> = BTreeMap::new();
+        for request in requests.iter().filter(|r| r.local_port.is_some() && r.started_at.is_some()) {
+            workers.entry(request.worker.unwrap_or_default()).or_default().push(request);
+        }
+
+        let mut connections: Vec<ConnectionLifetime> = Vec::new();
+        for (worker, mut pings) in workers {
+            pings.sort_by_key(|ping| (ping.index, ping.started_at));
+            let first = connections.len();
+            for ping in pings {
+                let local_port = ping.local_port.expect("pings with a local port");
+                let started_at = ping.started_at.expect("pings with a start");
+                let ended_at = started_at + ping.response_time;
+
+                match connections[first..].last_mut() {
+                    Some(connection) if connection.local_port == local_port => {
+                        connection.last_used_at = ended_at;
+                        connection.lifetime = ended_at - connection.opened_at;
+                        connection.pings += 1;
+                    },
+                    previous => {
+                        if let Some(previous) = previous {
+                            previous.dropped_at = Some(started_at);
+                        }
+                        connections.push(ConnectionLifetime {
+                            worker,
+                            local_port,
+                            opened_at: started_at,
+                            last_used_at: ended_at,
+                            lifetime: ping.response_time,
+                            pings: 1,
+                            dropped_at: None,
+                        });
+                    },
+                }
+            }
+        }
+
+        let dropped: Vec<u128> = connections.iter()
+            .filter(|connection| connection.dropped_at.is_some())
+            .map(|connection| connection.lifetime)
+            .collect();
+        Self {
+            pings: connections.iter().map(|connection| connection.pings).sum(),
+            drops: dropped.len(),
+            average_dropped_lifetime: (!dropped.is_empty())
+                .then(|| dropped.iter().sum::<u128>() as f64 / dropped.len() as f64),
+            min_dropped_lifetime: dropped.iter().copied().min(),
+            connections,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ping(index: usize, worker: usize, started_at: u128, local_port: Option<u16>) -> RequestResult {
+        RequestResult {
+            index: Some(index),
+            worker: Some(worker),
+            started_at: Some(started_at),
+            response_time: 5,
+            local_port,
+            success: local_port.is_some(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn a_new_connection_marks_the_previous_one_dropped() {
+        let requests = vec![
+            ping(0, 0, 0, Some(1000)),
+            ping(1, 1, 0, Some(2000)),
+            ping(2, 0, 100, Some(1000)),
+            ping(3, 1, 100, Some(2000)),
+            ping(4, 0, 200, None),
+            ping(5, 1, 200, Some(2000)),
+            ping(6, 0, 300, Some(1001)),
+            ping(7, 1, 300, Some(2000)),
+        ];
+        let stats = KeepAliveStats::from_requests(&requests);
+
+        assert_eq!(stats.pings, 7);
+        assert_eq!(stats.drops, 1);
+        assert_eq!(stats.connections.len(), 3);
+        assert_eq!(stats.connections[0], ConnectionLifetime {
+            worker: 0,
+            local_port: 1000,
+            opened_at: 0,
+            last_used_at: 105,
+            lifetime: 105,
+            pings: 2,
+            dropped_at: Some(300),
+        });
+        assert_eq!(stats.connections[1].dropped_at, None);
+        assert_eq!(stats.connections[2].worker, 1);
+        assert_eq!(stats.connections[2].lifetime, 305);
+        assert_eq!(stats.average_dropped_lifetime, Some(105.0));
+        assert_eq!(stats.min_dropped_lifetime, Some(105));
+    }
+}
@@ -8,10 +8,26 @@ mod data;
 mod runner;
 mod result;
 mod report;
+mod percentile;
+mod checkpoint;
+mod template;
+mod conditional;
+mod keepalive;
+mod comparison;
+mod metrics;
+#[cfg(test)]
+mod test_server;
 
 // Re-export public API
 pub use error::{Error, Result};
-pub use data::{RequestData};
-pub use runner::{Runner, Config};
-pub use result::{RequestResult, LoadTestResults};
-pub use report::{ReportFormat, ReportOptions, generate_report}; 
\ No newline at end of file
+pub use data::{RequestData, BodyTransform, BodyType, ScenarioStep, StepFailure, VariableStrategy, WeightedBody};
+pub use runner::{Runner, Config, DispatchMode, HttpVersion, Target, DEFAULT_MAX_REDIRECTS, DEFAULT_USER_AGENT, STREAM_CAPACITY, Auth, BurstProfile, RetryMatcher, StopHandle};
+pub use result::{RequestResult, LoadTestResults, Apdex, BurstStats, ConnectionStats, ServerTimingStats, SizeCorrelation, StreamingStats, Intermittency, StatusRun, Thresholds, ThresholdViolation, ErrorTier, ErrorTiers, HostStats, LatencyBand, QueueWaitStats, TimelineSecond, RESULTS_SCHEMA_VERSION, generate_run_id};
+pub use report::{ReportFormat, ReportOptions, DetailSelection, GeneratedReport, generate_report, to_prometheus, write_report};
+pub use percentile::{Percentiles, PercentileMethod, HistogramSettings, EstimatorKind, PercentileEstimator, TDigest};
+pub use checkpoint::Checkpoint;
+pub use template::{TemplateContext, request_seed};
+pub use conditional::{ConditionalStats, fetch_etag};
+pub use keepalive::{ConnectionLifetime, KeepAliveStats};
+pub use comparison::{Change, ComparisonReport, MetricDelta, DEFAULT_REGRESSION_THRESHOLD, compare_results};
+pub use metrics::{MetricsExporter, NoopExporter, FanoutExporter, CallbackExporter, ChannelExporter, ResultCallback}; 
\ No newline at end of file
@@ -0,0 +1,162 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use tokio::sync::mpsc;
+
+use crate::result::RequestResult;
+
+/// Exporter receiving every request result live while the test is running
+///
+/// `record` is called once per request as soon as it completes (in completion
+/// order, potentially from several tasks at once), `flush` once after the run.
+pub trait MetricsExporter: Send + Sync + Debug {
+    /// Record the result of a single request
+    fn record(&self, result: &RequestResult);
+    
+    /// Flush any buffered metrics
+    fn flush(&self) {}
+}
+
+/// Exporter that discards all metrics
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopExporter;
+
+impl MetricsExporter for NoopExporter {
+    fn record(&self, _result: &RequestResult) {}
+}
+
+/// Exporter forwarding every result to several exporters
+#[derive(Debug, Default, Clone)]
+pub struct FanoutExporter {
+    /// Exporters receiving the results, in order
+    exporters: Vec<Arc<dyn MetricsExporter>>,
+}
+
+impl FanoutExporter {
+    /// Create an exporter without any targets
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Add an exporter that receives every result
+    pub fn push(&mut self, exporter: Arc<dyn MetricsExporter>) {
+        self.exporters.push(exporter);
+    }
+    
+    /// Whether no exporters were added
+    pub fn is_empty(&self) -> bool {
+        self.exporters.is_empty()
+    }
+}
+
+impl MetricsExporter for FanoutExporter {
+    fn record(&self, result: &RequestResult) {
+        for exporter in &self.exporters {
+            exporter.record(result);
+        }
+    }
+    
+    fn flush(&self) {
+        for exporter in &self.exporters {
+            exporter.flush();
+        }
+    }
+}
+
+/// Exporter sending every result over a channel, e.g. to drive a live dashboard
+///
+/// The channel is unbounded so a slow receiver never holds up the requests; results
+/// sent after the receiver was dropped are discarded.
+#[derive(Debug, Clone)]
+pub struct ChannelExporter {
+    /// Sending half of the channel
+    sender: mpsc::UnboundedSender<RequestResult>,
+}
+
+impl ChannelExporter {
+    /// Create an exporter and the receiver of the results it sends
+    pub fn new() -> (Self, mpsc::UnboundedReceiver<RequestResult>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        (Self { sender }, receiver)
+    }
+}
+
+impl MetricsExporter for ChannelExporter {
+    fn record(&self, result: &RequestResult) {
+        let _ = self.sender.send(result.clone());
+    }
+}
+
+/// Callback invoked with every request result
+pub type ResultCallback = Arc<dyn Fn(&RequestResult) + Send + Sync>;
+
+/// Exporter calling a closure for every result
+#[derive(Clone)]
+pub struct CallbackExporter {
+    /// The callback
+    callback: ResultCallback,
+}
+
+impl CallbackExporter {
+    /// Create an exporter calling `callback` for every result
+    pub fn new(callback: ResultCallback) -> Self {
+        Self { callback }
+    }
+}
+
+impl Debug for CallbackExporter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CallbackExporter").finish_non_exhaustive()
+    }
+}
+
+impl MetricsExporter for CallbackExporter {
+    fn record(&self, result: &RequestResult) {
+        (self.callback)(result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use reqwest::Client;
+    use crate::runner::{Config, Runner};
+    use crate::test_server::{response, TestServer};
+    
+    /// Exporter keeping the recorded statuses in memory
+    #[derive(Debug, Default)]
+    struct MemoryExporter {
+        statuses: Mutex<Vec<Option<u16>>>,
+        flushed: AtomicBool,
+    }
+    
+    impl MetricsExporter for MemoryExporter {
+        fn record(&self, result: &RequestResult) {
+            assert!(!self.flushed.load(Ordering::SeqCst), "recorded after the flush");
+            self.statuses.lock().unwrap().push(result.status);
+        }
+        
+        fn flush(&self) {
+            self.flushed.store(true, Ordering::SeqCst);
+        }
+    }
+    
+    #[tokio::test]
+    async fn exporters_record_every_result_live_and_flush_once_done() {
+        let server = TestServer::start(|_| response(404, &[], ""));
+        let exporter = Arc::new(MemoryExporter::default());
+        let mut fanout = FanoutExporter::new();
+        fanout.push(exporter.clone());
+        let config = Config { url: server.url.clone(), request_count: 3, ..Default::default() };
+        
+        let results = Runner::new(Client::new(), config, None).unwrap()
+            .with_exporter(Arc::new(fanout))
+            .run().await.unwrap();
+        
+        assert_eq!(results.failed_requests, 3);
+        assert_eq!(*exporter.statuses.lock().unwrap(), [Some(404); 3]);
+        assert!(exporter.flushed.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,492 @@
+//! Response time percentiles and the estimators they are computed with
+
+use crate::result::LoadTestResults;
+use hdrhistogram::Histogram;
+use serde::{Serialize, Deserialize};
+use tracing::warn;
+
+/// How the percentiles of a run are computed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PercentileMethod {
+    /// Streaming estimator used when the percentiles are not computed exactly
+    pub estimator: EstimatorKind,
+    
+    /// Bounds and precision of the HDR histogram (None to auto-select from the data)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub histogram: Option<HistogramSettings>,
+    
+    /// Compute percentiles from the sorted raw response times instead of the estimator
+    ///
+    /// Exact values avoid the histogram's bucket precision and upper bound, which matters
+    /// for extreme percentiles (e.g. p99.99), at the cost of a sorted copy of every
+    /// response time (O(n) memory, O(n log n) time).
+    #[serde(default)]
+    pub exact: bool,
+}
+
+/// Response time percentiles of the successful requests in milliseconds
+///
+/// Every report, threshold and hook reads these values, so they agree with each other.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Percentiles {
+    /// Median
+    pub p50: f64,
+    
+    /// 75th percentile
+    pub p75: f64,
+    
+    /// 90th percentile
+    pub p90: f64,
+    
+    /// 95th percentile
+    pub p95: f64,
+    
+    /// 99th percentile
+    pub p99: f64,
+    
+    /// 99.9th percentile
+    pub p999: f64,
+    
+    /// Number of successful response times the percentiles were computed from
+    #[serde(default)]
+    pub samples: usize,
+    
+    /// How the percentiles were configured to be computed
+    #[serde(default)]
+    pub method: PercentileMethod,
+    
+    /// Settings of the HDR histogram the percentiles were read from (None if no histogram was used)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub histogram: Option<HistogramSettings>,
+}
+
+impl Percentiles {
+    /// Calculate the percentiles with the default method (an HDR histogram with settings
+    /// fitted to the data), or `None` if no request succeeded
+    pub fn from_results(results: &LoadTestResults) -> Option<Self> {
+        Self::calculate(results, PercentileMethod::default())
+    }
+    
+    /// Calculate the percentiles with the given method, or `None` if no request succeeded
+    pub fn calculate(results: &LoadTestResults, method: PercentileMethod) -> Option<Self> {
+        if method.exact {
+            let mut times: Vec<u128> = results.requests.iter()
+                .filter(|r| r.success)
+                .map(|r| r.response_time)
+                .collect();
+            if times.is_empty() {
+                return None;
+            }
+            times.sort_unstable();
+            return Some(Self::read(|p| nearest_rank(&times, p) as f64, times.len(), method, None));
+        }
+        
+        match method.estimator {
+            EstimatorKind::HdrHistogram => {
+                let settings = method.histogram.unwrap_or_else(|| HistogramSettings::from_results(results));
+                let histogram = create_histogram(results, settings)?;
+                Some(Self::read(|p| histogram.value_at_percentile(p) as f64, histogram.len() as usize, method, Some(settings)))
+            },
+            EstimatorKind::TDigest => {
+                let digest = create_tdigest(results)?;
+                Some(Self::read(|p| digest.value_at_percentile(p), digest.len() as usize, method, None))
+            },
+        }
+    }
+    
+    /// Value at one of the reported percentiles (50, 75, 90, 95, 99 or 99.9)
+    pub fn get(&self, p: f64) -> Option<f64> {
+        [(50.0, self.p50), (75.0, self.p75), (90.0, self.p90), (95.0, self.p95), (99.0, self.p99), (99.9, self.p999)]
+            .into_iter()
+            .find(|(level, _)| *level == p)
+            .map(|(_, value)| value)
+    }
+    
+    /// Read the reported percentiles from a percentile function
+    fn read(at: impl Fn(f64) -> f64, samples: usize, method: PercentileMethod, histogram: Option<HistogramSettings>) -> Self {
+        Self {
+            p50: at(50.0),
+            p75: at(75.0),
+            p90: at(90.0),
+            p95: at(95.0),
+            p99: at(99.0),
+            p999: at(99.9),
+            samples,
+            method,
+            histogram,
+        }
+    }
+}
+
+/// Nearest-rank value at the given percentile of sorted, non-empty values
+pub(crate) fn nearest_rank(sorted: &[u128], p: f64) -> u128 {
+    sorted[nearest_rank_index(sorted.len(), p)]
+}
+
+/// Index of the nearest-rank value at the given percentile among `len` sorted values
+pub(crate) fn nearest_rank_index(len: usize, p: f64) -> usize {
+    let rank = ((p / 100.0) * len as f64).ceil() as usize;
+    rank.clamp(1, len) - 1
+}
+
+/// Bounds and precision of the latency histogram used for percentiles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistogramSettings {
+    /// Lowest discernible value in milliseconds (at least 1)
+    pub lowest: u64,
+    
+    /// Highest trackable value in milliseconds (larger values are clamped)
+    pub highest: u64,
+    
+    /// Number of significant figures kept for each value (0-5)
+    pub significant_figures: u8,
+}
+
+impl Default for HistogramSettings {
+    fn default() -> Self {
+        Self {
+            lowest: 1,
+            highest: 3_600_000,
+            significant_figures: 3,
+        }
+    }
+}
+
+impl HistogramSettings {
+    /// Select settings that fit the observed response times
+    ///
+    /// The upper bound is twice the slowest response so nothing is clamped, and
+    /// narrow ranges get an extra significant figure for finer percentiles.
+    pub fn from_results(results: &LoadTestResults) -> Self {
+        let highest = (results.max_response_time as u64).saturating_mul(2).max(2);
+        let range = results.max_response_time.saturating_sub(results.min_response_time);
+        
+        Self {
+            lowest: 1,
+            highest,
+            significant_figures: if range < 100 { 4 } else { 3 },
+        }
+    }
+}
+
+/// Streaming percentile estimator used for the percentiles of a run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EstimatorKind {
+    /// HDR histogram with fixed bounds and precision (see `HistogramSettings`)
+    #[default]
+    HdrHistogram,
+    /// t-digest, which needs no bounds and is most accurate at the tails
+    TDigest,
+}
+
+/// Streaming percentile estimator built from the successful response times
+pub enum PercentileEstimator {
+    /// HDR histogram (values outside its bounds are clamped)
+    HdrHistogram(Histogram<u64>),
+    /// t-digest
+    TDigest(TDigest),
+}
+
+impl PercentileEstimator {
+    /// Estimated response time (in milliseconds) at the given percentile (0-100)
+    pub fn value_at_percentile(&self, p: f64) -> f64 {
+        match self {
+            Self::HdrHistogram(hist) => hist.value_at_percentile(p) as f64,
+            Self::TDigest(digest) => digest.value_at_percentile(p),
+        }
+    }
+    
+    /// Number of values recorded
+    pub fn len(&self) -> u64 {
+        match self {
+            Self::HdrHistogram(hist) => hist.len(),
+            Self::TDigest(digest) => digest.len(),
+        }
+    }
+    
+    /// Whether no values were recorded
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Cluster of nearby values in a t-digest
+#[derive(Debug, Clone, Copy)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+/// Merging t-digest (Dunning & Ertl) for percentiles over arbitrary value ranges
+///
+/// Memory is bounded by the compression factor rather than the number or range of
+/// values: centroids are small near the extremes and large around the median, so
+/// tail percentiles stay accurate.
+#[derive(Debug, Clone)]
+pub struct TDigest {
+    compression: f64,
+    centroids: Vec<Centroid>,
+    buffer: Vec<f64>,
+    count: u64,
+    min: f64,
+    max: f64,
+}
+
+impl Default for TDigest {
+    fn default() -> Self {
+        Self::new(100.0)
+    }
+}
+
+impl TDigest {
+    /// Create an empty digest (higher compression keeps more centroids and is more accurate)
+    pub fn new(compression: f64) -> Self {
+        Self {
+            compression: compression.max(10.0),
+            centroids: Vec::new(),
+            buffer: Vec::new(),
+            count: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+    
+    /// Record a value
+    pub fn record(&mut self, value: f64) {
+        self.buffer.push(value);
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        if self.buffer.len() >= (self.compression as usize) * 10 {
+            self.compress();
+        }
+    }
+    
+    /// Number of values recorded
+    pub fn len(&self) -> u64 {
+        self.count
+    }
+    
+    /// Whether no values were recorded
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+    
+    /// Merge buffered values into the centroids
+    pub fn compress(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        
+        let mut all: Vec<Centroid> = self.centroids.drain(..)
+            .chain(self.buffer.drain(..).map(|mean| Centroid { mean, weight: 1.0 }))
+            .collect();
+        all.sort_by(|a, b| a.mean.total_cmp(&b.mean));
+        
+        // k1 scale function: at most one unit of k per centroid
+        let total = self.count as f64;
+        let delta = self.compression;
+        let k = |q: f64| delta / (2.0 * std::f64::consts::PI) * (2.0 * q.clamp(0.0, 1.0) - 1.0).asin();
+        
+        let mut merged = Vec::with_capacity(all.len());
+        let mut current = all[0];
+        let mut weight_before = 0.0;
+        for next in all.into_iter().skip(1) {
+            let combined = current.weight + next.weight;
+            if k((weight_before + combined) / total) - k(weight_before / total) <= 1.0 {
+                current.mean += (next.mean - current.mean) * next.weight / combined;
+                current.weight = combined;
+            } else {
+                weight_before += current.weight;
+                merged.push(current);
+                current = next;
+            }
+        }
+        merged.push(current);
+        self.centroids = merged;
+    }
+    
+    /// Estimated value at the given percentile (0-100), 0 if no values were recorded
+    ///
+    /// Interpolates linearly between centroid midpoints, and towards the exact minimum
+    /// and maximum beyond the outermost centroids.
+    pub fn value_at_percentile(&self, p: f64) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        if !self.buffer.is_empty() {
+            let mut digest = self.clone();
+            digest.compress();
+            return digest.value_at_percentile(p);
+        }
+        
+        let target = (p / 100.0).clamp(0.0, 1.0) * self.count as f64;
+        let first = self.centroids[0];
+        if target <= first.weight / 2.0 {
+            return self.min + (first.mean - self.min) * target / (first.weight / 2.0);
+        }
+        
+        let mut center = first.weight / 2.0;
+        for pair in self.centroids.windows(2) {
+            let next_center = center + (pair[0].weight + pair[1].weight) / 2.0;
+            if target <= next_center {
+                let fraction = (target - center) / (next_center - center);
+                return pair[0].mean + (pair[1].mean - pair[0].mean) * fraction;
+            }
+            center = next_center;
+        }
+        
+        let last = self.centroids[self.centroids.len() - 1];
+        let remaining = (self.count as f64 - center).max(f64::EPSILON);
+        last.mean + (self.max - last.mean) * ((target - center) / remaining).min(1.0)
+    }
+}
+
+/// Create a histogram from the response times
+pub fn create_histogram(results: &LoadTestResults, settings: HistogramSettings) -> Option<Histogram<u64>> {
+    if results.requests.is_empty() {
+        return None;
+    }
+    
+    // Create histogram with the configured bounds and precision
+    let lowest = settings.lowest.max(1);
+    let highest = settings.highest.max(lowest * 2);
+    let mut hist = match Histogram::<u64>::new_with_bounds(lowest, highest, settings.significant_figures.min(5)) {
+        Ok(hist) => hist,
+        Err(e) => {
+            warn!("Invalid histogram settings {:?}: {:?}", settings, e);
+            return None;
+        }
+    };
+    
+    // Record response times (in milliseconds), clamping values outside the bounds
+    for result in &results.requests {
+        if result.success {
+            hist.saturating_record(result.response_time as u64);
+        }
+    }
+    
+    if !hist.is_empty() {
+        Some(hist)
+    } else {
+        None
+    }
+}
+
+/// Create a t-digest from the response times
+pub fn create_tdigest(results: &LoadTestResults) -> Option<TDigest> {
+    let mut digest = TDigest::default();
+    for result in &results.requests {
+        if result.success {
+            digest.record(result.response_time as f64);
+        }
+    }
+    digest.compress();
+    
+    if digest.is_empty() {
+        None
+    } else {
+        Some(digest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::result::RequestResult;
+    use std::time::Duration;
+    
+    fn results(times: impl IntoIterator<Item = u128>) -> LoadTestResults {
+        let requests = times.into_iter()
+            .map(|response_time| RequestResult { response_time, success: true, ..Default::default() })
+            .collect();
+        LoadTestResults::new(requests, Duration::from_secs(1))
+    }
+    
+    #[test]
+    fn exact_percentiles_use_the_nearest_rank() {
+        let exact = PercentileMethod { exact: true, ..Default::default() };
+        let results = results(1..=200).with_percentile_method(exact);
+        let percentiles = results.percentiles.unwrap();
+        
+        assert_eq!(percentiles.p50, 100.0);
+        assert_eq!(percentiles.p999, 200.0);
+        assert_eq!(percentiles.samples, 200);
+        assert_eq!(percentiles.method, exact);
+        assert_eq!(percentiles.histogram, None);
+        assert_eq!(percentiles.get(95.0), Some(190.0));
+        assert_eq!(percentiles.get(42.0), None);
+    }
+    
+    #[test]
+    fn default_percentiles_record_the_fitted_histogram() {
+        let results = results(1..=200);
+        let percentiles = results.percentiles.unwrap();
+        
+        assert_eq!(percentiles.method, PercentileMethod::default());
+        assert_eq!(percentiles.histogram, Some(HistogramSettings::from_results(&results)));
+        assert_eq!(percentiles.samples, 200);
+    }
+    
+    #[test]
+    fn merged_results_keep_the_percentile_method() {
+        let tdigest = PercentileMethod { estimator: EstimatorKind::TDigest, ..Default::default() };
+        let first = results(1..=50).with_percentile_method(tdigest);
+        let merged = first.merge(&results(51..=100));
+        let percentiles = merged.percentiles.unwrap();
+        
+        assert_eq!(percentiles.method, tdigest);
+        assert_eq!(percentiles.samples, 100);
+    }
+    
+    #[test]
+    fn more_significant_figures_separate_a_tight_cluster() {
+        let histogram = |significant_figures| PercentileMethod {
+            histogram: Some(HistogramSettings { lowest: 1, highest: 3_600_000, significant_figures }),
+            ..Default::default()
+        };
+        let coarse = results(10_000..=10_020).with_percentile_method(histogram(1)).percentiles.unwrap();
+        let fine = results(10_000..=10_020).with_percentile_method(histogram(5)).percentiles.unwrap();
+        
+        assert_eq!(coarse.p50, coarse.p99);
+        assert!(fine.p50 < fine.p90 && fine.p90 < fine.p99, "{:?}", fine);
+        assert_eq!(fine.histogram.map(|settings| settings.significant_figures), Some(5));
+    }
+    
+    #[test]
+    fn exact_percentiles_capture_outliers_beyond_the_histogram() {
+        let times = std::iter::repeat_n(10, 995).chain([4_000_000, 4_500_000, 5_000_000, 5_500_000, 6_000_000]);
+        let histogram = PercentileMethod {
+            histogram: Some(HistogramSettings { lowest: 1, highest: 3_600_000, significant_figures: 2 }),
+            ..Default::default()
+        };
+        let bucketed = results(times.clone()).with_percentile_method(histogram).percentiles.unwrap();
+        let exact = results(times).with_percentile_method(PercentileMethod { exact: true, ..Default::default() })
+            .percentiles.unwrap();
+        
+        assert_eq!(exact.p99, 10.0);
+        assert!(exact.p999 >= 5_500_000.0, "{:?}", exact);
+        // The histogram clamps the outliers to its highest trackable value
+        assert!(bucketed.p999 < 4_500_000.0, "{:?}", bucketed);
+        assert_eq!(bucketed.p50, exact.p50);
+    }
+    
+    #[test]
+    fn tdigest_percentiles_track_the_exact_ones_on_skewed_data() {
+        // Exponentially distributed response times (mean 50 ms) with a long tail
+        let times: Vec<u128> = (1..=10_000)
+            .map(|i| (-(1.0 - i as f64 / 10_001.0).ln() * 50.0).round() as u128)
+            .collect();
+        let tdigest = PercentileMethod { estimator: EstimatorKind::TDigest, ..Default::default() };
+        let estimated = results(times.clone()).with_percentile_method(tdigest).percentiles.unwrap();
+        let exact = results(times).with_percentile_method(PercentileMethod { exact: true, ..Default::default() })
+            .percentiles.unwrap();
+        
+        for p in [50.0, 75.0, 90.0, 95.0, 99.0, 99.9] {
+            let (estimate, actual) = (estimated.get(p).unwrap(), exact.get(p).unwrap());
+            assert!((estimate - actual).abs() <= (actual * 0.05).max(1.0), "p{}: {} vs {}", p, estimate, actual);
+        }
+        assert_eq!(estimated.samples, 10_000);
+        assert_eq!(estimated.histogram, None);
+    }
+}
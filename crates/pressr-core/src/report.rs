@@ -1,10 +1,18 @@
-use crate::result::{LoadTestResults, RequestResult};
+use crate::comparison::{compare_results, DEFAULT_REGRESSION_THRESHOLD};
+use crate::conditional::ConditionalStats;
+use crate::keepalive::KeepAliveStats;
+use crate::result::{Apdex, BurstStats, ConnectionStats, ErrorTier, ErrorTiers, HostStats, LatencyBand, LoadTestResults, Intermittency, QueueWaitStats, RequestResult, ServerTimingStats, SizeCorrelation, StreamingStats, ThresholdViolation, Thresholds};
 use crate::error::{Error, Result};
-use hdrhistogram::Histogram;
+use crate::percentile::{nearest_rank_index, EstimatorKind};
+use base64::prelude::*;
 use plotters::prelude::*;
-use std::collections::HashMap;
+use rand::rngs::StdRng;
+use rand::seq::index;
+use rand::SeedableRng;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
 use std::fs::{self, File};
-use std::io::Write;
+use std::io::{BufWriter, Write};
 use tracing::{debug, info, instrument, warn};
 use serde::Serialize;
 use chrono;
@@ -20,6 +28,12 @@ pub enum ReportFormat {
     Html,
     /// SVG (histograms only)
     Svg,
+    /// GitHub-flavored Markdown (summary tables, e.g. for PR comments)
+    Markdown,
+    /// JUnit XML (thresholds as test cases, for CI test reporting)
+    Junit,
+    /// Prometheus text exposition format (e.g. for node_exporter's textfile collector)
+    Prometheus,
 }
 
 /// Report output options
@@ -37,8 +51,56 @@ pub struct ReportOptions {
     /// Whether to include detailed per-request information
     pub include_details: bool,
     
+    /// Maximum number of requests listed in the detailed section (None for all)
+    pub detail_limit: Option<usize>,
+    
+    /// Which requests are listed when `detail_limit` is set
+    pub detail_selection: DetailSelection,
+    
+    /// Seed of the random detail sample, e.g. the run's seed (None for a fresh sample
+    /// every report)
+    pub seed: Option<u64>,
+    
     /// Custom output directory (None for default 'reports/')
     pub output_dir: Option<String>,
+    
+    /// Embed the logo into HTML reports instead of copying `pressr-logo.png` next to them
+    /// (Chart.js is still loaded from its CDN)
+    pub inline_assets: bool,
+    
+    /// Maximum number of failed requests listed in JSON reports
+    pub max_failures: usize,
+    
+    /// Show only the most frequent status codes and errors, folding the rest into an
+    /// "other" entry (None to show all)
+    pub max_categories: Option<usize>,
+    
+    /// Results of an earlier run overlaid on the HTML report's latency charts, with a delta table
+    pub baseline: Option<LoadTestResults>,
+    
+    /// Relative change (in percent) beyond which a metric in the delta table counts as
+    /// improved or regressed
+    pub regression_threshold: f64,
+    
+    /// Add a section to text reports showing how the headline numbers were computed
+    pub explain: bool,
+    
+    /// Capacity of the buffer the report file is written through (in bytes)
+    pub write_buffer_size: usize,
+}
+
+/// Requests kept for the detailed per-request section of a report
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DetailSelection {
+    /// The first requests of the run (biased towards cold-start behavior)
+    #[default]
+    First,
+    /// A uniform random sample of the requests
+    Random,
+    /// Only failed requests
+    Errors,
+    /// The slowest requests, slowest first
+    Slowest,
 }
 
 impl Default for ReportOptions {
@@ -48,85 +110,133 @@ impl Default for ReportOptions {
             output_file: None,
             include_histograms: true,
             include_details: false,
+            detail_limit: None,
+            detail_selection: DetailSelection::First,
+            seed: None,
             output_dir: None,
+            inline_assets: false,
+            max_failures: 100,
+            max_categories: None,
+            baseline: None,
+            regression_threshold: DEFAULT_REGRESSION_THRESHOLD,
+            explain: false,
+            write_buffer_size: DEFAULT_WRITE_BUFFER_SIZE,
         }
     }
 }
 
+/// Default capacity of the report file buffer
+const DEFAULT_WRITE_BUFFER_SIZE: usize = 64 * 1024;
+
 const HTML_TEMPLATE: &str = include_str!("../templates/report.html");
 
+/// Logo embedded into HTML reports generated with `inline_assets`
+const LOGO_PNG: &[u8] = include_bytes!("../../../assets/images/pressr-logo.png");
+
 /// Preprocessed data for report generation
 pub struct PreprocessedData<'a> {
     /// Reference to the original results
     pub results: &'a LoadTestResults,
-    /// Calculated histogram (if available)
-    pub histogram: Option<Histogram<u64>>,
 }
 
 impl<'a> PreprocessedData<'a> {
-    /// Create a new PreprocessedData instance with calculated histogram and percentiles
+    /// Create a new PreprocessedData instance
     pub fn new(results: &'a LoadTestResults) -> Self {
-        // Calculate histogram once
-        let histogram = create_histogram(results);
-        
-        Self {
-            results,
-            histogram,
-        }
+        Self { results }
     }
     
-    /// Get percentile value
+    /// Reported percentile value (50, 75, 90, 95, 99 or 99.9), read from the results'
+    /// percentiles so every report shows the same numbers
     pub fn percentile(&self, p: f64) -> Option<f64> {
-        if let Some(hist) = &self.histogram {
-            Some(hist.value_at_percentile(p) as f64)
-        } else {
-            None
-        }
+        self.results.percentiles.and_then(|percentiles| percentiles.get(p))
     }
 }
 
+/// A report written to disk
+#[derive(Debug, Clone)]
+pub struct GeneratedReport {
+    /// Report summary (empty for HTML and SVG reports)
+    pub summary: String,
+    
+    /// Path the report was written to
+    pub path: String,
+}
+
+/// Generate a report, returning its summary (empty for HTML and SVG reports)
+pub fn generate_report(results: &LoadTestResults, options: &ReportOptions) -> Result<String> {
+    write_report(results, options).map(|report| report.summary)
+}
+
 // Disable the warnings for instrument macro as it's an environmental issue
 #[allow(warnings)]
 #[instrument(skip(results, options))]
-pub fn generate_report(results: &LoadTestResults, options: &ReportOptions) -> Result<String> {
+pub fn write_report(results: &LoadTestResults, options: &ReportOptions) -> Result<GeneratedReport> {
     info!("Generating {:?} report for load test with {} requests", 
           options.format, results.total_requests);
     
     // Preprocess data (histogram, percentiles) once
     let preprocessed = PreprocessedData::new(results);
     
-    let report = match options.format {
-        ReportFormat::Text => generate_text_report(&preprocessed, options),
-        ReportFormat::Json => generate_json_report(&preprocessed, options),
-        ReportFormat::Html => generate_html_report(&preprocessed, options),
-        ReportFormat::Svg => generate_histogram_svg(&preprocessed)
-    }?;
-    
     // Get the output path (using the helper function)
-    let output_path = get_output_path(options)?;
+    let output_path = get_output_path(options, &results.run_id)?;
     
-    // For HTML reports, copy the logo file to the reports directory
-    if options.format == ReportFormat::Html {
+    // For HTML reports, copy the logo file to the reports directory (unless it is embedded)
+    if options.format == ReportFormat::Html && !options.inline_assets {
         copy_logo_file(options)?;
     }
     
     debug!("Writing report to: {}", output_path);
-    let mut file = File::create(&output_path)
+    let file = File::create(&output_path)
         .map_err(|e| Error::Io(e))?;
-    file.write_all(report.as_bytes())
+    let mut writer = BufWriter::with_capacity(options.write_buffer_size.max(1), file);
+    
+    // Text and JSON reports stream their per-request details straight to the file and
+    // return only the summary; HTML and SVG reports return an empty string to avoid
+    // cluttering the console
+    let summary = match options.format {
+        ReportFormat::Text => write_text_report(&preprocessed, options, &mut writer)?,
+        ReportFormat::Json => write_json_report(&preprocessed, options, &mut writer)?,
+        ReportFormat::Html => {
+            let report = generate_html_report(&preprocessed, options)?;
+            writer.write_all(report.as_bytes())
+                .map_err(|e| Error::Io(e))?;
+            String::new()
+        },
+        ReportFormat::Svg => {
+            let report = generate_histogram_svg(&preprocessed)?;
+            writer.write_all(report.as_bytes())
+                .map_err(|e| Error::Io(e))?;
+            String::new()
+        },
+        ReportFormat::Markdown => {
+            let report = generate_markdown_report(&preprocessed, options);
+            writer.write_all(report.as_bytes())
+                .map_err(|e| Error::Io(e))?;
+            report
+        },
+        ReportFormat::Junit => {
+            let report = generate_junit_report(results);
+            writer.write_all(report.as_bytes())
+                .map_err(|e| Error::Io(e))?;
+            report
+        },
+        ReportFormat::Prometheus => {
+            let report = to_prometheus(results);
+            writer.write_all(report.as_bytes())
+                .map_err(|e| Error::Io(e))?;
+            report
+        }
+    };
+    
+    writer.flush()
         .map_err(|e| Error::Io(e))?;
     info!("Report written to {}", output_path);
     
-    // For HTML and SVG reports, return an empty string to avoid cluttering the console
-    if options.format == ReportFormat::Html || options.format == ReportFormat::Svg {
-        Ok(String::new())
-    } else {
-        Ok(report)
-    }
+    Ok(GeneratedReport { summary, path: output_path })
 }
 
 /// Get output file path based on options
-fn get_output_path(options: &ReportOptions) -> Result<String> {
+fn get_output_path(options: &ReportOptions, run_id: &str) -> Result<String> {
     // Get the project root directory (or working directory)
     let project_root = std::env::current_dir()
         .map_err(|e| Error::Io(e))?;
@@ -157,20 +267,29 @@ fn get_output_path(options: &ReportOptions) -> Result<String> {
         // Place in the specified output directory
         format!("{}/{}", base_dir, filename)
     } else {
-        // Auto-generate filename with format "report_N.ext"
+        // Auto-generate filename with format "report_<run id>.ext" (or "report_N.ext"
+        // for results without a run ID)
         let extension = match options.format {
             ReportFormat::Text => "txt",
             ReportFormat::Json => "json",
             ReportFormat::Html => "html",
             ReportFormat::Svg => "svg",
+            ReportFormat::Markdown => "md",
+            ReportFormat::Junit => "xml",
+            ReportFormat::Prometheus => "prom",
         };
         
-        // Find first available filename (report_1.html, report_2.html, etc.)
+        // Find first available filename (report_1.html, report_2.html, etc., or
+        // report_<run id>.html, report_<run id>_2.html, etc.)
         let mut counter = 1;
         let mut output_path;
         
         loop {
-            output_path = format!("{}/report_{}.{}", base_dir, counter, extension);
+            output_path = match (run_id.is_empty(), counter) {
+                (true, _) => format!("{}/report_{}.{}", base_dir, counter, extension),
+                (false, 1) => format!("{}/report_{}.{}", base_dir, run_id, extension),
+                (false, _) => format!("{}/report_{}_{}.{}", base_dir, run_id, counter, extension),
+            };
             
             // Check if file already exists
             if !std::path::Path::new(&output_path).exists() {
@@ -237,20 +356,31 @@ fn copy_logo_file(options: &ReportOptions) -> Result<()> {
 
 // Disable the warnings for instrument macro
 #[allow(warnings)]
-#[instrument(skip(preprocessed, options))]
-fn generate_text_report(preprocessed: &PreprocessedData, options: &ReportOptions) -> Result<String> {
+#[instrument(skip(preprocessed, options, out))]
+fn write_text_report<W: Write>(preprocessed: &PreprocessedData, options: &ReportOptions, out: &mut W) -> Result<String> {
     debug!("Generating text report");
     let results = preprocessed.results;
     let mut report = String::new();
     
     // Header
     report.push_str(&format!("LOAD TEST REPORT\n"));
+    if !results.run_id.is_empty() {
+        report.push_str(&format!("Run ID: {}\n", results.run_id));
+    }
+    if let Some(sni) = &results.sni {
+        report.push_str(&format!("SNI: {}\n", sni));
+    }
     report.push_str(&format!("Requests: {}\n", results.total_requests));
     report.push_str("\n");
     
     // Summary
     report.push_str("SUMMARY\n");
     report.push_str(&format!("Total requests:     {}\n", results.total_requests));
+    report.push_str(&format!("Requests:           configured: {}, attempted: {}, completed: {}\n",
+        results.configured_requests,
+        results.attempted_requests,
+        results.completed_requests()
+    ));
     report.push_str(&format!("Successful:        {} ({:.1}%)\n", 
         results.successful_requests, 
         percentage(results.successful_requests, results.total_requests)
@@ -259,12 +389,71 @@ fn generate_text_report(preprocessed: &PreprocessedData, options: &ReportOptions
         results.failed_requests, 
         percentage(results.failed_requests, results.total_requests)
     ));
+    if results.expected_failures > 0 {
+        report.push_str(&format!("Expected failures: {} ({:.1}%)\n",
+            results.expected_failures,
+            percentage(results.expected_failures, results.total_requests)
+        ));
+    }
+    report.push_str(&format!("Transport errors:  {} ({:.1}%)\n",
+        results.error_tiers.transport,
+        results.error_tiers.transport_percent
+    ));
+    report.push_str(&format!("Application errors: {} ({:.1}%)\n",
+        results.error_tiers.application,
+        results.error_tiers.application_percent
+    ));
+    if results.truncated_responses > 0 {
+        report.push_str(&format!("Truncated bodies:  {} (body read limit reached)\n", results.truncated_responses));
+    }
+    if results.cancelled_requests > 0 {
+        report.push_str(&format!("Cancelled:         {} (in flight at shutdown)\n", results.cancelled_requests));
+    }
+    if let Some(reason) = &results.aborted {
+        report.push_str(&format!("Aborted:           {}\n", reason));
+    }
+    if results.retries > 0 || results.retry_budget_exhausted {
+        report.push_str(&format!("Retries:           {}{}\n",
+            results.retries,
+            if results.retry_budget_exhausted { " (retry budget exhausted)" } else { "" }
+        ));
+    }
+    if let Some(connections) = &results.connections {
+        report.push_str(&format!("New connections:   {} ({:.1}% of requests, {} distinct source ports)\n",
+            connections.new_connections,
+            connections.new_connection_percent,
+            connections.distinct_ports
+        ));
+        if let Some(setup) = connections.average_setup {
+            report.push_str(&format!("Connection setup:  {:.2} ms (estimated from TTFB)\n", setup));
+        }
+    }
+    if let Some(apdex) = &results.apdex {
+        report.push_str(&format!("Apdex (T={} ms):   {:.2} ({} satisfied, {} tolerating, {} frustrated)\n",
+            apdex.threshold_ms,
+            apdex.score,
+            apdex.satisfied,
+            apdex.tolerating,
+            apdex.frustrated
+        ));
+    }
     report.push_str("\n");
     
+    // Pass/fail thresholds
+    if results.thresholds.is_some() {
+        report.push_str("THRESHOLDS\n");
+        report.push_str(&format!("Result:             {}\n", if results.passed { "passed" } else { "FAILED" }));
+        for violation in &results.threshold_violations {
+            report.push_str(&format!("Violated:           {}\n", violation));
+        }
+        report.push_str("\n");
+    }
+    
     // Timing
     report.push_str("TIMING\n");
     report.push_str(&format!("Total duration:     {:.2} s\n", results.duration_secs));
     report.push_str(&format!("Average:            {:.2} ms\n", results.average_response_time));
+    report.push_str(&format!("Median:             {:.2} ms\n", results.median_response_time));
     report.push_str(&format!("Minimum:            {} ms\n", results.min_response_time));
     report.push_str(&format!("Maximum:            {} ms\n", results.max_response_time));
     
@@ -281,82 +470,586 @@ fn generate_text_report(preprocessed: &PreprocessedData, options: &ReportOptions
             report.push_str(&format!("99th percentile:     {:.2} ms\n", p99));
         }
     }
+    if let Some(ttfb) = results.average_ttfb {
+        report.push_str(&format!("Avg TTFB:           {:.2} ms\n", ttfb));
+    }
+    if let Some(server_timing) = &results.server_timing {
+        report.push_str(&format!("Avg server time:    {:.2} ms (overhead {:.2} ms, {} requests)\n",
+            server_timing.average_server_time, server_timing.average_overhead, server_timing.requests));
+    }
+    if let Some(streaming) = &results.streaming {
+        report.push_str(&format!("Avg first chunk:    {:.2} ms\n", streaming.average_first_chunk));
+        report.push_str(&format!("Avg streaming:      {:.2} ms (max {} ms)\n",
+            streaming.average_stream_duration, streaming.max_stream_duration));
+    }
+    report.push_str("\n");
+    
+    // Latency bands
+    if !results.latency_bands.is_empty() {
+        report.push_str("LATENCY BANDS (successful requests)\n");
+        for band in &results.latency_bands {
+            report.push_str(&format!("{:<19} {} ({:.1}%)\n", format!("{}:", band.label()), band.count, band.percent));
+        }
+        report.push_str("\n");
+    }
+    
+    // Throughput
+    report.push_str("THROUGHPUT\n");
+    report.push_str(&format!("Requests/sec:       {:.2}\n", results.throughput));
+    if let Some(rate) = results.transfer_rate {
+        report.push_str(&format!("Transfer rate:      {:.2} KB/s\n", rate / 1024.0));
+    }
+    if let Some(goodput) = results.goodput {
+        report.push_str(&format!("Goodput:            {:.2} KB/s (successful responses only)\n", goodput / 1024.0));
+    }
     report.push_str("\n");
     
     // Status codes
     if !results.status_codes.is_empty() {
         report.push_str("STATUS CODES\n");
         
-        // Sort status codes for consistent output
-        let mut sorted_status_codes: Vec<_> = results.status_codes.iter().collect();
-        sorted_status_codes.sort_by_key(|&(code, _)| *code);
+        // Sort status codes for consistent output (by frequency when capped)
+        let sorted_status_codes = match options.max_categories {
+            Some(_) => cap_categories(&results.status_codes, options.max_categories, "codes"),
+            None => {
+                let mut sorted: Vec<_> = results.status_codes.iter().collect();
+                sorted.sort_by_key(|&(code, _)| *code);
+                sorted.into_iter().map(|(code, count)| (code.to_string(), *count)).collect()
+            }
+        };
         
         for (code, count) in sorted_status_codes {
-            let percent = percentage(*count, results.total_requests);
+            let percent = percentage(count, results.total_requests);
             report.push_str(&format!("{}: {} ({:.1}%)\n", code, count, percent));
         }
         report.push_str("\n");
     }
     
+    // Negotiated HTTP versions
+    if !results.http_versions.is_empty() {
+        report.push_str("HTTP VERSIONS\n");
+        let mut versions: Vec<_> = results.http_versions.iter().collect();
+        versions.sort();
+        for (version, count) in versions {
+            let percent = percentage(*count, results.total_requests);
+            report.push_str(&format!("{}: {} ({:.1}%)\n", version, count, percent));
+        }
+        report.push_str("\n");
+    }
+    
+    // Conditional requests (ETag validation)
+    if let Some(conditional) = &results.conditional {
+        report.push_str("CONDITIONAL REQUESTS\n");
+        report.push_str(&format!("ETag:               {}\n", conditional.etag));
+        report.push_str(&format!("304 hit rate:       {:.1}% ({} not modified)\n",
+            conditional.hit_rate * 100.0,
+            conditional.not_modified
+        ));
+        report.push_str(&format!("Unexpected 200s:    {}\n", conditional.unexpected_full));
+        if let Some(avg) = conditional.avg_not_modified_time {
+            report.push_str(&format!("Avg 304 time:       {:.2} ms\n", avg));
+        }
+        if let Some(avg) = conditional.avg_full_time {
+            report.push_str(&format!("Avg 200 time:       {:.2} ms\n", avg));
+        }
+        report.push_str("\n");
+    }
+    
+    // Keep-alive connection lifetimes
+    if let Some(keepalive) = &results.keepalive {
+        report.push_str("KEEP-ALIVE\n");
+        report.push_str(&format!("Pings answered:     {}\n", keepalive.pings));
+        report.push_str(&format!("Connections:        {}\n", keepalive.connections.len()));
+        report.push_str(&format!("Dropped:            {}\n", keepalive.drops));
+        if let Some(avg) = keepalive.average_dropped_lifetime {
+            report.push_str(&format!("Avg lifetime:       {:.2} ms\n", avg));
+        }
+        if let Some(min) = keepalive.min_dropped_lifetime {
+            report.push_str(&format!("Min lifetime:       {} ms\n", min));
+        }
+        for connection in keepalive.connections.iter().filter(|c| c.dropped_at.is_some()) {
+            report.push_str(&format!("  worker {} port {}: dropped after {} ms ({} pings)\n",
+                connection.worker,
+                connection.local_port,
+                connection.lifetime,
+                connection.pings
+            ));
+        }
+        report.push_str("\n");
+    }
+    
+    // Burst breakdown
+    if !results.bursts.is_empty() {
+        report.push_str("BURSTS\n");
+        for burst in &results.bursts {
+            report.push_str(&format!("Burst #{}: {} requests, {} failed, avg {:.2} ms, min {} ms, max {} ms\n",
+                burst.index + 1,
+                burst.requests,
+                burst.failed,
+                burst.average_response_time,
+                burst.min_response_time,
+                burst.max_response_time
+            ));
+        }
+        report.push_str("\n");
+    }
+    
+    // Body pool distribution
+    if !results.body_usage.is_empty() {
+        report.push_str("BODIES\n");
+        let mut bodies: Vec<_> = results.body_usage.iter().collect();
+        bodies.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        for (body, count) in bodies {
+            report.push_str(&format!("{}: {} ({:.1}%)\n", body, count, percentage(*count, results.total_requests)));
+        }
+        report.push_str("\n");
+    }
+    
+    // Request size correlation
+    if let Some(correlation) = &results.size_correlation {
+        let coefficient = |r: Option<f64>| r.map(|r| format!("{:.3}", r)).unwrap_or_else(|| "n/a".to_string());
+        report.push_str("REQUEST SIZE CORRELATION\n");
+        report.push_str(&format!("Requests:           {}\n", correlation.requests));
+        report.push_str(&format!("Response size (r):  {}\n", coefficient(correlation.response_size)));
+        report.push_str(&format!("Response time (r):  {}\n", coefficient(correlation.response_time)));
+        report.push_str("\n");
+    }
+    
+    // Per-host, per-target and per-step breakdowns
+    for (title, groups) in [("HOSTS", &results.by_host), ("TARGETS", &results.by_target), ("STEPS", &results.by_step)] {
+        if groups.is_empty() {
+            continue;
+        }
+        report.push_str(title);
+        report.push('\n');
+        let mut groups: Vec<_> = groups.iter().collect();
+        groups.sort_by(|a, b| a.0.cmp(b.0));
+        for (name, stats) in groups {
+            report.push_str(&format!("{}: {} requests, {} failed ({:.1}%), avg {:.2} ms, min {} ms, max {} ms\n",
+                name,
+                stats.requests,
+                stats.failed,
+                percentage(stats.failed, stats.requests),
+                stats.average_response_time,
+                stats.min_response_time,
+                stats.max_response_time
+            ));
+        }
+        report.push_str("\n");
+    }
+    
+    // Client-side queuing
+    if let Some(queue_wait) = &results.queue_wait {
+        report.push_str("QUEUE WAIT\n");
+        report.push_str(&format!("Delayed requests:   {} of {}\n", queue_wait.delayed, queue_wait.requests));
+        report.push_str(&format!("Average:            {:.2} ms\n", queue_wait.average));
+        report.push_str(&format!("50th percentile:     {} ms\n", queue_wait.p50));
+        report.push_str(&format!("95th percentile:     {} ms\n", queue_wait.p95));
+        report.push_str(&format!("99th percentile:     {} ms\n", queue_wait.p99));
+        report.push_str(&format!("Maximum:            {} ms\n", queue_wait.max));
+        report.push_str(&format!("Late requests:      {} ({:.1}%)\n", queue_wait.late, queue_wait.late_percent));
+        if let Some(average) = queue_wait.late_average_response_time {
+            report.push_str(&format!("Late latency:       {:.2} ms\n", average));
+        }
+        if let Some(average) = queue_wait.on_time_average_response_time {
+            report.push_str(&format!("On-time latency:    {:.2} ms\n", average));
+        }
+        report.push_str("\n");
+    }
+    
+    // Intermittent failures
+    if let Some(intermittency) = &results.intermittency {
+        report.push_str("INTERMITTENT FAILURES\n");
+        report.push_str(&format!("Status runs:        {}\n", intermittency.run_count));
+        report.push_str(&format!("Failure runs:       {} (longest {}, average {:.1} requests)\n",
+            intermittency.failure_runs, intermittency.longest_failure_run, intermittency.average_failure_run));
+        report.push_str(&format!("Pattern:            {}\n",
+            if intermittency.clustered { "clustered" } else { "spread out" }));
+        report.push_str("\n");
+    }
+    
     // Error summary
     if !results.errors.is_empty() {
         report.push_str("ERRORS\n");
         
-        for (error, count) in &results.errors {
-            let percent = percentage(*count, results.total_requests);
+        for (error, count) in cap_categories(&results.errors, options.max_categories, "errors") {
+            let percent = percentage(count, results.total_requests);
             report.push_str(&format!("{}: {} ({:.1}%)\n", error, count, percent));
         }
         report.push_str("\n");
     }
     
-    // Add detailed per-request information if requested
+    if options.explain {
+        report.push_str(&explain_section(preprocessed));
+    }
+    
+    out.write_all(report.as_bytes())
+        .map_err(|e| Error::Io(e))?;
+    
+    // Stream detailed per-request information if requested, one line at a time
     if options.include_details {
-        report.push_str("REQUEST DETAILS\n");
-        for (i, result) in results.requests.iter().enumerate() {
-            report.push_str(&format!("Request #{}: ", i + 1));
-            if result.success {
-                report.push_str(&format!("Success, Status: {}, ", 
-                                        result.status.map(|s| s.to_string()).unwrap_or_else(|| "None".to_string())));
-            } else {
-                let error_text = result.error
-                    .as_deref()
-                    .unwrap_or("Unknown")
-                    .replace("HTTP Error: ", "");
-                
-                report.push_str(&format!("Failed, Error: {}, ", error_text));
+        write_text_details(&select_details(results, options), out)
+            .map_err(|e| Error::Io(e))?;
+    }
+    
+    debug!("Text report summary generated ({} chars)", report.len());
+    Ok(report)
+}
+
+/// Generate a GitHub-flavored Markdown report: summary and status code tables, with the
+/// percentiles in a fenced block
+fn generate_markdown_report(preprocessed: &PreprocessedData, options: &ReportOptions) -> String {
+    debug!("Generating Markdown report");
+    let results = preprocessed.results;
+    let mut report = String::from("# Load Test Report\n\n");
+    
+    if !results.run_id.is_empty() {
+        report.push_str(&format!("Run ID: `{}`\n\n", results.run_id));
+    }
+    
+    // Summary
+    report.push_str("## Summary\n\n");
+    report.push_str("| Metric | Value |\n");
+    report.push_str("| --- | ---: |\n");
+    let mut row = |metric: &str, value: String| report.push_str(&format!("| {} | {} |\n", metric, value));
+    row("Total requests", results.total_requests.to_string());
+    row("Successful", format!("{} ({:.1}%)", results.successful_requests,
+        percentage(results.successful_requests, results.total_requests)));
+    row("Failed", format!("{} ({:.1}%)", results.failed_requests,
+        percentage(results.failed_requests, results.total_requests)));
+    row("Duration", format!("{:.2} s", results.duration_secs));
+    row("Throughput", format!("{:.2} req/s", results.throughput));
+    row("Average", format!("{:.2} ms", results.average_response_time));
+    row("Median", format!("{:.2} ms", results.median_response_time));
+    row("Minimum", format!("{} ms", results.min_response_time));
+    row("Maximum", format!("{} ms", results.max_response_time));
+    if let Some(apdex) = &results.apdex {
+        row("Apdex", format!("{:.2} (T={} ms)", apdex.score, apdex.threshold_ms));
+    }
+    if results.thresholds.is_some() {
+        row("Thresholds", if results.passed { "passed".to_string() } else { "**FAILED**".to_string() });
+    }
+    report.push('\n');
+    
+    for violation in &results.threshold_violations {
+        report.push_str(&format!("- Violated: {}\n", violation));
+    }
+    if !results.threshold_violations.is_empty() {
+        report.push('\n');
+    }
+    
+    // Percentiles
+    if results.percentiles.is_some() {
+        report.push_str("## Percentiles\n\n```text\n");
+        for (label, p) in [("p50", 50.0), ("p75", 75.0), ("p90", 90.0), ("p95", 95.0), ("p99", 99.0), ("p99.9", 99.9)] {
+            if let Some(value) = preprocessed.percentile(p) {
+                report.push_str(&format!("{:<6} {:.2} ms\n", label, value));
             }
-            report.push_str(&format!("Time: {} ms", result.response_time));
-            if let Some(size) = result.response_size {
-                report.push_str(&format!(", Size: {} bytes", size));
+        }
+        report.push_str("```\n\n");
+    }
+    
+    // Status codes
+    if !results.status_codes.is_empty() {
+        report.push_str("## Status Codes\n\n");
+        report.push_str("| Status | Count | Share |\n");
+        report.push_str("| --- | ---: | ---: |\n");
+        let status_codes = match options.max_categories {
+            Some(_) => cap_categories(&results.status_codes, options.max_categories, "codes"),
+            None => {
+                let mut sorted: Vec<_> = results.status_codes.iter().collect();
+                sorted.sort_by_key(|&(code, _)| *code);
+                sorted.into_iter().map(|(code, count)| (code.to_string(), *count)).collect()
             }
-            report.push_str("\n");
+        };
+        for (code, count) in status_codes {
+            report.push_str(&format!("| {} | {} | {:.1}% |\n", code, count, percentage(count, results.total_requests)));
         }
-        report.push_str("\n");
+        report.push('\n');
     }
     
-    debug!("Text report generated ({} chars)", report.len());
-    Ok(report)
+    // Errors
+    if !results.errors.is_empty() {
+        report.push_str("## Errors\n\n");
+        report.push_str("| Error | Count | Share |\n");
+        report.push_str("| --- | ---: | ---: |\n");
+        for (error, count) in cap_categories(&results.errors, options.max_categories, "errors") {
+            report.push_str(&format!("| {} | {} | {:.1}% |\n",
+                escape_markdown_cell(&error), count, percentage(count, results.total_requests)));
+        }
+        report.push('\n');
+    }
+    
+    report
+}
+
+/// Escape text for a Markdown table cell (pipes would end the cell, newlines the row)
+fn escape_markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+/// Generate a JUnit XML report: one test case per configured threshold, failed when the
+/// threshold was violated, plus a throughput test case summarizing the run
+fn generate_junit_report(results: &LoadTestResults) -> String {
+    debug!("Generating JUnit XML report");
+    let mut cases = Vec::new();
+    
+    if let Some(thresholds) = &results.thresholds {
+        let configured = [
+            ("max_p95_ms", thresholds.max_p95_ms),
+            ("min_throughput", thresholds.min_throughput),
+            ("max_error_rate", thresholds.max_error_rate),
+        ];
+        for (name, limit) in configured {
+            if limit.is_none() {
+                continue;
+            }
+            cases.push(match results.threshold_violations.iter().find(|v| v.threshold == name) {
+                Some(violation) => format!(
+                    "    <testcase classname=\"pressr.thresholds\" name=\"{}\">\n      <failure type=\"threshold\" message=\"{}\"/>\n    </testcase>\n",
+                    name, escape_xml(&violation.to_string())),
+                None => format!("    <testcase classname=\"pressr.thresholds\" name=\"{}\"/>\n", name),
+            });
+        }
+    }
+    
+    cases.push(format!(
+        "    <testcase classname=\"pressr.summary\" name=\"throughput\" time=\"{:.3}\">\n      <system-out>{}</system-out>\n    </testcase>\n",
+        results.duration_secs, escape_xml(&results.summary())));
+    
+    let mut report = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    report.push_str(&format!("<testsuites>\n  <testsuite name=\"pressr\" tests=\"{}\" failures=\"{}\" errors=\"0\" time=\"{:.3}\">\n",
+        cases.len(), results.threshold_violations.len(), results.duration_secs));
+    if !results.run_id.is_empty() {
+        report.push_str(&format!("    <properties>\n      <property name=\"run_id\" value=\"{}\"/>\n    </properties>\n",
+            escape_xml(&results.run_id)));
+    }
+    for case in cases {
+        report.push_str(&case);
+    }
+    report.push_str("  </testsuite>\n</testsuites>\n");
+    report
+}
+
+/// Render the results as metrics in the Prometheus text exposition format
+///
+/// Response time quantiles, sum and count cover the successful requests; the quantiles
+/// are the results' percentiles, like in the other reports.
+pub fn to_prometheus(results: &LoadTestResults) -> String {
+    debug!("Generating Prometheus metrics");
+    let mut metrics = String::new();
+    let mut metric = |name: &str, kind: &str, help: &str, samples: Vec<(String, String)>| {
+        metrics.push_str(&format!("# HELP {} {}\n# TYPE {} {}\n", name, help, name, kind));
+        for (labels, value) in samples {
+            metrics.push_str(&format!("{}{} {}\n", name, labels, value));
+        }
+    };
+    
+    if !results.run_id.is_empty() {
+        metric("pressr_run_info", "gauge", "Run the metrics belong to.",
+            vec![(format!("{{run_id=\"{}\"}}", escape_label_value(&results.run_id)), "1".to_string())]);
+    }
+    metric("pressr_requests_total", "counter", "Completed requests.",
+        vec![(String::new(), results.total_requests.to_string())]);
+    metric("pressr_successful_requests_total", "counter", "Successful requests.",
+        vec![(String::new(), results.successful_requests.to_string())]);
+    metric("pressr_failures_total", "counter", "Failed requests.",
+        vec![(String::new(), results.failed_requests.to_string())]);
+    
+    let mut status_codes: Vec<_> = results.status_codes.iter().collect();
+    status_codes.sort_by_key(|&(code, _)| *code);
+    metric("pressr_status_codes_total", "counter", "Responses by HTTP status code.",
+        status_codes.into_iter()
+            .map(|(code, count)| (format!("{{code=\"{}\"}}", code), count.to_string()))
+            .collect());
+    
+    let mut http_versions: Vec<_> = results.http_versions.iter().collect();
+    http_versions.sort();
+    metric("pressr_http_versions_total", "counter", "Responses by negotiated HTTP version.",
+        http_versions.into_iter()
+            .map(|(version, count)| (format!("{{version=\"{}\"}}", escape_label_value(version)), count.to_string()))
+            .collect());
+    
+    let mut samples: Vec<(String, String)> = results.percentiles
+        .map(|percentiles| [(0.5, percentiles.p50), (0.9, percentiles.p90), (0.95, percentiles.p95), (0.99, percentiles.p99)]
+            .into_iter()
+            .map(|(quantile, ms)| (format!("{{quantile=\"{}\"}}", quantile), (ms / 1000.0).to_string()))
+            .collect())
+        .unwrap_or_default();
+    let successful_time: u128 = results.requests.iter().filter(|r| r.success).map(|r| r.response_time).sum();
+    samples.push(("_sum".to_string(), (successful_time as f64 / 1000.0).to_string()));
+    samples.push(("_count".to_string(), results.successful_requests.to_string()));
+    metric("pressr_response_time_seconds", "summary", "Response time of the successful requests.", samples);
+    
+    metric("pressr_throughput", "gauge", "Requests per second over the run.",
+        vec![(String::new(), results.throughput.to_string())]);
+    metric("pressr_duration_seconds", "gauge", "Duration of the run.",
+        vec![(String::new(), results.duration_secs.to_string())]);
+    if results.thresholds.is_some() {
+        metric("pressr_thresholds_passed", "gauge", "Whether the run met all thresholds (1) or not (0).",
+            vec![(String::new(), u8::from(results.passed).to_string())]);
+    }
+    
+    metrics
+}
+
+/// Escape a Prometheus label value (backslashes, quotes and newlines)
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Escape text for XML attribute values and character data
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than tab and newlines are not allowed in XML 1.0
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {},
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// EXPLAIN section of the text report: the headline numbers with their inputs plugged in
+fn explain_section(preprocessed: &PreprocessedData) -> String {
+    let results = preprocessed.results;
+    let mut section = String::from("EXPLAIN\n");
+    
+    section.push_str(&format!("Throughput = {} requests / {:.3} s = {:.2} req/s\n",
+        results.total_requests, results.duration_secs, results.throughput));
+    section.push_str(&format!("Success rate = {} successful / {} requests x 100 = {:.1}%\n",
+        results.successful_requests, results.total_requests,
+        percentage(results.successful_requests, results.total_requests)));
+    
+    // Cancelled requests have no response time and are left out of the latency figures
+    let total_time: u128 = results.requests.iter()
+        .filter(|r| r.has_latency())
+        .map(|r| r.response_time)
+        .sum();
+    let measured = results.total_requests - results.cancelled_requests;
+    section.push_str(&format!("Average = {} ms total / {} requests = {:.2} ms\n",
+        total_time, measured, results.average_response_time));
+    section.push_str(&format!("Std dev = sqrt(sum of squared deviations from {:.2} ms / ({} - 1)) = {:.2} ms\n",
+        results.average_response_time, measured, results.response_time_std_dev));
+    
+    // Percentiles are taken over the successful requests only
+    match results.percentiles {
+        Some(percentiles) if percentiles.method.exact => {
+            section.push_str(&format!("Percentiles: exact, nearest rank over {} sorted successful samples\n", percentiles.samples));
+            for p in [50.0, 95.0, 99.0] {
+                let rank = nearest_rank_index(percentiles.samples, p) + 1;
+                if let Some(value) = percentiles.get(p) {
+                    section.push_str(&format!("  p{} = sample #{} of {} = {} ms\n", p, rank, percentiles.samples, value));
+                }
+            }
+        },
+        Some(percentiles) => {
+            match (percentiles.method.estimator, percentiles.histogram) {
+                (EstimatorKind::HdrHistogram, Some(settings)) => section.push_str(&format!(
+                    "Percentiles: HDR histogram over {} successful samples ({} significant figures, values above {} ms clamped)\n",
+                    percentiles.samples, settings.significant_figures.min(5), settings.highest)),
+                (EstimatorKind::HdrHistogram, None) => section.push_str(&format!(
+                    "Percentiles: HDR histogram over {} successful samples\n", percentiles.samples)),
+                (EstimatorKind::TDigest, _) => section.push_str(&format!(
+                    "Percentiles: t-digest over {} successful samples (interpolated between centroids)\n",
+                    percentiles.samples)),
+            }
+            for p in [50.0, 95.0, 99.0] {
+                if let Some(value) = percentiles.get(p) {
+                    section.push_str(&format!("  p{} = {} ms\n", p, value));
+                }
+            }
+        },
+        None => section.push_str("Percentiles: no successful samples\n"),
+    }
+    
+    if let (Some(bytes), Some(rate)) = (results.total_data_transferred, results.transfer_rate) {
+        section.push_str(&format!("Transfer rate = {} bytes / {:.3} s = {:.2} bytes/s\n",
+            bytes, results.duration_secs, rate));
+    }
+    if let Some(goodput) = results.goodput {
+        let successful_bytes: usize = results.requests.iter()
+            .filter(|r| r.success)
+            .filter_map(|r| r.response_size)
+            .sum();
+        section.push_str(&format!("Goodput = {} bytes of successful responses / {:.3} s = {:.2} bytes/s\n",
+            successful_bytes, results.duration_secs, goodput));
+    }
+    if let Some(apdex) = &results.apdex {
+        section.push_str(&format!("Apdex = ({} satisfied + {} tolerating / 2) / {} requests = {:.2}\n",
+            apdex.satisfied, apdex.tolerating, apdex.satisfied + apdex.tolerating + apdex.frustrated, apdex.score));
+    }
+    
+    section.push('\n');
+    section
+}
+
+/// Write the REQUEST DETAILS section of the text report
+fn write_text_details<W: Write>(details: &[(usize, &RequestResult)], out: &mut W) -> std::io::Result<()> {
+    writeln!(out, "REQUEST DETAILS")?;
+    for &(i, result) in details {
+        write!(out, "Request #{}: ", i + 1)?;
+        if result.success {
+            write!(out, "Success, Status: {}, ", 
+                   result.status.map(|s| s.to_string()).unwrap_or_else(|| "None".to_string()))?;
+        } else {
+            let error_text = result.error_message().unwrap_or("Unknown");
+            
+            write!(out, "Failed, Error: {}, ", error_text)?;
+        }
+        write!(out, "Time: {} ms", result.response_time)?;
+        if let Some(size) = result.response_size {
+            write!(out, ", Size: {} bytes", size)?;
+        }
+        for (name, value) in &result.rendered_headers {
+            write!(out, ", {}: {}", name, value)?;
+        }
+        writeln!(out)?;
+    }
+    writeln!(out)
 }
 
 // Disable the warnings for instrument macro
 #[allow(warnings)]
-#[instrument(skip(preprocessed, options))]
-fn generate_json_report(preprocessed: &PreprocessedData, options: &ReportOptions) -> Result<String> {
+#[instrument(skip(preprocessed, options, out))]
+fn write_json_report<W: Write>(preprocessed: &PreprocessedData, options: &ReportOptions, out: &mut W) -> Result<String> {
     debug!("Generating JSON report");
     
     #[derive(Serialize)]
     struct JsonReport<'a> {
+        schema_version: u32,
+        #[serde(skip_serializing_if = "str::is_empty")]
+        run_id: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sni: Option<&'a str>,
+        configured_requests: usize,
+        attempted_requests: usize,
         completed_requests: usize,
         successful_requests: usize,
         failed_requests: usize,
+        expected_failures: usize,
+        error_tiers: ErrorTiers,
         total_duration_secs: f64,
         avg_duration_ms: f64,
+        median_duration_ms: f64,
+        
+        #[serde(skip_serializing_if = "Option::is_none")]
+        avg_ttfb_ms: Option<f64>,
+        
         min_duration_ms: u128,
         max_duration_ms: u128,
         percentiles: Option<HashMap<String, f64>>,
         success_rate: f64,
         failure_rate: f64,
-        status_codes: HashMap<u16, usize>,
+        status_codes: HashMap<String, usize>,
+        #[serde(skip_serializing_if = "HashMap::is_empty")]
+        http_versions: &'a HashMap<String, usize>,
         error_counts: HashMap<String, usize>,
         
         // New fields for enhanced reporting
@@ -364,64 +1057,127 @@ fn generate_json_report(preprocessed: &PreprocessedData, options: &ReportOptions
         response_time_std_dev: f64,
         total_data_transferred: Option<usize>,
         transfer_rate: Option<f64>,
+        goodput: Option<f64>,
+        truncated_responses: usize,
+        cancelled_requests: usize,
+        retries: usize,
+        retry_budget_exhausted: bool,
+        
+        #[serde(skip_serializing_if = "Option::is_none")]
+        aborted: Option<&'a str>,
+        
+        #[serde(skip_serializing_if = "Option::is_none")]
+        connections: Option<ConnectionStats>,
+        
+        #[serde(skip_serializing_if = "Option::is_none")]
+        streaming: Option<StreamingStats>,
+        
+        #[serde(skip_serializing_if = "Option::is_none")]
+        server_timing: Option<&'a ServerTimingStats>,
+        
+        #[serde(skip_serializing_if = "Option::is_none")]
+        intermittency: Option<&'a Intermittency>,
         
         #[serde(skip_serializing_if = "Option::is_none")]
-        request_details: Option<&'a Vec<RequestResult>>,
+        size_correlation: Option<&'a SizeCorrelation>,
+        
+        #[serde(skip_serializing_if = "Option::is_none")]
+        apdex: Option<Apdex>,
+        
+        #[serde(skip_serializing_if = "Option::is_none")]
+        thresholds: Option<Thresholds>,
+        
+        #[serde(skip_serializing_if = "<[_]>::is_empty")]
+        threshold_violations: &'a [ThresholdViolation],
+        
+        passed: bool,
+        
+        #[serde(skip_serializing_if = "<[_]>::is_empty")]
+        latency_bands: &'a [LatencyBand],
+        
+        #[serde(skip_serializing_if = "<[_]>::is_empty")]
+        bursts: &'a [BurstStats],
+        
+        #[serde(skip_serializing_if = "HashMap::is_empty")]
+        by_host: &'a HashMap<String, HostStats>,
+        
+        #[serde(skip_serializing_if = "HashMap::is_empty")]
+        by_target: &'a HashMap<String, HostStats>,
+        
+        #[serde(skip_serializing_if = "HashMap::is_empty")]
+        by_step: &'a HashMap<String, HostStats>,
+        
+        #[serde(skip_serializing_if = "HashMap::is_empty")]
+        body_usage: &'a HashMap<String, usize>,
+        
+        #[serde(skip_serializing_if = "Option::is_none")]
+        conditional: Option<&'a ConditionalStats>,
+        
+        #[serde(skip_serializing_if = "Option::is_none")]
+        keepalive: Option<&'a KeepAliveStats>,
+        
+        #[serde(skip_serializing_if = "Option::is_none")]
+        queue_wait: Option<&'a QueueWaitStats>,
+        
+        failures: Vec<FailureDetail<'a>>,
         
         #[serde(skip_serializing_if = "HashMap::is_empty")]
         response_time_distribution: &'a HashMap<String, usize>,
     }
     
-    // Calculate percentiles if histograms are enabled
-    let percentiles = if options.include_histograms {
-        if let Some(hist) = create_histogram(preprocessed.results) {
-            let mut map = HashMap::new();
-            
-            map.insert("p50".to_string(), hist.value_at_percentile(50.0) as f64);
-            map.insert("p75".to_string(), hist.value_at_percentile(75.0) as f64);
-            map.insert("p90".to_string(), hist.value_at_percentile(90.0) as f64);
-            map.insert("p95".to_string(), hist.value_at_percentile(95.0) as f64);
-            map.insert("p99".to_string(), hist.value_at_percentile(99.0) as f64);
-            map.insert("p999".to_string(), hist.value_at_percentile(99.9) as f64);
-            
-            Some(map)
-        } else {
-            None
-        }
-    } else {
-        None
-    };
-    
-    // Convert status_codes HashMap to BTreeMap for deterministic ordering
-    let status_codes = preprocessed.results.status_codes.clone();
+    // Include percentiles if histograms are enabled
+    let percentiles = (options.include_histograms && preprocessed.results.percentiles.is_some())
+        .then(|| create_percentile_data(preprocessed));
     
-    // Convert errors HashMap to BTreeMap for deterministic ordering
-    let error_counts = preprocessed.results.errors.clone();
+    // Status codes and errors, capped to the most frequent categories
+    let status_codes = cap_categories(&preprocessed.results.status_codes, options.max_categories, "codes")
+        .into_iter()
+        .collect();
+    let error_counts = cap_categories(&preprocessed.results.errors, options.max_categories, "errors")
+        .into_iter()
+        .collect();
     
     // Calculate percentages
     let success_rate = percentage(preprocessed.results.successful_requests, preprocessed.results.total_requests);
     let failure_rate = percentage(preprocessed.results.failed_requests, preprocessed.results.total_requests);
     
-    // Optional detailed results
-    let request_details = if options.include_details {
-        Some(&preprocessed.results.requests)
-    } else {
-        None
-    };
+    // Failed requests are always listed (up to the cap) for automated triage
+    let failures = preprocessed.results.requests.iter()
+        .enumerate()
+        .filter_map(|(index, result)| {
+            result.error_tier().map(|tier| FailureDetail {
+                index,
+                status: result.status,
+                tier,
+                error: result.error.as_deref(),
+            })
+        })
+        .take(options.max_failures)
+        .collect();
     
     // Create the JSON report
     let report = JsonReport {
-        completed_requests: preprocessed.results.total_requests,
+        schema_version: preprocessed.results.schema_version,
+        run_id: &preprocessed.results.run_id,
+        sni: preprocessed.results.sni.as_deref(),
+        configured_requests: preprocessed.results.configured_requests,
+        attempted_requests: preprocessed.results.attempted_requests,
+        completed_requests: preprocessed.results.completed_requests(),
         successful_requests: preprocessed.results.successful_requests,
         failed_requests: preprocessed.results.failed_requests,
+        expected_failures: preprocessed.results.expected_failures,
+        error_tiers: preprocessed.results.error_tiers,
         total_duration_secs: preprocessed.results.duration_secs,
         avg_duration_ms: preprocessed.results.average_response_time,
+        median_duration_ms: preprocessed.results.median_response_time,
+        avg_ttfb_ms: preprocessed.results.average_ttfb,
         min_duration_ms: preprocessed.results.min_response_time,
         max_duration_ms: preprocessed.results.max_response_time,
         percentiles,
         success_rate,
         failure_rate,
         status_codes,
+        http_versions: &preprocessed.results.http_versions,
         error_counts,
         
         // New fields
@@ -429,47 +1185,222 @@ fn generate_json_report(preprocessed: &PreprocessedData, options: &ReportOptions
         response_time_std_dev: preprocessed.results.response_time_std_dev,
         total_data_transferred: preprocessed.results.total_data_transferred,
         transfer_rate: preprocessed.results.transfer_rate,
+        goodput: preprocessed.results.goodput,
+        truncated_responses: preprocessed.results.truncated_responses,
+        cancelled_requests: preprocessed.results.cancelled_requests,
+        retries: preprocessed.results.retries,
+        retry_budget_exhausted: preprocessed.results.retry_budget_exhausted,
+        aborted: preprocessed.results.aborted.as_deref(),
+        connections: preprocessed.results.connections,
+        streaming: preprocessed.results.streaming,
+        server_timing: preprocessed.results.server_timing.as_ref(),
+        intermittency: preprocessed.results.intermittency.as_ref(),
+        size_correlation: preprocessed.results.size_correlation.as_ref(),
+        apdex: preprocessed.results.apdex,
+        thresholds: preprocessed.results.thresholds,
+        threshold_violations: &preprocessed.results.threshold_violations,
+        passed: preprocessed.results.passed,
+        latency_bands: &preprocessed.results.latency_bands,
+        bursts: &preprocessed.results.bursts,
+        by_host: &preprocessed.results.by_host,
+        by_target: &preprocessed.results.by_target,
+        by_step: &preprocessed.results.by_step,
+        body_usage: &preprocessed.results.body_usage,
+        conditional: preprocessed.results.conditional.as_ref(),
+        keepalive: preprocessed.results.keepalive.as_ref(),
+        queue_wait: preprocessed.results.queue_wait.as_ref(),
         response_time_distribution: &preprocessed.results.response_time_distribution,
         
-        request_details,
+        failures,
     };
     
-    // Serialize to JSON
+    // The summary is serialized once, and returned without the per-request details
     let json = serde_json::to_string_pretty(&report)
         .map_err(|e| Error::Json(e))?;
+    debug!("JSON report summary generated ({} chars)", json.len());
+    
+    if !options.include_details {
+        out.write_all(json.as_bytes())
+            .map_err(|e| Error::Io(e))?;
+        return Ok(json);
+    }
+    
+    // Optional detailed results, streamed into the summary as its last field
+    let request_details: Vec<&RequestResult> = select_details(preprocessed.results, options)
+        .into_iter()
+        .map(|(_, result)| result)
+        .collect();
+    let fields = json.strip_suffix("\n}").expect("pretty-printed JSON object");
+    out.write_all(fields.as_bytes())
+        .and_then(|_| out.write_all(b",\n  \"request_details\": "))
+        .map_err(|e| Error::Io(e))?;
+    serde_json::to_writer_pretty(IndentWriter(&mut *out), &request_details)
+        .map_err(|e| Error::Json(e))?;
+    out.write_all(b"\n}")
+        .map_err(|e| Error::Io(e))?;
     
-    debug!("JSON report generated ({} chars)", json.len());
     Ok(json)
 }
 
+/// Writer indenting every line but the first by one level of pretty-printed JSON, to nest
+/// a value serialized on its own inside an object
+struct IndentWriter<W>(W);
+
+impl<W: Write> Write for IndentWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        // Pretty-printed JSON only has line breaks between tokens, never inside strings
+        for (i, line) in buf.split(|&byte| byte == b'\n').enumerate() {
+            if i > 0 {
+                self.0.write_all(b"\n  ")?;
+            }
+            self.0.write_all(line)?;
+        }
+        Ok(buf.len())
+    }
+    
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+/// Requests listed in the detailed section, with their index in the run
+fn select_details<'a>(results: &'a LoadTestResults, options: &ReportOptions) -> Vec<(usize, &'a RequestResult)> {
+    let requests = results.requests.iter().enumerate();
+    let limit = match options.detail_limit {
+        Some(limit) => limit,
+        None => return requests.collect(),
+    };
+    
+    match options.detail_selection {
+        DetailSelection::First => requests.take(limit).collect(),
+        DetailSelection::Random => {
+            let mut rng = match options.seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_entropy(),
+            };
+            let mut sample = index::sample(&mut rng, results.requests.len(), limit.min(results.requests.len())).into_vec();
+            sample.sort_unstable();
+            sample.into_iter().map(|i| (i, &results.requests[i])).collect()
+        },
+        DetailSelection::Errors => requests.filter(|(_, result)| result.is_failure()).take(limit).collect(),
+        DetailSelection::Slowest => {
+            // Keep a running top-K, with the fastest retained request on top of the heap
+            let mut slowest = BinaryHeap::with_capacity(limit + 1);
+            for (i, result) in requests {
+                slowest.push(Reverse((result.response_time, i)));
+                if slowest.len() > limit {
+                    slowest.pop();
+                }
+            }
+            slowest.into_sorted_vec()
+                .into_iter()
+                .map(|Reverse((_, i))| (i, &results.requests[i]))
+                .collect()
+        },
+    }
+}
+
+/// Sort categories (status codes, errors) by count and keep the `max` most frequent,
+/// folding the rest into a single "other (N codes)" entry
+fn cap_categories<K: ToString>(counts: &HashMap<K, usize>, max: Option<usize>, noun: &str) -> Vec<(String, usize)> {
+    let mut sorted: Vec<(String, usize)> = counts.iter()
+        .map(|(key, count)| (key.to_string(), *count))
+        .collect();
+    sorted.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    
+    if let Some(max) = max {
+        if sorted.len() > max {
+            let rest = sorted.split_off(max);
+            let count = rest.iter().map(|(_, count)| count).sum();
+            sorted.push((format!("other ({} {})", rest.len(), noun), count));
+        }
+    }
+    
+    sorted
+}
+
+/// A failed request listed in the JSON report
+#[derive(Serialize)]
+struct FailureDetail<'a> {
+    /// Position of the request in the results
+    index: usize,
+    
+    /// HTTP status code, if a response was received
+    status: Option<u16>,
+    
+    /// Whether the failure was transport- or application-level
+    tier: ErrorTier,
+    
+    /// Error message
+    error: Option<&'a str>,
+}
+
 /// Generate an enhanced HTML report with interactive charts
 fn generate_html_report(preprocessed: &PreprocessedData, options: &ReportOptions) -> Result<String> {
     debug!("Generating enhanced HTML report");
     
     // Create chart data in JSON format for the JavaScript charts
-    let chart_data = serde_json::json!({
+    let mut chart_data = serde_json::json!({
         "summary": {
             "total": preprocessed.results.total_requests,
+            "configured": preprocessed.results.configured_requests,
+            "attempted": preprocessed.results.attempted_requests,
             "successful": preprocessed.results.successful_requests,
             "failed": preprocessed.results.failed_requests,
-            "duration": preprocessed.results.duration_secs
+            "duration": preprocessed.results.duration_secs,
+            "apdex": preprocessed.results.apdex,
+            "errorTiers": preprocessed.results.error_tiers
         },
         "timing": {
             "average": preprocessed.results.average_response_time,
+            "median": preprocessed.results.median_response_time,
+            "ttfb": preprocessed.results.average_ttfb,
             "min": preprocessed.results.min_response_time,
             "max": preprocessed.results.max_response_time,
             "stdDev": preprocessed.results.response_time_std_dev,
             "throughput": preprocessed.results.throughput,
-            "transferRate": preprocessed.results.transfer_rate
+            "transferRate": preprocessed.results.transfer_rate,
+            "goodput": preprocessed.results.goodput
         },
         "distribution": {
             "responseTimes": preprocessed.results.response_time_distribution,
-            "statusCodes": preprocessed.results.status_codes
+            "statusCodes": cap_categories(&preprocessed.results.status_codes, options.max_categories, "codes")
+                .into_iter()
+                .collect::<HashMap<_, _>>()
         },
-        "percentiles": create_percentile_data(preprocessed.results),
-        "errors": preprocessed.results.errors
+        "httpVersions": preprocessed.results.http_versions,
+        "hosts": preprocessed.results.by_host,
+        "targets": preprocessed.results.by_target,
+        "steps": preprocessed.results.by_step,
+        "latencyBands": preprocessed.results.latency_bands.iter()
+            .map(|band| serde_json::json!({
+                "label": band.label(),
+                "count": band.count,
+                "percent": band.percent
+            }))
+            .collect::<Vec<_>>(),
+        "percentiles": create_percentile_data(preprocessed),
+        "throughputOverTime": preprocessed.results.requests_per_second(),
+        "sizeCorrelation": preprocessed.results.size_correlation,
+        "errors": cap_categories(&preprocessed.results.errors, options.max_categories, "errors")
+            .into_iter()
+            .collect::<HashMap<_, _>>()
     });
     
+    // Overlay the baseline run, if any
+    let baseline = options.baseline.as_ref()
+        .map(PreprocessedData::new);
+    if let Some(baseline) = &baseline {
+        chart_data["baseline"] = serde_json::json!({
+            "distribution": {
+                "responseTimes": baseline.results.response_time_distribution
+            },
+            "percentiles": create_percentile_data(baseline)
+        });
+        let comparison = compare_results(baseline.results, preprocessed.results, options.regression_threshold);
+        chart_data["comparison"] = serde_json::to_value(comparison.metrics)?;
+    }
+    
     // Format the chart data as JSON string for embedding in the HTML
     let chart_data_json = serde_json::to_string(&chart_data)
         .map_err(|e| Error::Serialization(e))?;
@@ -482,21 +1413,29 @@ fn generate_html_report(preprocessed: &PreprocessedData, options: &ReportOptions
     
     // Add metadata
     let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
-    let metadata = format!(
-        "Test Date: {}",
-        timestamp
-    );
+    let metadata = if preprocessed.results.run_id.is_empty() {
+        format!("Test Date: {}", timestamp)
+    } else {
+        format!("Test Date: {} | Run ID: {}", timestamp, preprocessed.results.run_id)
+    };
     
     let html = template.replace("<!-- METADATA_PLACEHOLDER -->", &metadata);
     
     // Generate and embed SVG histograms if requested
     let html = if options.include_histograms {
-        let response_time_histogram = generate_histogram_svg_embedded(preprocessed.results, "Response Time Distribution (ms)")?;
+        let response_time_histogram = generate_histogram_svg_embedded(preprocessed, baseline.as_ref(), "Response Time Distribution (ms)")?;
         html.replace("<!-- HISTOGRAM_PLACEHOLDER -->", &response_time_histogram)
     } else {
         html.replace("<!-- HISTOGRAM_PLACEHOLDER -->", "")
     };
     
+    // The request size scatter plot has no Chart.js counterpart, so it is always embedded
+    let html = if preprocessed.results.size_correlation.is_some() {
+        html.replace("<!-- SIZE_SCATTER_PLACEHOLDER -->", &generate_size_scatter_svg_embedded(preprocessed.results)?)
+    } else {
+        html.replace("<!-- SIZE_SCATTER_PLACEHOLDER -->", "")
+    };
+    
     // Always add detailed request information for HTML reports
     let mut details_html = String::from("<h3>Request Details</h3>");
     
@@ -528,16 +1467,13 @@ fn generate_html_report(preprocessed: &PreprocessedData, options: &ReportOptions
     details_html.push_str(r#"<div class="table-container"><table class="details-table" id="request-details-table">"#);
     details_html.push_str("<thead><tr><th>#</th><th>Status</th><th>Time (ms)</th><th>Size (bytes)</th><th>Result</th></tr></thead><tbody>");
     
-    for (i, result) in preprocessed.results.requests.iter().enumerate() {
+    for (i, result) in select_details(preprocessed.results, options) {
         let status = result.status.map(|s| s.to_string()).unwrap_or_else(|| "-".to_string());
         let size = result.response_size.map(|s| s.to_string()).unwrap_or_else(|| "-".to_string());
         let result_text = if result.success {
             "Success".to_string()
         } else {
-            let error_text = result.error
-                .as_deref()
-                .unwrap_or("Unknown")
-                .replace("HTTP Error: ", "");
+            let error_text = result.error_message().unwrap_or("Unknown");
             
             format!("Error: {}", error_text)
         };
@@ -574,22 +1510,32 @@ fn generate_html_report(preprocessed: &PreprocessedData, options: &ReportOptions
     
     let html = html.replace("<!-- DETAILS_PLACEHOLDER -->", &details_html);
     
+    // Embed the logo so the report has no sidecar files
+    let html = if options.inline_assets {
+        html.replace(
+            "src=\"pressr-logo.png\"",
+            &format!("src=\"data:image/png;base64,{}\"", BASE64_STANDARD.encode(LOGO_PNG))
+        )
+    } else {
+        html
+    };
+    
     // Return the HTML content for file writing
     Ok(html)
 }
 
 /// Create percentile data for charts
-fn create_percentile_data(results: &LoadTestResults) -> HashMap<String, f64> {
+fn create_percentile_data(preprocessed: &PreprocessedData) -> HashMap<String, f64> {
     let mut percentiles = HashMap::new();
     
-    if let Some(hist) = create_histogram(results) {
+    if let Some(reported) = preprocessed.results.percentiles {
         // Add standard percentiles
-        percentiles.insert("p50".to_string(), hist.value_at_percentile(50.0) as f64);
-        percentiles.insert("p75".to_string(), hist.value_at_percentile(75.0) as f64);
-        percentiles.insert("p90".to_string(), hist.value_at_percentile(90.0) as f64);
-        percentiles.insert("p95".to_string(), hist.value_at_percentile(95.0) as f64);
-        percentiles.insert("p99".to_string(), hist.value_at_percentile(99.0) as f64);
-        percentiles.insert("p999".to_string(), hist.value_at_percentile(99.9) as f64);
+        percentiles.insert("p50".to_string(), reported.p50);
+        percentiles.insert("p75".to_string(), reported.p75);
+        percentiles.insert("p90".to_string(), reported.p90);
+        percentiles.insert("p95".to_string(), reported.p95);
+        percentiles.insert("p99".to_string(), reported.p99);
+        percentiles.insert("p999".to_string(), reported.p999);
     }
     
     percentiles
@@ -606,6 +1552,7 @@ fn generate_histogram_svg(preprocessed: &PreprocessedData) -> Result<String> {
     
     // Extract response times
     let response_times: Vec<f64> = preprocessed.results.requests.iter()
+        .filter(|r| r.has_latency())
         .map(|r| r.response_time as f64)
         .collect();
     
@@ -711,11 +1658,11 @@ fn generate_histogram_svg(preprocessed: &PreprocessedData) -> Result<String> {
         .label("Response Times");
         
         // Draw the percentile lines
-        if let Some(hist) = &preprocessed.histogram {
-            let p50 = hist.value_at_percentile(50.0) as f64;
-            let p90 = hist.value_at_percentile(90.0) as f64;
-            let p95 = hist.value_at_percentile(95.0) as f64;
-            let p99 = hist.value_at_percentile(99.0) as f64;
+        if preprocessed.results.percentiles.is_some() {
+            let p50 = preprocessed.percentile(50.0).unwrap_or(0.0);
+            let p90 = preprocessed.percentile(90.0).unwrap_or(0.0);
+            let p95 = preprocessed.percentile(95.0).unwrap_or(0.0);
+            let p99 = preprocessed.percentile(99.0).unwrap_or(0.0);
             
             // Draw 50th percentile line
             chart.draw_series(LineSeries::new(
@@ -778,15 +1725,96 @@ fn generate_histogram_svg(preprocessed: &PreprocessedData) -> Result<String> {
     Ok(buffer)
 }
 
+/// Generate an SVG scatter plot of request size against response time for embedding in
+/// HTML reports (successful requests that sent a body)
+fn generate_size_scatter_svg_embedded(results: &LoadTestResults) -> Result<String> {
+    debug!("Generating embedded SVG request size scatter plot");
+    let points: Vec<(f64, f64)> = results.requests.iter()
+        .filter(|r| r.success)
+        .filter_map(|r| r.request_size.map(|size| (size as f64, r.response_time as f64)))
+        .collect();
+    
+    let max_size = points.iter().map(|(size, _)| *size).fold(0.0, f64::max);
+    let max_time = points.iter().map(|(_, time)| *time).fold(0.0, f64::max);
+    let cells = bin_points(&points, max_size, max_time);
+    let most = cells.iter().map(|(_, count)| *count).max().unwrap_or(1);
+    
+    let mut buffer = String::new();
+    {
+        let root = SVGBackend::with_string(&mut buffer, (800, 400))
+            .into_drawing_area();
+        
+        root.fill(&WHITE)
+            .map_err(|e| Error::Plotting(format!("Failed to fill plot background: {}", e)))?;
+        
+        let mut chart = ChartBuilder::on(&root)
+            .caption("Request Size vs Response Time", ("sans-serif", 20))
+            .margin(10)
+            .x_label_area_size(40)
+            .y_label_area_size(60)
+            .build_cartesian_2d(0f64..(max_size * 1.1).max(1.0), 0f64..(max_time * 1.1).max(1.0))
+            .map_err(|e| Error::Plotting(format!("Failed to build chart: {}", e)))?;
+        
+        chart.configure_mesh()
+            .x_desc("Request Size (bytes)")
+            .y_desc("Response Time (ms)")
+            .draw()
+            .map_err(|e| Error::Plotting(format!("Failed to draw chart mesh: {}", e)))?;
+        
+        // Busier cells are drawn more opaque
+        chart.draw_series(
+            cells.iter().map(|(point, count)| {
+                let opacity = 0.3 + 0.7 * (*count as f64).ln_1p() / (most as f64).ln_1p();
+                Circle::new(*point, 3, BLUE.mix(opacity).filled())
+            })
+        )
+        .map_err(|e| Error::Plotting(format!("Failed to draw scatter points: {}", e)))?;
+        
+        root.present()
+            .map_err(|e| Error::Plotting(format!("Failed to render SVG: {}", e)))?;
+    }
+    
+    Ok(buffer)
+}
+
+/// Columns and rows of the grid the size scatter plot is binned into
+const SCATTER_GRID: (usize, usize) = (200, 100);
+
+/// Bin scatter points into a grid spanning `0..=max_x` and `0..=max_y`, so a plot draws at most
+/// one point per cell however many requests there were
+///
+/// Returns the mean point of every occupied cell with its number of points, in grid order.
+fn bin_points(points: &[(f64, f64)], max_x: f64, max_y: f64) -> Vec<((f64, f64), usize)> {
+    let (columns, rows) = SCATTER_GRID;
+    let cell = |value: f64, max: f64, cells: usize| {
+        if max > 0.0 { ((value / max * cells as f64) as usize).min(cells - 1) } else { 0 }
+    };
+    
+    let mut grid: BTreeMap<(usize, usize), (f64, f64, usize)> = BTreeMap::new();
+    for &(x, y) in points {
+        let entry = grid.entry((cell(x, max_x, columns), cell(y, max_y, rows))).or_insert((0.0, 0.0, 0));
+        entry.0 += x;
+        entry.1 += y;
+        entry.2 += 1;
+    }
+    grid.into_values()
+        .map(|(x, y, count)| ((x / count as f64, y / count as f64), count))
+        .collect()
+}
+
 /// Generate standalone SVG histogram for embedding in HTML reports
-fn generate_histogram_svg_embedded(results: &LoadTestResults, title: &str) -> Result<String> {
+fn generate_histogram_svg_embedded(
+    preprocessed: &PreprocessedData,
+    baseline: Option<&PreprocessedData>,
+    title: &str,
+) -> Result<String> {
     debug!("Generating embedded SVG histogram");
+    let results = preprocessed.results;
     
-    // Create a histogram from response times
-    let hist = match create_histogram(results) {
-        Some(h) => h,
-        None => return Ok("No data available for histogram".to_string()),
-    };
+    // Nothing to plot without successful response times
+    if results.percentiles.is_none() {
+        return Ok("No data available for histogram".to_string());
+    }
     
     // Dimensions for the SVG
     let width = 800u32;
@@ -811,7 +1839,8 @@ fn generate_histogram_svg_embedded(results: &LoadTestResults, title: &str) -> Re
         
         // Create and populate histogram data
         let mut hist_data = Vec::new();
-        let p99 = hist.value_at_percentile(99.0) as f64;
+        let mut baseline_data = Vec::new();
+        let p99 = preprocessed.percentile(99.0).unwrap_or(0.0);
         
         // Cap the x-axis at p99 to avoid outliers stretching the graph
         let max_x = p99 * 1.1;
@@ -825,21 +1854,37 @@ fn generate_histogram_svg_embedded(results: &LoadTestResults, title: &str) -> Re
             let count = results.requests.iter()
                 .filter(|r| {
                     let t = r.response_time as f64;
-                    t >= bin_start && t < bin_end
+                    r.has_latency() && t >= bin_start && t < bin_end
                 })
                 .count();
                 
             if count > 0 {
                 hist_data.push((mid_point, count as f64));
             }
+            
+            // Count the baseline values in the same bin
+            if let Some(baseline) = baseline {
+                let count = baseline.results.requests.iter()
+                    .filter(|r| {
+                        let t = r.response_time as f64;
+                        r.has_latency() && t >= bin_start && t < bin_end
+                    })
+                    .count();
+                baseline_data.push((mid_point, count as f64));
+            }
         }
         
+        let max_count = hist_data.iter()
+            .chain(&baseline_data)
+            .map(|(_, c)| *c)
+            .fold(0.0, f64::max);
+        
         let mut chart = ChartBuilder::on(&root)
             .caption(title, ("sans-serif", 20))
             .margin(10)
             .x_label_area_size(40)
             .y_label_area_size(60)
-            .build_cartesian_2d(0f64..max_x, 0f64..hist_data.iter().map(|(_, c)| *c).fold(0.0, f64::max) * 1.1)
+            .build_cartesian_2d(0f64..max_x, 0f64..max_count * 1.1)
             .map_err(|e| Error::Plotting(format!("Failed to build chart: {}", e)))?;
             
         chart.configure_mesh()
@@ -858,11 +1903,24 @@ fn generate_histogram_svg_embedded(results: &LoadTestResults, title: &str) -> Re
         )
         .map_err(|e| Error::Plotting(format!("Failed to draw histogram bars: {}", e)))?;
         
+        // Overlay the baseline distribution as a dashed line
+        if !baseline_data.is_empty() {
+            chart.draw_series(DashedLineSeries::new(
+                baseline_data.iter().copied(),
+                6,
+                4,
+                BLACK.mix(0.7).stroke_width(2),
+            ))
+            .map_err(|e| Error::Plotting(format!("Failed to draw baseline line: {}", e)))?
+            .label("Baseline")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], BLACK));
+        }
+        
         // Draw percentile lines
-        let p50 = hist.value_at_percentile(50.0) as f64;
-        let p90 = hist.value_at_percentile(90.0) as f64;
+        let p50 = preprocessed.percentile(50.0).unwrap_or(0.0);
+        let p90 = preprocessed.percentile(90.0).unwrap_or(0.0);
         
-        let max_y = hist_data.iter().map(|(_, c)| *c).fold(0.0, f64::max);
+        let max_y = max_count;
         
         // Draw the median line (50th percentile)
         chart.draw_series(LineSeries::new(
@@ -905,36 +1963,317 @@ fn generate_histogram_svg_embedded(results: &LoadTestResults, title: &str) -> Re
     Ok(buffer)
 }
 
-/// Create a histogram from the response times
-fn create_histogram(results: &LoadTestResults) -> Option<Histogram<u64>> {
-    if results.requests.is_empty() {
-        return None;
+/// Calculate percentage
+fn percentage(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        (part as f64 / total as f64) * 100.0
+    }
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::percentile::PercentileMethod;
+    use std::time::Duration;
+    
+    fn request(response_time: u128, http_version: &str) -> RequestResult {
+        RequestResult {
+            status: Some(200),
+            success: true,
+            response_time,
+            http_version: Some(http_version.to_string()),
+            ..Default::default()
+        }
     }
     
-    // Create histogram with 3 significant figures precision
-    let mut hist = Histogram::<u64>::new_with_bounds(1, 3_600_000, 3)
-        .expect("Failed to create histogram with specified bounds");
+    #[test]
+    fn seeded_random_details_are_reproducible() {
+        let results = LoadTestResults::new((0..100).map(|i| request(i, "HTTP/1.1")).collect(), Duration::from_secs(1));
+        let options = ReportOptions {
+            detail_limit: Some(10),
+            detail_selection: DetailSelection::Random,
+            seed: Some(7),
+            ..Default::default()
+        };
+        let indices = |options: &ReportOptions| select_details(&results, options)
+            .into_iter()
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        
+        let sample = indices(&options);
+        assert_eq!(sample.len(), 10);
+        assert!(sample.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(sample, indices(&options));
+        assert_ne!(sample, indices(&ReportOptions { seed: Some(8), ..options.clone() }));
+    }
     
-    // Record response times (in milliseconds)
-    for result in &results.requests {
-        if result.success {
-            hist.record(result.response_time as u64)
-                .expect("Failed to record value in histogram");
+    #[test]
+    fn prometheus_counts_http_versions() {
+        let results = LoadTestResults::new(
+            vec![request(10, "HTTP/1.1"), request(20, "HTTP/2.0"), request(30, "HTTP/2.0")],
+            Duration::from_secs(1),
+        );
+        let metrics = to_prometheus(&results);
+        
+        assert!(metrics.contains("pressr_http_versions_total{version=\"HTTP/1.1\"} 1\n"));
+        assert!(metrics.contains("pressr_http_versions_total{version=\"HTTP/2.0\"} 2\n"));
+    }
+    
+    #[test]
+    fn html_report_embeds_http_versions() {
+        let results = LoadTestResults::new(vec![request(10, "HTTP/2.0")], Duration::from_secs(1));
+        let options = ReportOptions::default();
+        let html = generate_html_report(&PreprocessedData::new(&results), &options).unwrap();
+        
+        assert!(html.contains("\"httpVersions\":{\"HTTP/2.0\":1}"));
+        assert!(html.contains("id=\"versions-section\""));
+    }
+    
+    #[test]
+    fn html_comparison_uses_the_baseline_comparison() {
+        let baseline = LoadTestResults::new((1..=100).map(|i| request(i, "HTTP/1.1")).collect(), Duration::from_secs(1));
+        let current = LoadTestResults::new((1..=100).map(|i| request(i * 2, "HTTP/1.1")).collect(), Duration::from_secs(1));
+        let options = ReportOptions { baseline: Some(baseline.clone()), ..Default::default() };
+        let html = generate_html_report(&PreprocessedData::new(&current), &options).unwrap();
+        
+        let expected = serde_json::to_value(compare_results(&baseline, &current, DEFAULT_REGRESSION_THRESHOLD).metrics).unwrap().to_string();
+        assert!(html.contains(&format!("\"comparison\":{}", expected)));
+        assert!(expected.contains("\"change\":\"regressed\""));
+    }
+    
+    #[test]
+    fn every_report_reads_the_results_percentiles() {
+        let mut results = LoadTestResults::new((1..=100).map(|i| request(i, "HTTP/1.1")).collect(), Duration::from_secs(1));
+        let mut percentiles = results.percentiles.unwrap();
+        percentiles.p95 = 1234.0;
+        results.percentiles = Some(percentiles);
+        let preprocessed = PreprocessedData::new(&results);
+        let options = ReportOptions::default();
+        
+        let mut text = Vec::new();
+        write_text_report(&preprocessed, &options, &mut text).unwrap();
+        assert!(String::from_utf8(text).unwrap().contains("95th percentile:     1234.00 ms"));
+        assert!(generate_markdown_report(&preprocessed, &options).contains("p95    1234.00 ms"));
+        let mut json = Vec::new();
+        write_json_report(&preprocessed, &options, &mut json).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(json["percentiles"]["p95"], 1234.0);
+        assert_eq!(create_percentile_data(&preprocessed)["p95"], 1234.0);
+        assert!(to_prometheus(&results).contains("pressr_response_time_seconds{quantile=\"0.95\"} 1.234\n"));
+    }
+    
+    #[test]
+    fn size_scatter_points_are_binned() {
+        let points: Vec<(f64, f64)> = (0..100_000).map(|i| ((i % 1000) as f64, (i % 7) as f64)).collect();
+        let cells = bin_points(&points, 999.0, 6.0);
+        
+        assert!(cells.len() <= SCATTER_GRID.0 * SCATTER_GRID.1);
+        assert_eq!(cells.iter().map(|(_, count)| count).sum::<usize>(), points.len());
+        assert_eq!(bin_points(&[(5.0, 0.0), (5.0, 0.0)], 5.0, 0.0), vec![((5.0, 0.0), 2)]);
+    }
+    
+    #[test]
+    fn json_reports_stream_the_details_into_the_summary() {
+        let results = LoadTestResults::new((1..=3).map(|i| request(i, "HTTP/1.1")).collect(), Duration::from_secs(1));
+        let options = ReportOptions { include_details: true, ..Default::default() };
+        let mut json = Vec::new();
+        let summary = write_json_report(&PreprocessedData::new(&results), &options, &mut json).unwrap();
+        
+        let json = String::from_utf8(json).unwrap();
+        assert!(json.contains("\n  \"request_details\": [\n    {\n      \"status\": 200,"));
+        let mut report: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let details = report.as_object_mut().unwrap().remove("request_details").unwrap();
+        assert_eq!(details.as_array().unwrap().len(), 3);
+        assert_eq!(report, serde_json::from_str::<serde_json::Value>(&summary).unwrap());
+    }
+    
+    #[test]
+    fn inlined_html_reports_embed_the_logo() {
+        let results = LoadTestResults::new(vec![request(10, "HTTP/1.1")], Duration::from_secs(1));
+        let options = ReportOptions { inline_assets: true, ..Default::default() };
+        let html = generate_html_report(&PreprocessedData::new(&results), &options).unwrap();
+        
+        assert!(html.contains("<img src=\"data:image/png;base64,iVBORw0KGgo"));
+        assert!(html.split("<img ").skip(1).all(|tag| tag.starts_with("src=\"data:")));
+        assert!(!html.contains("pressr-logo.png"));
+    }
+    
+    #[test]
+    fn json_reports_list_only_the_failures_without_details() {
+        let mut requests: Vec<RequestResult> = (0..1000).map(|i| request(i, "HTTP/1.1")).collect();
+        requests[10] = RequestResult { status: Some(503), error: Some("HTTP Error: 503 Service Unavailable".to_string()), ..Default::default() };
+        requests[20] = RequestResult { error: Some("connection refused".to_string()), ..Default::default() };
+        let results = LoadTestResults::new(requests, Duration::from_secs(1));
+        let options = ReportOptions { max_failures: 1, ..Default::default() };
+        let mut json = Vec::new();
+        write_json_report(&PreprocessedData::new(&results), &options, &mut json).unwrap();
+        
+        // 1000 requests, but only the capped failure list and no per-request details
+        assert!(json.len() < 10_000, "{} bytes", json.len());
+        let report: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert!(report.get("request_details").is_none());
+        assert_eq!(report["failures"], serde_json::json!([
+            { "index": 10, "status": 503, "tier": "application", "error": "HTTP Error: 503 Service Unavailable" },
+        ]));
+        
+        let mut json = Vec::new();
+        write_json_report(&PreprocessedData::new(&results), &ReportOptions::default(), &mut json).unwrap();
+        let report: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(report["failures"][1]["tier"], "transport");
+        assert_eq!(report["failures"].as_array().unwrap().len(), 2);
+    }
+    
+    #[test]
+    fn capped_status_codes_fold_the_rest_into_other() {
+        let statuses = [200; 10].into_iter().chain([404; 5]).chain([500; 4]).chain(201..=208);
+        let requests = statuses.map(|status| RequestResult { status: Some(status), ..request(10, "HTTP/1.1") }).collect();
+        let results = LoadTestResults::new(requests, Duration::from_secs(1));
+        let options = ReportOptions { max_categories: Some(3), ..Default::default() };
+        let mut text = Vec::new();
+        write_text_report(&PreprocessedData::new(&results), &options, &mut text).unwrap();
+        
+        let text = String::from_utf8(text).unwrap();
+        let section: Vec<&str> = text.split("STATUS CODES\n").nth(1).unwrap().lines().take_while(|line| !line.is_empty()).collect();
+        assert_eq!(section, ["200: 10 (37.0%)", "404: 5 (18.5%)", "500: 4 (14.8%)", "other (8 codes): 8 (29.6%)"]);
+    }
+    
+    #[test]
+    fn html_chart_data_holds_the_current_and_baseline_series() {
+        let baseline = LoadTestResults::new((1..=100).map(|i| request(i, "HTTP/1.1")).collect(), Duration::from_secs(1));
+        let current = LoadTestResults::new((1..=100).map(|i| request(i * 3, "HTTP/1.1")).collect(), Duration::from_secs(1));
+        let options = ReportOptions { baseline: Some(baseline), ..Default::default() };
+        let html = generate_html_report(&PreprocessedData::new(&current), &options).unwrap();
+        
+        let data = html.split("const chartData = ").nth(1).unwrap();
+        let chart: serde_json::Value = serde_json::Deserializer::from_str(data).into_iter().next().unwrap().unwrap();
+        assert_eq!(chart["baseline"]["percentiles"]["p95"], 95.0);
+        assert_eq!(chart["percentiles"]["p95"], 285.0);
+        assert!(!chart["baseline"]["distribution"]["responseTimes"].as_object().unwrap().is_empty());
+        assert_ne!(chart["baseline"]["distribution"]["responseTimes"], chart["distribution"]["responseTimes"]);
+    }
+    
+    #[test]
+    fn the_explain_section_plugs_in_the_numbers() {
+        let mut requests: Vec<RequestResult> = [10, 20, 30].into_iter().map(|time| request(time, "HTTP/1.1")).collect();
+        requests.push(RequestResult { status: Some(500), response_time: 40, ..Default::default() });
+        let exact = PercentileMethod { exact: true, ..Default::default() };
+        let results = LoadTestResults::new(requests, Duration::from_secs(2)).with_percentile_method(exact);
+        let section = explain_section(&PreprocessedData::new(&results));
+        
+        assert!(section.contains("Throughput = 4 requests / 2.000 s = 2.00 req/s\n"), "{}", section);
+        assert!(section.contains("Success rate = 3 successful / 4 requests x 100 = 75.0%\n"), "{}", section);
+        assert!(section.contains("Average = 100 ms total / 4 requests = 25.00 ms\n"), "{}", section);
+        assert!(section.contains("Percentiles: exact, nearest rank over 3 sorted successful samples\n"), "{}", section);
+        assert!(section.contains("  p50 = sample #2 of 3 = 20 ms\n"), "{}", section);
+        assert!(section.contains("  p95 = sample #3 of 3 = 30 ms\n"), "{}", section);
+    }
+    
+    #[test]
+    fn markdown_reports_render_tables_and_fenced_percentiles() {
+        let mut requests: Vec<RequestResult> = [10, 20, 30].into_iter().map(|time| request(time, "HTTP/1.1")).collect();
+        requests.push(RequestResult { status: Some(503), response_time: 5, ..Default::default() });
+        let mut results = LoadTestResults::new(requests, Duration::from_secs(2));
+        results.run_id = "run-7".to_string();
+        let report = generate_markdown_report(&PreprocessedData::new(&results), &ReportOptions::default());
+        
+        assert!(report.starts_with("# Load Test Report\n\nRun ID: `run-7`\n\n## Summary\n\n| Metric | Value |\n| --- | ---: |\n"), "{}", report);
+        assert!(report.contains("| Total requests | 4 |\n| Successful | 3 (75.0%) |\n"), "{}", report);
+        assert!(report.contains("## Percentiles\n\n```text\np50    20.00 ms\n"), "{}", report);
+        assert!(report.contains("| Status | Count | Share |\n| --- | ---: | ---: |\n| 200 | 3 | 75.0% |\n| 503 | 1 | 25.0% |\n"), "{}", report);
+        assert_eq!(report.matches("```").count(), 2);
+    }
+    
+    #[test]
+    fn junit_reports_are_well_formed_xml() {
+        let requests = (1..=4).map(|time| request(time * 100, "HTTP/1.1")).collect();
+        let mut results = LoadTestResults::new(requests, Duration::from_secs(1))
+            .with_thresholds(Thresholds { max_p95_ms: Some(200.0), max_error_rate: Some(1.0), ..Default::default() });
+        results.run_id = "<run & \"id\">".to_string();
+        let xml = generate_junit_report(&results);
+        
+        // Every tag is closed in order, attributes are quoted and entities are escaped
+        let body = xml.strip_prefix("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n").unwrap();
+        let mut open: Vec<&str> = Vec::new();
+        for (index, segment) in body.split('<').enumerate().skip(1) {
+            let (tag, text) = segment.split_once('>').unwrap_or_else(|| panic!("unterminated tag {}", index));
+            assert!(!text.contains('>'), "{}", text);
+            for entity in text.split('&').skip(1).chain(tag.split('&').skip(1)) {
+                assert!(["amp;", "lt;", "gt;", "quot;", "apos;"].iter().any(|e| entity.starts_with(e)), "{}", entity);
+            }
+            assert_eq!(tag.matches('"').count() % 2, 0, "{}", tag);
+            let name = tag.trim_start_matches('/').split([' ', '/']).next().unwrap();
+            if tag.starts_with('/') {
+                assert_eq!(open.pop(), Some(name), "{}", xml);
+            } else if !tag.ends_with('/') {
+                open.push(name);
+            }
         }
+        assert!(open.is_empty(), "{:?}", open);
+        
+        assert!(xml.contains("<testsuite name=\"pressr\" tests=\"3\" failures=\"1\""), "{}", xml);
+        assert!(xml.contains("<testcase classname=\"pressr.thresholds\" name=\"max_p95_ms\">\n      <failure type=\"threshold\""), "{}", xml);
+        assert!(xml.contains("<testcase classname=\"pressr.thresholds\" name=\"max_error_rate\"/>"), "{}", xml);
+        assert!(xml.contains("value=\"&lt;run &amp; &quot;id&quot;&gt;\""), "{}", xml);
     }
     
-    if hist.len() > 0 {
-        Some(hist)
-    } else {
-        None
+    #[test]
+    fn prometheus_metrics_expose_the_counters_and_quantiles() {
+        let mut requests: Vec<RequestResult> = [100, 200, 300].into_iter().map(|time| request(time, "HTTP/1.1")).collect();
+        requests.push(RequestResult { status: Some(500), response_time: 50, ..Default::default() });
+        let exact = PercentileMethod { exact: true, ..Default::default() };
+        let results = LoadTestResults::new(requests, Duration::from_secs(2)).with_percentile_method(exact);
+        let metrics = to_prometheus(&results);
+        
+        for line in [
+            "# TYPE pressr_requests_total counter\npressr_requests_total 4\n",
+            "pressr_failures_total 1\n",
+            "pressr_status_codes_total{code=\"500\"} 1\n",
+            "# TYPE pressr_response_time_seconds summary\n",
+            "pressr_response_time_seconds{quantile=\"0.5\"} 0.2\n",
+            "pressr_response_time_seconds{quantile=\"0.95\"} 0.3\n",
+            "pressr_response_time_seconds_sum 0.6\n",
+            "pressr_response_time_seconds_count 3\n",
+            "pressr_throughput 2\n",
+        ] {
+            assert!(metrics.contains(line), "{}\n{}", line, metrics);
+        }
+        // Every sample line is a metric name, optional labels and a value
+        for line in metrics.lines().filter(|line| !line.starts_with('#')) {
+            let (name, value) = line.rsplit_once(' ').unwrap();
+            assert!(name.starts_with("pressr_"), "{}", line);
+            assert!(value.parse::<f64>().is_ok(), "{}", line);
+        }
     }
-}
-
-/// Calculate percentage
-fn percentage(part: usize, total: usize) -> f64 {
-    if total == 0 {
-        0.0
-    } else {
-        (part as f64 / total as f64) * 100.0
+    
+    #[test]
+    fn large_detailed_reports_stream_without_growing_the_summary() {
+        /// Counts the bytes written without keeping them
+        struct Counter(usize);
+        impl Write for Counter {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0 += buf.len();
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+        
+        let options = ReportOptions { include_details: true, ..Default::default() };
+        let write = |count: u128| {
+            let results = LoadTestResults::new((0..count).map(|i| request(i % 500, "HTTP/1.1")).collect(), Duration::from_secs(10));
+            let mut counter = Counter(0);
+            let summary = write_text_report(&PreprocessedData::new(&results), &options, &mut counter).unwrap();
+            (summary.len(), counter.0)
+        };
+        
+        let (small_summary, small_written) = write(1_000);
+        let (large_summary, large_written) = write(200_000);
+        assert!(large_written > small_written * 100, "{} vs {}", large_written, small_written);
+        // Only the details scale with the run: the summary kept in memory stays the same size
+        assert!(large_summary < small_summary + 200, "{} vs {}", large_summary, small_summary);
+        assert!(large_summary * 100 < large_written);
     }
-} 
\ No newline at end of file
+}
@@ -1,38 +1,1137 @@
 use serde::{Serialize, Deserialize};
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
 use std::time::Duration;
+use tracing::{debug, instrument, warn};
+
+use crate::conditional::ConditionalStats;
+use crate::keepalive::KeepAliveStats;
+use crate::percentile::{PercentileMethod, Percentiles};
+use crate::error::{Error, Result};
+
+/// Longest stretch of a run (in seconds) whose empty seconds `LoadTestResults::timeline`
+/// fills in (one day)
+const MAX_TIMELINE_SECONDS: u64 = 24 * 60 * 60;
+
+/// Most status runs kept in `Intermittency::runs` (a flapping target can change its
+/// status class on every request)
+const MAX_STATUS_RUNS: usize = 1000;
+
+/// Smallest part of a second a rate in the timeline is scaled up from
+const MIN_COVERED_FRACTION: f64 = 0.1;
+
+/// Current version of the serialized `LoadTestResults` schema
+///
+/// Version 1 is the original format without a `schema_version` field.
+pub const RESULTS_SCHEMA_VERSION: u32 = 2;
+
+/// Error message prefix used when the connection fails while reading the response body
+pub(crate) const BODY_READ_ERROR: &str = "Error reading response body";
+
+/// Error message of requests cancelled at shutdown
+pub(crate) const CANCELLED_ERROR: &str = "Request cancelled at shutdown";
+
+/// Error message prefix used when a response has an unexpected status code
+pub(crate) const HTTP_ERROR: &str = "HTTP Error";
+
+/// Error message prefix used when a scenario step cannot extract a value from its response
+pub(crate) const EXTRACT_ERROR: &str = "Extraction failed";
+
+/// Tier of a failed request, used to separate infrastructure from application problems
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorTier {
+    /// Connection-level failure (connection refused, TLS, timeout, dropped connection)
+    Transport,
+    
+    /// A response was received but was not acceptable (e.g. 5xx)
+    Application,
+}
+
+/// Generate a run identifier from the current UTC time and a random suffix (e.g. "20240501-142233-3fa9")
+pub fn generate_run_id() -> String {
+    format!("{}-{:04x}", chrono::Utc::now().format("%Y%m%d-%H%M%S"), rand::random::<u16>())
+}
+
+/// Convert a (possibly externally supplied) number of seconds into a `Duration`
+///
+/// Negative, NaN or infinite values, e.g. from edited result files or wall-clock
+/// jumps, would panic in `Duration::from_secs_f64`; they are clamped to zero instead.
+pub(crate) fn duration_from_secs(secs: f64) -> Duration {
+    if secs.is_finite() && secs >= 0.0 {
+        Duration::try_from_secs_f64(secs).unwrap_or(Duration::MAX)
+    } else {
+        warn!("Invalid duration of {} seconds, clamping to zero", secs);
+        Duration::ZERO
+    }
+}
 
 /// Result of a single HTTP request
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RequestResult {
     /// HTTP status code
     pub status: Option<u16>,
     
-    /// Response time in milliseconds
-    pub response_time: u128,
+    /// Response time in milliseconds
+    pub response_time: u128,
+    
+    /// Whether the request was successful
+    pub success: bool,
+    
+    /// Error message, if any
+    pub error: Option<String>,
+    
+    /// Response size in bytes
+    pub response_size: Option<usize>,
+    
+    /// Size in bytes of the request body (if one was sent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_size: Option<usize>,
+    
+    /// Worker that sent this request (partitioned dispatch only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worker: Option<usize>,
+    
+    /// Index of the burst this request was sent in (burst mode only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burst: Option<usize>,
+    
+    /// Time in milliseconds the request waited past its scheduled start (scheduled dispatch only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queue_wait: Option<u128>,
+    
+    /// Whether the request started later than the late tolerance past its scheduled start
+    /// (see `BurstProfile::late_tolerance`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub late: bool,
+    
+    /// Body size announced by the `Content-Length` header, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_length: Option<u64>,
+    
+    /// Whether the body was cut off at the configured read limit
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub body_truncated: bool,
+    
+    /// Host the request was sent to (multi-target runs only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    
+    /// Name of the target the request was sent to (multi-target runs only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    
+    /// Name of the scenario step the request was sent by (scenario step results only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step: Option<String>,
+    
+    /// HTTP method the request was sent with (target and scenario step results only,
+    /// as their methods can differ from the configured one)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    
+    /// URL the request was sent to, after templating (target and scenario step results only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    
+    /// Results of the steps run by a scenario iteration, in order (scenario runs only)
+    ///
+    /// The iteration itself is timed from the start of the first step to the end of the
+    /// last, and fails when any of its steps fails.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<RequestResult>,
+    
+    /// Name of the body picked from the body pool (body pool runs only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    
+    /// Values of the templated headers as sent (e.g. generated idempotency keys), only
+    /// recorded with `Config::record_headers` and with sensitive values redacted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rendered_headers: Vec<(String, String)>,
+    
+    /// Number of times the request was retried before this (final) attempt
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub retries: u32,
+    
+    /// Whether the response arrived on a newly opened connection rather than a pooled one
+    /// (None if the connection info is unavailable)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_connection: Option<bool>,
+    
+    /// Local (source) port of the connection the response arrived on, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local_port: Option<u16>,
+    
+    /// HTTP version the response arrived with, e.g. "HTTP/1.1" or "HTTP/2.0"
+    /// (None if no response was received)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_version: Option<String>,
+    
+    /// Time to first byte: milliseconds from sending the request until the response
+    /// headers arrived (None if no response was received)
+    ///
+    /// Unlike `first_chunk`, this does not depend on the streaming body read path, so it
+    /// is also recorded for HEAD requests, empty bodies and with body reading disabled.
+    /// It includes setting up the connection for requests on new connections (see
+    /// `ConnectionStats::average_setup`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttfb: Option<u128>,
+    
+    /// Time in milliseconds from sending the request until the first body chunk arrived
+    /// (None if the body was not read or was empty)
+    ///
+    /// Only recorded on the streaming body read path: the body is read chunk by chunk
+    /// unless body reading is disabled (`Config::read_body`).
+    #[serde(default, alias = "first_byte", skip_serializing_if = "Option::is_none")]
+    pub first_chunk: Option<u128>,
+    
+    /// Time in milliseconds between the first and the last body chunk, i.e. how long the
+    /// body took to stream (None if the body was not read or was empty)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream_duration: Option<u128>,
+    
+    /// Durations in milliseconds reported by the server in `Server-Timing` metrics, by name
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub server_timing: HashMap<String, f64>,
+    
+    /// Index of the request (or scenario iteration) in dispatch order, starting at 0
+    /// (results are stored in completion order)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<usize>,
+    
+    /// Time in milliseconds from the start of the run until the request was started
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<u128>,
+    
+    /// Time in milliseconds from the start of the run until the request completed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<u128>,
+    
+    /// Whether the response had an expected-failure status: the request is neither
+    /// successful nor counted as failed
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub expected_failure: bool,
+    
+    /// Whether the request was still in flight when the run was stopped and was cancelled:
+    /// it counts toward the totals and errors, but has no measured response time
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cancelled: bool,
+}
+
+/// Whether a retry count is zero (used to omit it from serialized results)
+fn is_zero(retries: &u32) -> bool {
+    *retries == 0
+}
+
+/// Results saved before thresholds existed passed (used as the serde default)
+fn default_passed() -> bool {
+    true
+}
+
+impl RequestResult {
+    /// Processing time reported by the server in milliseconds: the longest `Server-Timing`
+    /// duration, as an outer metric (e.g. "total") usually spans the others
+    pub fn server_time(&self) -> Option<f64> {
+        self.server_timing.values().copied().reduce(f64::max)
+    }
+    
+    /// Tier of the failure, or `None` if the request succeeded (or was an expected failure)
+    pub fn error_tier(&self) -> Option<ErrorTier> {
+        if !self.is_failure() {
+            return None;
+        }
+        
+        match (self.status, &self.error) {
+            (None, _) => Some(ErrorTier::Transport),
+            (Some(_), Some(error)) if error.starts_with(BODY_READ_ERROR) => Some(ErrorTier::Transport),
+            (Some(_), _) => Some(ErrorTier::Application),
+        }
+    }
+    
+    /// Whether the request failed (expected failures don't count)
+    pub fn is_failure(&self) -> bool {
+        !self.success && !self.expected_failure
+    }
+    
+    /// Whether the request was still in flight when the run was stopped and was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        // Results saved before the flag existed are recognized by their error
+        self.cancelled || self.error.as_deref() == Some(CANCELLED_ERROR)
+    }
+    
+    /// Whether the response time was measured and belongs in latency statistics
+    /// (cancelled requests never completed)
+    pub fn has_latency(&self) -> bool {
+        !self.is_cancelled()
+    }
+    
+    /// Whether the request failed because it timed out
+    pub fn is_timeout(&self) -> bool {
+        !self.success && self.error.as_deref().is_some_and(|error| error.contains("timed out"))
+    }
+    
+    /// Whether the request failed at the connection level for a reason other than a timeout
+    /// (connection refused, TLS, DNS, dropped connection while reading the body)
+    pub fn is_connection_error(&self) -> bool {
+        self.error_tier() == Some(ErrorTier::Transport) && !self.is_timeout()
+    }
+    
+    /// Whether the request failed because of an unexpected status code
+    pub fn is_http_error(&self) -> bool {
+        self.error_tier() == Some(ErrorTier::Application)
+    }
+    
+    /// Whether the failure is worth retrying: a transport error or a 5xx response
+    pub fn is_retryable(&self) -> bool {
+        self.error_tier() == Some(ErrorTier::Transport) || (self.is_failure() && self.http_status_class() == Some(5))
+    }
+    
+    /// Class of the response status (e.g. 2 for 2xx, 5 for 5xx), or `None` if no response was received
+    pub fn http_status_class(&self) -> Option<u16> {
+        self.status.map(|status| status / 100)
+    }
+    
+    /// Bytes transferred for the response body: the bytes read, or the announced
+    /// `Content-Length` when the body was not read
+    pub fn transferred_bytes(&self) -> Option<u64> {
+        self.response_size.map(|size| size as u64).or(self.content_length)
+    }
+    
+    /// Error message without the classification prefix (e.g. "503 Service Unavailable"
+    /// instead of "HTTP Error: 503 Service Unavailable"), or `None` if the request succeeded
+    pub fn error_message(&self) -> Option<&str> {
+        let error = self.error.as_deref()?;
+        if self.is_http_error() {
+            Some(error
+                .strip_prefix(HTTP_ERROR)
+                .map(|rest| rest.trim_start_matches(':').trim_start())
+                .unwrap_or(error))
+        } else {
+            Some(error)
+        }
+    }
+}
+
+/// Failed requests split into transport and application tiers
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct ErrorTiers {
+    /// Number of transport-level failures
+    pub transport: usize,
+    
+    /// Number of application-level failures
+    pub application: usize,
+    
+    /// Transport failures as a percentage of all requests
+    pub transport_percent: f64,
+    
+    /// Application failures as a percentage of all requests
+    pub application_percent: f64,
+}
+
+impl ErrorTiers {
+    /// Tally the failure tiers of a set of requests
+    pub fn from_requests(requests: &[RequestResult]) -> Self {
+        let mut tiers = Self::default();
+        
+        for result in requests {
+            match result.error_tier() {
+                Some(ErrorTier::Transport) => tiers.transport += 1,
+                Some(ErrorTier::Application) => tiers.application += 1,
+                None => {}
+            }
+        }
+        
+        if !requests.is_empty() {
+            tiers.transport_percent = tiers.transport as f64 / requests.len() as f64 * 100.0;
+            tiers.application_percent = tiers.application as f64 / requests.len() as f64 * 100.0;
+        }
+        
+        tiers
+    }
+}
+
+/// Statistics for a single burst of requests
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BurstStats {
+    /// Burst index (starting at 0)
+    pub index: usize,
+    
+    /// Number of requests in the burst
+    pub requests: usize,
+    
+    /// Number of failed requests in the burst
+    pub failed: usize,
+    
+    /// Average response time in milliseconds
+    pub average_response_time: f64,
+    
+    /// Minimum response time in milliseconds
+    pub min_response_time: u128,
+    
+    /// Maximum response time in milliseconds
+    pub max_response_time: u128,
+}
+
+/// Statistics for the requests sent to a single host or target
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostStats {
+    /// Number of requests sent to the host or target
+    pub requests: usize,
+    
+    /// Number of successful requests
+    pub successful: usize,
+    
+    /// Number of failed requests
+    pub failed: usize,
+    
+    /// Average response time in milliseconds
+    pub average_response_time: f64,
+    
+    /// Minimum response time in milliseconds
+    pub min_response_time: u128,
+    
+    /// Maximum response time in milliseconds
+    pub max_response_time: u128,
+    
+    /// Distribution of error messages
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub errors: HashMap<String, usize>,
+}
+
+impl HostStats {
+    /// Group requests by host, skipping requests without a host
+    pub fn by_host(requests: &[RequestResult]) -> HashMap<String, HostStats> {
+        Self::group_by(requests, |result| result.host.as_deref())
+    }
+    
+    /// Group requests by target, skipping requests without a target
+    pub fn by_target(requests: &[RequestResult]) -> HashMap<String, HostStats> {
+        Self::group_by(requests, |result| result.target.as_deref())
+    }
+    
+    /// Group the steps of scenario iterations by step name
+    pub fn by_step(requests: &[RequestResult]) -> HashMap<String, HostStats> {
+        Self::group_by(requests.iter().flat_map(|result| &result.steps), |result| result.step.as_deref())
+    }
+    
+    /// Group requests by a key, skipping requests without one
+    fn group_by<'a>(
+        requests: impl IntoIterator<Item = &'a RequestResult>,
+        key: impl Fn(&'a RequestResult) -> Option<&'a str>,
+    ) -> HashMap<String, HostStats> {
+        let mut groups: HashMap<&str, Vec<&RequestResult>> = HashMap::new();
+        for result in requests {
+            if let Some(key) = key(result) {
+                groups.entry(key).or_default().push(result);
+            }
+        }
+        
+        groups.into_iter()
+            .map(|(key, group)| {
+                let times: Vec<u128> = group.iter()
+                    .filter(|r| r.has_latency())
+                    .map(|r| r.response_time)
+                    .collect();
+                let successful = group.iter().filter(|r| r.success).count();
+                let mut errors = HashMap::new();
+                for error in group.iter().filter_map(|r| r.error.as_ref()) {
+                    *errors.entry(error.clone()).or_insert(0) += 1;
+                }
+                
+                let stats = HostStats {
+                    requests: group.len(),
+                    successful,
+                    failed: group.iter().filter(|r| r.is_failure()).count(),
+                    average_response_time: average(&times),
+                    min_response_time: times.iter().copied().min().unwrap_or(0),
+                    max_response_time: times.iter().copied().max().unwrap_or(0),
+                    errors,
+                };
+                (key.to_string(), stats)
+            })
+            .collect()
+    }
+}
+
+/// Client-side queuing statistics: how long requests waited past their scheduled start
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueWaitStats {
+    /// Number of requests with a scheduled start
+    pub requests: usize,
+    
+    /// Number of requests that started late
+    pub delayed: usize,
+    
+    /// Average queue wait in milliseconds
+    pub average: f64,
+    
+    /// Median queue wait in milliseconds
+    pub p50: u128,
+    
+    /// 95th percentile queue wait in milliseconds
+    pub p95: u128,
+    
+    /// 99th percentile queue wait in milliseconds
+    pub p99: u128,
+    
+    /// Maximum queue wait in milliseconds
+    pub max: u128,
+    
+    /// Number of requests that started later than the late tolerance
+    #[serde(default)]
+    pub late: usize,
+    
+    /// Share of requests that started late (0-100)
+    #[serde(default)]
+    pub late_percent: f64,
+    
+    /// Average response time of the late requests in milliseconds (None if none were late)
+    #[serde(default)]
+    pub late_average_response_time: Option<f64>,
+    
+    /// Average response time of the on-time requests in milliseconds (None if none were on time)
+    #[serde(default)]
+    pub on_time_average_response_time: Option<f64>,
+}
+
+impl QueueWaitStats {
+    /// Calculate queue wait statistics, or `None` if no request had a scheduled start
+    pub fn from_requests(requests: &[RequestResult]) -> Option<Self> {
+        let scheduled: Vec<&RequestResult> = requests.iter()
+            .filter(|r| r.queue_wait.is_some())
+            .collect();
+        if scheduled.is_empty() {
+            return None;
+        }
+        let mut waits: Vec<u128> = scheduled.iter()
+            .filter_map(|r| r.queue_wait)
+            .collect();
+        waits.sort_unstable();
+        
+        let (late, on_time): (Vec<&RequestResult>, Vec<&RequestResult>) = scheduled.iter()
+            .partition(|r| r.late);
+        let average_response_time = |group: &[&RequestResult]| {
+            let times: Vec<u128> = group.iter()
+                .filter(|r| r.has_latency())
+                .map(|r| r.response_time)
+                .collect();
+            (!times.is_empty()).then(|| average(&times))
+        };
+        
+        let percentile = |p: f64| {
+            let rank = ((p / 100.0) * waits.len() as f64).ceil() as usize;
+            waits[rank.clamp(1, waits.len()) - 1]
+        };
+        
+        Some(Self {
+            requests: waits.len(),
+            delayed: waits.iter().filter(|w| **w > 0).count(),
+            average: waits.iter().sum::<u128>() as f64 / waits.len() as f64,
+            p50: percentile(50.0),
+            p95: percentile(95.0),
+            p99: percentile(99.0),
+            max: waits[waits.len() - 1],
+            late: late.len(),
+            late_percent: late.len() as f64 / waits.len() as f64 * 100.0,
+            late_average_response_time: average_response_time(&late),
+            on_time_average_response_time: average_response_time(&on_time),
+        })
+    }
+}
+
+/// Connection reuse statistics: how often a request had to open a new connection
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ConnectionStats {
+    /// Number of requests whose connection is known
+    pub requests: usize,
+    
+    /// Number of those requests that opened a new connection
+    pub new_connections: usize,
+    
+    /// Share of requests that opened a new connection (0-100)
+    pub new_connection_percent: f64,
+    
+    /// Number of distinct local (source) ports, roughly the number of connections used
+    #[serde(default)]
+    pub distinct_ports: usize,
+    
+    /// Estimated connection setup time (DNS lookup, TCP connect and TLS handshake) in
+    /// milliseconds: the average TTFB of requests on new connections minus that of requests
+    /// on reused ones (None unless both kinds of requests have a TTFB)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub average_setup: Option<f64>,
+}
+
+impl ConnectionStats {
+    /// Calculate connection statistics, or `None` if no request has connection info
+    pub fn from_requests(requests: &[RequestResult]) -> Option<Self> {
+        let known: Vec<bool> = requests.iter().filter_map(|r| r.new_connection).collect();
+        if known.is_empty() {
+            return None;
+        }
+        
+        let new_connections = known.iter().filter(|new| **new).count();
+        let distinct_ports = requests.iter()
+            .filter_map(|r| r.local_port)
+            .collect::<HashSet<_>>()
+            .len();
+        // reqwest doesn't expose the connection phases, but the TTFB of a request on a new
+        // connection includes setting it up
+        let ttfbs = |new: bool| -> Vec<u128> {
+            requests.iter()
+                .filter(|r| r.new_connection == Some(new))
+                .filter_map(|r| r.ttfb)
+                .collect()
+        };
+        let (new_ttfbs, reused_ttfbs) = (ttfbs(true), ttfbs(false));
+        let average_setup = (!new_ttfbs.is_empty() && !reused_ttfbs.is_empty())
+            .then(|| (average(&new_ttfbs) - average(&reused_ttfbs)).max(0.0));
+        Some(Self {
+            requests: known.len(),
+            new_connections,
+            new_connection_percent: new_connections as f64 / known.len() as f64 * 100.0,
+            distinct_ports,
+            average_setup,
+        })
+    }
+}
+
+/// Body streaming statistics: time to the first body chunk versus time spent streaming the rest
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct StreamingStats {
+    /// Number of requests whose body timing is known
+    pub requests: usize,
+    
+    /// Average time to the first body chunk in milliseconds
+    #[serde(alias = "average_first_byte")]
+    pub average_first_chunk: f64,
+    
+    /// Average time between the first and the last body chunk in milliseconds
+    pub average_stream_duration: f64,
+    
+    /// Longest time between the first and the last body chunk in milliseconds
+    pub max_stream_duration: u128,
+}
+
+impl StreamingStats {
+    /// Calculate streaming statistics, or `None` if no request has body timing
+    pub fn from_requests(requests: &[RequestResult]) -> Option<Self> {
+        let known: Vec<(u128, u128)> = requests.iter()
+            .filter_map(|r| r.first_chunk.zip(r.stream_duration))
+            .collect();
+        if known.is_empty() {
+            return None;
+        }
+        
+        let count = known.len() as f64;
+        Some(Self {
+            requests: known.len(),
+            average_first_chunk: known.iter().map(|(first_chunk, _)| first_chunk).sum::<u128>() as f64 / count,
+            average_stream_duration: known.iter().map(|(_, stream)| stream).sum::<u128>() as f64 / count,
+            max_stream_duration: known.iter().map(|(_, stream)| *stream).max().unwrap_or(0),
+        })
+    }
+}
+
+/// Server-reported processing time (`Server-Timing`) versus the latency measured by the client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerTimingStats {
+    /// Number of requests with a `Server-Timing` duration
+    pub requests: usize,
+    
+    /// Average server processing time in milliseconds (see `RequestResult::server_time`)
+    pub average_server_time: f64,
+    
+    /// Average client latency minus server time in milliseconds, i.e. network and client overhead
+    pub average_overhead: f64,
+    
+    /// Average duration of each reported metric in milliseconds, by name
+    pub metrics: HashMap<String, f64>,
+}
+
+impl ServerTimingStats {
+    /// Calculate server timing statistics, or `None` if no response had `Server-Timing` durations
+    pub fn from_requests(requests: &[RequestResult]) -> Option<Self> {
+        let timed: Vec<(&RequestResult, f64)> = requests.iter()
+            .filter_map(|r| r.server_time().map(|server_time| (r, server_time)))
+            .collect();
+        if timed.is_empty() {
+            return None;
+        }
+        
+        let mut totals: HashMap<&str, (f64, usize)> = HashMap::new();
+        for (request, _) in &timed {
+            for (name, duration) in &request.server_timing {
+                let total = totals.entry(name.as_str()).or_default();
+                total.0 += duration;
+                total.1 += 1;
+            }
+        }
+        
+        let count = timed.len() as f64;
+        Some(Self {
+            requests: timed.len(),
+            average_server_time: timed.iter().map(|(_, server_time)| server_time).sum::<f64>() / count,
+            average_overhead: timed.iter()
+                .map(|(request, server_time)| request.response_time as f64 - server_time)
+                .sum::<f64>() / count,
+            metrics: totals.into_iter()
+                .map(|(name, (total, count))| (name.to_string(), total / count as f64))
+                .collect(),
+        })
+    }
+}
+
+/// Correlation of the request body size with the response size and latency
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SizeCorrelation {
+    /// Number of successful requests that sent a body
+    pub requests: usize,
+    
+    /// Pearson coefficient between request size and response size (if response sizes vary)
+    pub response_size: Option<f64>,
+    
+    /// Pearson coefficient between request size and response time (if response times vary)
+    pub response_time: Option<f64>,
+}
+
+impl SizeCorrelation {
+    /// Correlate the request sizes of the successful requests, or `None` if fewer than
+    /// two requests sent a body or all bodies had the same size
+    pub fn from_requests(requests: &[RequestResult]) -> Option<Self> {
+        let sized: Vec<(&RequestResult, f64)> = requests.iter()
+            .filter(|r| r.success)
+            .filter_map(|r| r.request_size.map(|size| (r, size as f64)))
+            .collect();
+        // Without any variation in request size there is nothing to correlate
+        if sized.windows(2).all(|pair| pair[0].1 == pair[1].1) {
+            return None;
+        }
+        
+        let with_response_size: Vec<(f64, f64)> = sized.iter()
+            .filter_map(|(r, size)| r.response_size.map(|response_size| (*size, response_size as f64)))
+            .collect();
+        let with_response_time: Vec<(f64, f64)> = sized.iter()
+            .map(|(r, size)| (*size, r.response_time as f64))
+            .collect();
+        
+        Some(Self {
+            requests: sized.len(),
+            response_size: pearson(&with_response_size),
+            response_time: pearson(&with_response_time),
+        })
+    }
+}
+
+/// Average of the response times in milliseconds (0 if there are none)
+fn average(times: &[u128]) -> f64 {
+    if times.is_empty() {
+        0.0
+    } else {
+        times.iter().sum::<u128>() as f64 / times.len() as f64
+    }
+}
+
+/// Pearson correlation coefficient of the pairs, or `None` if there are fewer than two
+/// pairs or either side does not vary
+fn pearson(pairs: &[(f64, f64)]) -> Option<f64> {
+    if pairs.len() < 2 {
+        return None;
+    }
+    
+    let count = pairs.len() as f64;
+    let mean_x = pairs.iter().map(|(x, _)| x).sum::<f64>() / count;
+    let mean_y = pairs.iter().map(|(_, y)| y).sum::<f64>() / count;
+    
+    let (mut covariance, mut variance_x, mut variance_y) = (0.0, 0.0, 0.0);
+    for (x, y) in pairs {
+        covariance += (x - mean_x) * (y - mean_y);
+        variance_x += (x - mean_x).powi(2);
+        variance_y += (y - mean_y).powi(2);
+    }
+    
+    if variance_x == 0.0 || variance_y == 0.0 {
+        return None;
+    }
+    Some((covariance / (variance_x * variance_y).sqrt()).clamp(-1.0, 1.0))
+}
+
+/// Consecutive requests (in dispatch order) with the same status class
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusRun {
+    /// Status class, e.g. "2xx", "5xx", or "error" for requests without a response
+    pub class: String,
+    
+    /// Index of the first request of the run (0-based)
+    pub first: usize,
+    
+    /// Index of the last request of the run (0-based)
+    pub last: usize,
+    
+    /// Number of requests in the run
+    pub requests: usize,
+    
+    /// Whether the requests of the run failed
+    pub failed: bool,
+}
+
+/// Intermittent failures: the status class changed between otherwise identical requests
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Intermittency {
+    /// The first `MAX_STATUS_RUNS` runs of consecutive requests with the same status class
+    pub runs: Vec<StatusRun>,
+    
+    /// Number of runs, including those left out of `runs`
+    #[serde(default)]
+    pub run_count: usize,
+    
+    /// Number of runs of failed requests
+    pub failure_runs: usize,
+    
+    /// Longest run of failed requests
+    pub longest_failure_run: usize,
+    
+    /// Average length of the runs of failed requests
+    pub average_failure_run: f64,
+    
+    /// Whether failures come in runs (average run of at least 2 requests) rather than
+    /// being spread out between successes
+    pub clustered: bool,
+}
+
+impl Intermittency {
+    /// Status class of a request
+    fn class(request: &RequestResult) -> String {
+        match request.status {
+            Some(status) => format!("{}xx", status / 100),
+            None => "error".to_string(),
+        }
+    }
+    
+    /// Find the status runs, or `None` if only one status class appears
+    ///
+    /// Requests are ordered by their dispatch index (falling back to their position for
+    /// results without one), so concurrent requests completing out of order don't break
+    /// up the runs.
+    pub fn from_requests(requests: &[RequestResult]) -> Option<Self> {
+        let mut ordered: Vec<(usize, &RequestResult)> = requests.iter()
+            .enumerate()
+            .map(|(position, request)| (request.index.unwrap_or(position), request))
+            .collect();
+        ordered.sort_by_key(|(i, _)| *i);
+        
+        let mut runs: Vec<StatusRun> = Vec::new();
+        for (i, request) in ordered {
+            let class = Self::class(request);
+            match runs.last_mut() {
+                Some(run) if run.class == class && run.failed == request.is_failure() => {
+                    run.last = i;
+                    run.requests += 1;
+                },
+                _ => runs.push(StatusRun { class, first: i, last: i, requests: 1, failed: request.is_failure() }),
+            }
+        }
+        
+        let classes: HashSet<&str> = runs.iter().map(|run| run.class.as_str()).collect();
+        if classes.len() < 2 {
+            return None;
+        }
+        
+        let failure_lengths: Vec<usize> = runs.iter()
+            .filter(|run| run.failed)
+            .map(|run| run.requests)
+            .collect();
+        let average_failure_run = if failure_lengths.is_empty() {
+            0.0
+        } else {
+            failure_lengths.iter().sum::<usize>() as f64 / failure_lengths.len() as f64
+        };
+        let run_count = runs.len();
+        runs.truncate(MAX_STATUS_RUNS);
+        
+        Some(Self {
+            run_count,
+            failure_runs: failure_lengths.len(),
+            longest_failure_run: failure_lengths.iter().copied().max().unwrap_or(0),
+            average_failure_run,
+            clustered: average_failure_run >= 2.0,
+            runs,
+        })
+    }
+}
+
+/// Requests started and completed within one second of a run (one row of the time series)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineSecond {
+    /// Seconds since the start of the run
+    pub timestamp: u64,
+    
+    /// Number of requests started in this second (the offered load)
+    #[serde(default)]
+    pub started: usize,
+    
+    /// Number of requests completed in this second
+    pub completed: usize,
+    
+    /// Requests completed per second (scaled up for a final partial second, see
+    /// `LoadTestResults::timeline`)
+    pub rps: f64,
+    
+    /// Average response time in milliseconds of the requests completed in this second
+    pub avg_latency: f64,
+    
+    /// 95th percentile response time in milliseconds (nearest rank, 0 if none completed)
+    pub p95: u128,
+    
+    /// Number of failed requests completed in this second
+    pub error_count: usize,
+}
+
+/// Share of successful requests whose response time falls within a latency band
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyBand {
+    /// Inclusive lower bound in milliseconds
+    pub from_ms: u64,
+    
+    /// Exclusive upper bound in milliseconds (None for the open-ended last band)
+    pub to_ms: Option<u64>,
+    
+    /// Number of successful requests in the band
+    pub count: usize,
+    
+    /// Percentage of successful requests in the band
+    pub percent: f64,
+}
+
+impl LatencyBand {
+    /// Split successful requests into bands at the given boundaries (in milliseconds)
+    ///
+    /// Boundaries `[100, 300]` produce the bands `<100`, `100-300` and `>=300`.
+    pub fn calculate(requests: &[RequestResult], boundaries: &[u64]) -> Vec<LatencyBand> {
+        let mut boundaries = boundaries.to_vec();
+        boundaries.sort_unstable();
+        boundaries.dedup();
+        
+        let mut bands: Vec<LatencyBand> = std::iter::once(0)
+            .chain(boundaries.iter().copied())
+            .zip(boundaries.iter().copied().map(Some).chain(std::iter::once(None)))
+            .map(|(from_ms, to_ms)| LatencyBand { from_ms, to_ms, count: 0, percent: 0.0 })
+            .collect();
+        
+        let successful: Vec<u128> = requests.iter()
+            .filter(|r| r.success)
+            .map(|r| r.response_time)
+            .collect();
+        
+        for response_time in &successful {
+            let index = boundaries.partition_point(|boundary| *boundary as u128 <= *response_time);
+            bands[index].count += 1;
+        }
+        
+        if !successful.is_empty() {
+            for band in &mut bands {
+                band.percent = band.count as f64 / successful.len() as f64 * 100.0;
+            }
+        }
+        
+        bands
+    }
     
-    /// Whether the request was successful
-    pub success: bool,
+    /// Human-readable label of the band, e.g. "100-300 ms"
+    pub fn label(&self) -> String {
+        match self.to_ms {
+            Some(to_ms) if self.from_ms == 0 => format!("<{} ms", to_ms),
+            Some(to_ms) => format!("{}-{} ms", self.from_ms, to_ms),
+            None => format!(">={} ms", self.from_ms),
+        }
+    }
+}
+
+/// Pass/fail limits on the results of a run, e.g. for gating CI builds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Thresholds {
+    /// Maximum 95th percentile response time of the successful requests in milliseconds,
+    /// checked against the results' `percentiles` like the reports show them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_p95_ms: Option<f64>,
     
-    /// Error message, if any
-    pub error: Option<String>,
+    /// Minimum throughput in requests per second
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_throughput: Option<f64>,
     
-    /// Response size in bytes
-    pub response_size: Option<usize>,
+    /// Maximum share of failed requests (0-100)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_error_rate: Option<f64>,
+}
+
+impl Thresholds {
+    /// Whether no threshold is set
+    pub fn is_empty(&self) -> bool {
+        self.max_p95_ms.is_none() && self.min_throughput.is_none() && self.max_error_rate.is_none()
+    }
+    
+    /// Check the results against the thresholds, returning the ones they violate
+    ///
+    /// The p95 threshold counts as violated when no request succeeded.
+    pub fn evaluate(&self, results: &LoadTestResults) -> Vec<ThresholdViolation> {
+        let mut violations = Vec::new();
+        
+        if let Some(limit) = self.max_p95_ms {
+            let p95 = results.percentiles.map(|percentiles| percentiles.p95);
+            if p95.is_none_or(|p95| p95 > limit) {
+                violations.push(ThresholdViolation { threshold: "max_p95_ms".to_string(), limit, actual: p95 });
+            }
+        }
+        if let Some(limit) = self.min_throughput {
+            if results.throughput < limit {
+                violations.push(ThresholdViolation {
+                    threshold: "min_throughput".to_string(),
+                    limit,
+                    actual: Some(results.throughput),
+                });
+            }
+        }
+        if let Some(limit) = self.max_error_rate {
+            let error_rate = if results.total_requests > 0 {
+                results.failed_requests as f64 / results.total_requests as f64 * 100.0
+            } else {
+                0.0
+            };
+            if error_rate > limit {
+                violations.push(ThresholdViolation {
+                    threshold: "max_error_rate".to_string(),
+                    limit,
+                    actual: Some(error_rate),
+                });
+            }
+        }
+        
+        violations
+    }
+}
+
+/// A threshold the results of a run did not meet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdViolation {
+    /// Name of the threshold (the `Thresholds` field, e.g. "max_p95_ms")
+    pub threshold: String,
+    
+    /// Configured limit
+    pub limit: f64,
+    
+    /// Measured value (None if it could not be measured)
+    pub actual: Option<f64>,
+}
+
+impl fmt::Display for ThresholdViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.actual {
+            Some(actual) => write!(f, "{}: {:.2} (limit {:.2})", self.threshold, actual, self.limit),
+            None => write!(f, "{}: not measured (limit {:.2})", self.threshold, self.limit),
+        }
+    }
+}
+
+/// Apdex (Application Performance Index) user-satisfaction score
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Apdex {
+    /// Satisfied threshold `T` in milliseconds (tolerating up to `4T`)
+    pub threshold_ms: u64,
+    
+    /// Apdex score between 0.0 and 1.0
+    pub score: f64,
+    
+    /// Number of successful requests completed within `T`
+    pub satisfied: usize,
+    
+    /// Number of successful requests completed within `4T`
+    pub tolerating: usize,
+    
+    /// Number of requests slower than `4T` or failed
+    pub frustrated: usize,
+}
+
+impl Apdex {
+    /// Calculate the Apdex score as `(satisfied + tolerating / 2) / total`
+    ///
+    /// Failed requests are always counted as frustrated; cancelled requests are left out.
+    pub fn calculate(requests: &[RequestResult], threshold: Duration) -> Self {
+        let threshold_ms = threshold.as_millis();
+        let mut satisfied = 0;
+        let mut tolerating = 0;
+        let mut frustrated = 0;
+        
+        for result in requests.iter().filter(|r| r.has_latency()) {
+            if !result.success {
+                frustrated += 1;
+            } else if result.response_time <= threshold_ms {
+                satisfied += 1;
+            } else if result.response_time <= threshold_ms * 4 {
+                tolerating += 1;
+            } else {
+                frustrated += 1;
+            }
+        }
+        
+        let score = if satisfied + tolerating + frustrated == 0 {
+            0.0
+        } else {
+            (satisfied as f64 + tolerating as f64 / 2.0) / (satisfied + tolerating + frustrated) as f64
+        };
+        
+        Self {
+            threshold_ms: threshold_ms as u64,
+            score,
+            satisfied,
+            tolerating,
+            frustrated,
+        }
+    }
 }
 
 /// Results of a load test
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LoadTestResults {
+    /// Version of the serialized schema (see `RESULTS_SCHEMA_VERSION`)
+    #[serde(default)]
+    pub schema_version: u32,
+    
+    /// Identifier of the run, shared by every artifact it produces (empty for older results)
+    #[serde(default)]
+    pub run_id: String,
+    
+    /// TLS server name (SNI) presented instead of the URL's host, if overridden
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sni: Option<String>,
+    
     /// Total number of requests sent
     pub total_requests: usize,
     
+    /// Number of requests the test was configured to send
+    #[serde(default)]
+    pub configured_requests: usize,
+    
+    /// Number of requests that were started (see `completed_requests` for those that completed)
+    #[serde(default)]
+    pub attempted_requests: usize,
+    
     /// Number of successful requests
     pub successful_requests: usize,
     
-    /// Number of failed requests
+    /// Number of failed requests (excluding expected failures)
     pub failed_requests: usize,
     
+    /// Number of requests with an expected-failure status, neither successful nor failed
+    #[serde(default)]
+    pub expected_failures: usize,
+    
+    /// Failed requests split into transport and application errors
+    #[serde(default)]
+    pub error_tiers: ErrorTiers,
+    
     /// Average response time in milliseconds
     pub average_response_time: f64,
     
@@ -42,6 +1141,20 @@ pub struct LoadTestResults {
     /// Maximum response time in milliseconds
     pub max_response_time: u128,
     
+    /// Median response time of the successful requests in milliseconds (the mean of
+    /// the two middle values for an even count, 0 if none succeeded)
+    #[serde(default)]
+    pub median_response_time: f64,
+    
+    /// Average time to first byte in milliseconds of the requests that received a response
+    /// (see `RequestResult::ttfb`; None if none did)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub average_ttfb: Option<f64>,
+    
+    /// Response time percentiles of the successful requests (None if none succeeded)
+    #[serde(default)]
+    pub percentiles: Option<Percentiles>,
+    
     /// Total test duration
     #[serde(skip)]
     pub duration: Duration,
@@ -53,6 +1166,10 @@ pub struct LoadTestResults {
     /// Status code distribution
     pub status_codes: HashMap<u16, usize>,
     
+    /// Number of responses by the HTTP version they arrived with
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub http_versions: HashMap<String, usize>,
+    
     /// Error message distribution
     pub errors: HashMap<String, usize>,
     
@@ -68,12 +1185,108 @@ pub struct LoadTestResults {
     /// Response time standard deviation in milliseconds
     pub response_time_std_dev: f64,
     
+    /// Number of responses whose body was cut off at the read limit
+    #[serde(default)]
+    pub truncated_responses: usize,
+    
+    /// Number of requests cancelled because they were still in flight when the run was stopped
+    #[serde(default)]
+    pub cancelled_requests: usize,
+    
+    /// Total number of retries sent
+    #[serde(default)]
+    pub retries: usize,
+    
+    /// Whether the retry budget ran out, so later failures were not retried
+    #[serde(default)]
+    pub retry_budget_exhausted: bool,
+    
+    /// Why the run was aborted early (None if it was not)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aborted: Option<String>,
+    
+    /// Connection reuse statistics (if connection info was available)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connections: Option<ConnectionStats>,
+    
+    /// Body streaming statistics (if any response body was read)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub streaming: Option<StreamingStats>,
+    
+    /// Server-reported processing time (if any response had `Server-Timing` durations)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_timing: Option<ServerTimingStats>,
+    
+    /// Intermittent failure analysis (if more than one status class appeared)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intermittency: Option<Intermittency>,
+    
+    /// Request size correlation (if successful requests sent bodies of varying sizes)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_correlation: Option<SizeCorrelation>,
+    
     /// Transfer rate in bytes per second (if response sizes are available)
     pub transfer_rate: Option<f64>,
     
+    /// Goodput: bytes per second transferred by successful requests only
+    #[serde(default)]
+    pub goodput: Option<f64>,
+    
     /// Distribution of response times in buckets (for histograms)
-    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub response_time_distribution: HashMap<String, usize>,
+    
+    /// Per-burst statistics (burst mode only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bursts: Vec<BurstStats>,
+    
+    /// Per-host statistics (multi-target runs only)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub by_host: HashMap<String, HostStats>,
+    
+    /// Per-target statistics, keyed by target name (multi-target runs only)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub by_target: HashMap<String, HostStats>,
+    
+    /// Per-step statistics, keyed by step name (scenario runs only)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub by_step: HashMap<String, HostStats>,
+    
+    /// Number of requests sent with each body of the body pool (body pool runs only)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub body_usage: HashMap<String, usize>,
+    
+    /// Client-side queue wait statistics (scheduled dispatch only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queue_wait: Option<QueueWaitStats>,
+    
+    /// Apdex score (if an Apdex threshold was configured)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub apdex: Option<Apdex>,
+    
+    /// Share of successful requests per latency band (if bands were configured)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub latency_bands: Vec<LatencyBand>,
+    
+    /// Configured pass/fail thresholds (if any)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thresholds: Option<Thresholds>,
+    
+    /// Thresholds the run violated
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub threshold_violations: Vec<ThresholdViolation>,
+    
+    /// Whether the run met all configured thresholds (true without thresholds)
+    #[serde(default = "default_passed")]
+    pub passed: bool,
+    
+    /// Conditional-request statistics (ETag validation mode only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conditional: Option<ConditionalStats>,
+    
+    /// Connection lifetimes and drops (keep-alive ping mode only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keepalive: Option<KeepAliveStats>,
 }
 
 impl LoadTestResults {
@@ -81,7 +1294,17 @@ impl LoadTestResults {
     pub fn new(requests: Vec<RequestResult>, duration: Duration) -> Self {
         let total_requests = requests.len();
         let successful_requests = requests.iter().filter(|r| r.success).count();
-        let failed_requests = total_requests - successful_requests;
+        let expected_failures = requests.iter().filter(|r| r.expected_failure).count();
+        let failed_requests = total_requests - successful_requests - expected_failures;
+        let error_tiers = ErrorTiers::from_requests(&requests);
+        let truncated_responses = requests.iter().filter(|r| r.body_truncated).count();
+        let retries = requests.iter().map(|r| r.retries as usize).sum();
+        let cancelled_requests = requests.iter().filter(|r| r.is_cancelled()).count();
+        let connections = ConnectionStats::from_requests(&requests);
+        let streaming = StreamingStats::from_requests(&requests);
+        let intermittency = Intermittency::from_requests(&requests);
+        let server_timing = ServerTimingStats::from_requests(&requests);
+        let size_correlation = SizeCorrelation::from_requests(&requests);
         let duration_secs = duration.as_secs_f64();
         
         // Calculate response time statistics
@@ -92,22 +1315,32 @@ impl LoadTestResults {
         
         // Build status code and error distributions
         let mut status_codes = HashMap::new();
+        let mut http_versions = HashMap::new();
         let mut errors = HashMap::new();
         
         // Calculate total data transferred
         let mut total_data = 0;
         let mut has_all_response_sizes = true;
         
+        // Calculate data transferred by successful requests (for goodput)
+        let mut successful_data = 0;
+        let mut has_all_successful_sizes = true;
+        
         for result in &requests {
-            // Response time stats
-            min_response_time = min_response_time.min(result.response_time);
-            max_response_time = max_response_time.max(result.response_time);
-            total_response_time += result.response_time;
+            // Response time stats (cancelled requests have no response time)
+            if result.has_latency() {
+                min_response_time = min_response_time.min(result.response_time);
+                max_response_time = max_response_time.max(result.response_time);
+                total_response_time += result.response_time;
+            }
             
             // Status code distribution
             if let Some(status) = result.status {
                 *status_codes.entry(status).or_insert(0) += 1;
             }
+            if let Some(version) = &result.http_version {
+                *http_versions.entry(version.clone()).or_insert(0) += 1;
+            }
             
             // Error distribution
             if let Some(error) = &result.error {
@@ -120,27 +1353,51 @@ impl LoadTestResults {
             } else {
                 has_all_response_sizes = false;
             }
+            
+            if result.success {
+                match result.response_size {
+                    Some(size) => successful_data += size,
+                    None => has_all_successful_sizes = false,
+                }
+            }
         }
         
-        // Handle edge case of empty results
-        if total_requests == 0 {
+        // Handle edge case of no measured response times
+        let measured_requests = total_requests - cancelled_requests;
+        if measured_requests == 0 {
             min_response_time = 0;
         }
         
-        let average_response_time = if total_requests > 0 {
-            total_response_time as f64 / total_requests as f64
+        let mut successful_times: Vec<u128> = requests.iter()
+            .filter(|r| r.success)
+            .map(|r| r.response_time)
+            .collect();
+        successful_times.sort_unstable();
+        let middle = successful_times.len() / 2;
+        let median_response_time = match successful_times.len() {
+            0 => 0.0,
+            len if len % 2 == 0 => (successful_times[middle - 1] + successful_times[middle]) as f64 / 2.0,
+            _ => successful_times[middle] as f64,
+        };
+        
+        let ttfbs: Vec<u128> = requests.iter().filter_map(|r| r.ttfb).collect();
+        let average_ttfb = (!ttfbs.is_empty())
+            .then(|| ttfbs.iter().sum::<u128>() as f64 / ttfbs.len() as f64);
+        
+        let average_response_time = if measured_requests > 0 {
+            total_response_time as f64 / measured_requests as f64
         } else {
             0.0
         };
         
         // Calculate standard deviation
-        for result in &requests {
+        for result in requests.iter().filter(|r| r.has_latency()) {
             let diff = result.response_time as f64 - average_response_time;
             sum_squared_diff += diff * diff;
         }
         
-        let response_time_std_dev = if total_requests > 1 {
-            (sum_squared_diff / (total_requests as f64 - 1.0)).sqrt()
+        let response_time_std_dev = if measured_requests > 1 {
+            (sum_squared_diff / (measured_requests as f64 - 1.0)).sqrt()
         } else {
             0.0
         };
@@ -154,37 +1411,666 @@ impl LoadTestResults {
         
         // Create response time distribution for histograms
         let mut response_time_distribution = HashMap::new();
-        if !requests.is_empty() {
+        if measured_requests > 0 {
             // Create buckets for response times
             let bucket_size = if max_response_time > 1000 { 100 } else { 10 };
-            for result in &requests {
+            for result in requests.iter().filter(|r| r.has_latency()) {
                 let bucket = (result.response_time / bucket_size) * bucket_size;
                 let bucket_key = format!("{}-{}", bucket, bucket + bucket_size);
                 *response_time_distribution.entry(bucket_key).or_insert(0) += 1;
             }
         }
         
-        Self {
+        // Group requests by burst for burst mode
+        let mut burst_groups: HashMap<usize, Vec<&RequestResult>> = HashMap::new();
+        for result in &requests {
+            if let Some(burst) = result.burst {
+                burst_groups.entry(burst).or_default().push(result);
+            }
+        }
+        
+        let mut bursts: Vec<BurstStats> = burst_groups.into_iter()
+            .map(|(index, group)| {
+                let times: Vec<u128> = group.iter()
+                    .filter(|r| r.has_latency())
+                    .map(|r| r.response_time)
+                    .collect();
+                BurstStats {
+                    index,
+                    requests: group.len(),
+                    failed: group.iter().filter(|r| r.is_failure()).count(),
+                    average_response_time: average(&times),
+                    min_response_time: times.iter().copied().min().unwrap_or(0),
+                    max_response_time: times.iter().copied().max().unwrap_or(0),
+                }
+            })
+            .collect();
+        bursts.sort_by_key(|b| b.index);
+        
+        let queue_wait = QueueWaitStats::from_requests(&requests);
+        let by_host = HostStats::by_host(&requests);
+        let by_target = HostStats::by_target(&requests);
+        let by_step = HostStats::by_step(&requests);
+        let mut body_usage = HashMap::new();
+        for body in requests.iter().filter_map(|r| r.body.as_ref()) {
+            *body_usage.entry(body.clone()).or_insert(0) += 1;
+        }
+        
+        let mut results = Self {
+            schema_version: RESULTS_SCHEMA_VERSION,
+            run_id: String::new(),
+            sni: None,
             total_requests,
+            configured_requests: total_requests,
+            attempted_requests: total_requests,
             successful_requests,
             failed_requests,
+            expected_failures,
+            error_tiers,
             average_response_time,
             min_response_time,
             max_response_time,
+            median_response_time,
+            average_ttfb,
+            percentiles: None,
             duration,
             duration_secs,
             status_codes,
+            http_versions,
             errors,
             requests,
             throughput,
             total_data_transferred: if has_all_response_sizes { Some(total_data) } else { None },
             response_time_std_dev,
+            truncated_responses,
+            cancelled_requests,
+            retries,
+            retry_budget_exhausted: false,
+            aborted: None,
+            connections,
+            streaming,
+            server_timing,
+            intermittency,
+            size_correlation,
             transfer_rate: if has_all_response_sizes && duration_secs > 0.0 {
                 Some(total_data as f64 / duration_secs)
             } else {
                 None
             },
+            goodput: if has_all_successful_sizes && duration_secs > 0.0 {
+                Some(successful_data as f64 / duration_secs)
+            } else {
+                None
+            },
             response_time_distribution,
+            bursts,
+            by_host,
+            by_target,
+            by_step,
+            body_usage,
+            queue_wait,
+            apdex: None,
+            latency_bands: Vec::new(),
+            thresholds: None,
+            threshold_violations: Vec::new(),
+            passed: true,
+            conditional: None,
+            keepalive: None,
+        };
+        results.percentiles = Percentiles::from_results(&results);
+        results
+    }
+    
+    /// Parse results from JSON, migrating older schema versions
+    pub fn from_json_str(json: &str) -> Result<Self> {
+        let mut value: Value = serde_json::from_str(json)?;
+        Self::migrate(&mut value);
+        Self::from_json_value(value)
+    }
+    
+    /// Load results previously saved as JSON, migrating older schema versions
+    #[instrument(skip_all, fields(path = %path.as_ref().display()))]
+    pub async fn load_json<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path_ref = path.as_ref();
+        debug!("Loading results from file: {}", path_ref.display());
+        
+        let content = tokio::fs::read_to_string(path_ref).await
+            .map_err(|e| Error::DataLoad {
+                path: path_ref.to_path_buf(),
+                source: Box::new(e),
+            })?;
+        
+        Self::from_json_str(&content)
+            .map_err(|e| Error::DataLoad {
+                path: path_ref.to_path_buf(),
+                source: Box::new(e),
+            })
+    }
+    
+    /// Save the results as JSON, e.g. to use them as a baseline later
+    #[instrument(skip_all, fields(path = %path.as_ref().display()))]
+    pub async fn save_json<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path_ref = path.as_ref();
+        debug!("Saving results to file: {}", path_ref.display());
+        
+        let json = serde_json::to_string(self)
+            .map_err(Error::Serialization)?;
+        tokio::fs::write(path_ref, json).await?;
+        
+        Ok(())
+    }
+    
+    /// Deserialize already-migrated results, restoring fields that aren't serialized
+    pub(crate) fn from_json_value(value: Value) -> Result<Self> {
+        let mut results: LoadTestResults = serde_json::from_value(value)?;
+        
+        // The `Duration` itself is not serialized, restore it from the seconds value
+        results.duration = duration_from_secs(results.duration_secs);
+        results.duration_secs = results.duration.as_secs_f64();
+        // Results saved before percentiles were recorded
+        if results.percentiles.is_none() {
+            results.percentiles = Percentiles::from_results(&results);
+        }
+        Ok(results)
+    }
+    
+    /// Upgrade serialized results from an older schema version in place
+    pub(crate) fn migrate(value: &mut Value) {
+        let Some(object) = value.as_object_mut() else {
+            return;
+        };
+        
+        let version = object.get("schema_version")
+            .and_then(Value::as_u64)
+            .unwrap_or(1) as u32;
+        
+        if version < 2 {
+            // v1 had no configured/attempted counts, every request was configured and attempted
+            debug!("Migrating results from schema version {}", version);
+            let total = object.get("total_requests").cloned().unwrap_or(Value::from(0));
+            object.entry("configured_requests").or_insert_with(|| total.clone());
+            object.entry("attempted_requests").or_insert(total);
+        }
+        
+        object.insert("schema_version".to_string(), Value::from(RESULTS_SCHEMA_VERSION));
+    }
+    
+    /// Merge two sets of results into one, e.g. when resuming from a checkpoint
+    ///
+    /// Only active test time is counted: the merged duration is the sum of both
+    /// durations, so any gap between the runs does not lower the throughput.
+    pub fn merge(&self, other: &LoadTestResults) -> Self {
+        let mut requests = Vec::with_capacity(self.requests.len() + other.requests.len());
+        requests.extend(self.requests.iter().cloned());
+        // The other run's start and completion times (and dispatch order) continue after this run's
+        let offset = (self.duration_secs * 1000.0) as u128;
+        let index_offset = self.requests.iter().filter_map(|r| r.index).max().map_or(0, |max| max.saturating_add(1));
+        requests.extend(other.requests.iter().cloned().map(|mut request| {
+            request.index = request.index.map(|index| index.saturating_add(index_offset));
+            request.started_at = request.started_at.map(|started_at| started_at.saturating_add(offset));
+            request.completed_at = request.completed_at.map(|completed_at| completed_at.saturating_add(offset));
+            request
+        }));
+        
+        // Saved durations can be arbitrarily large, so the sum saturates instead of overflowing
+        let duration = duration_from_secs(self.duration_secs).saturating_add(duration_from_secs(other.duration_secs));
+        let mut merged = Self::new(requests, duration);
+        merged.configured_requests = self.configured_requests + other.configured_requests;
+        merged.attempted_requests = self.attempted_requests + other.attempted_requests;
+        merged.retry_budget_exhausted = self.retry_budget_exhausted || other.retry_budget_exhausted;
+        merged.aborted = self.aborted.clone().or_else(|| other.aborted.clone());
+        merged.run_id = if self.run_id.is_empty() { other.run_id.clone() } else { self.run_id.clone() };
+        merged.sni = self.sni.clone().or_else(|| other.sni.clone());
+        
+        if let Some(conditional) = self.conditional.as_ref().or(other.conditional.as_ref()) {
+            merged.conditional = Some(ConditionalStats::from_requests(&conditional.etag, &merged.requests));
+        }
+        if self.keepalive.is_some() || other.keepalive.is_some() {
+            merged.keepalive = Some(KeepAliveStats::from_requests(&merged.requests));
+        }
+        
+        // Keep the percentile method of either side
+        if let Some(percentiles) = self.percentiles.or(other.percentiles) {
+            merged = merged.with_percentile_method(percentiles.method);
+        }
+        
+        // Keep the latency bands if either side was configured for them
+        let bands = if self.latency_bands.is_empty() { &other.latency_bands } else { &self.latency_bands };
+        if !bands.is_empty() {
+            let boundaries: Vec<u64> = bands.iter().filter_map(|band| band.to_ms).collect();
+            merged = merged.with_latency_bands(&boundaries);
+        }
+        
+        // Keep the Apdex score if either side was configured for it
+        if let Some(apdex) = self.apdex.or(other.apdex) {
+            merged = merged.with_apdex(Duration::from_millis(apdex.threshold_ms));
+        }
+        
+        // Check the merged run against the thresholds of either side
+        match self.thresholds.or(other.thresholds) {
+            Some(thresholds) => merged.with_thresholds(thresholds),
+            None => merged,
+        }
+    }
+    
+    /// Calculate the Apdex score for the given satisfied threshold
+    pub fn with_apdex(mut self, threshold: Duration) -> Self {
+        self.apdex = Some(Apdex::calculate(&self.requests, threshold));
+        self
+    }
+    
+    /// Recalculate the percentiles with the given method
+    pub fn with_percentile_method(mut self, method: PercentileMethod) -> Self {
+        self.percentiles = Percentiles::calculate(&self, method);
+        self
+    }
+    
+    /// Check the results against the thresholds, setting `passed` and the violations
+    pub fn with_thresholds(mut self, thresholds: Thresholds) -> Self {
+        self.threshold_violations = thresholds.evaluate(&self);
+        self.passed = self.threshold_violations.is_empty();
+        self.thresholds = Some(thresholds);
+        self
+    }
+    
+    /// Calculate the share of successful requests per latency band, given the
+    /// band boundaries in milliseconds
+    pub fn with_latency_bands(mut self, boundaries: &[u64]) -> Self {
+        self.latency_bands = LatencyBand::calculate(&self.requests, boundaries);
+        self
+    }
+    
+    /// Share of successful requests (0-100)
+    pub fn success_rate(&self) -> f64 {
+        if self.total_requests > 0 {
+            self.successful_requests as f64 / self.total_requests as f64 * 100.0
+        } else {
+            0.0
+        }
+    }
+    
+    /// Per-second time series of the started and completed requests, one entry for every
+    /// (started) second of the run and every second a request started or completed in
+    ///
+    /// Requests without a start or completion time (e.g. cancelled ones) are left out of
+    /// the respective counts. Empty seconds are only filled in for the first
+    /// `MAX_TIMELINE_SECONDS` of the run, so the series stays bounded by the number of
+    /// requests for loaded results with bogus durations or timestamps.
+    ///
+    /// Rates of a final partial second are scaled up to the part of the second the run
+    /// covered, but by at most `1 / MIN_COVERED_FRACTION`.
+    pub fn timeline(&self) -> Vec<TimelineSecond> {
+        let second_of = |ms: u128| u64::try_from(ms / 1000).unwrap_or(u64::MAX);
+        let mut seconds: BTreeMap<u64, (usize, Vec<&RequestResult>)> = BTreeMap::new();
+        for request in &self.requests {
+            if let Some(started_at) = request.started_at {
+                seconds.entry(second_of(started_at)).or_default().0 += 1;
+            }
+            if let Some(completed_at) = request.completed_at {
+                seconds.entry(second_of(completed_at)).or_default().1.push(request);
+            }
+        }
+        // Casting saturates, and a NaN duration becomes zero
+        let span = (self.duration_secs.ceil() as u64).min(MAX_TIMELINE_SECONDS);
+        for second in 0..span {
+            seconds.entry(second).or_default();
         }
+        
+        seconds.into_iter().map(|(second, (started, requests))| {
+            let mut times: Vec<u128> = requests.iter().map(|r| r.response_time).collect();
+            times.sort_unstable();
+            let length = self.covered_length(second);
+            
+            TimelineSecond {
+                timestamp: second,
+                started,
+                completed: requests.len(),
+                rps: requests.len() as f64 / length,
+                avg_latency: if times.is_empty() { 0.0 } else { times.iter().sum::<u128>() as f64 / times.len() as f64 },
+                p95: if times.is_empty() {
+                    0
+                } else {
+                    let rank = (0.95 * times.len() as f64).ceil() as usize;
+                    times[rank.clamp(1, times.len()) - 1]
+                },
+                error_count: requests.iter().filter(|r| r.is_failure()).count(),
+            }
+        }).collect()
+    }
+    
+    /// Requests started per second, one `(second, rate)` entry for every second of the
+    /// timeline (see `timeline`)
+    ///
+    /// Unlike the timeline's `rps`, requests are counted by their start time, so the
+    /// series shows the offered load rather than the completions.
+    pub fn requests_per_second(&self) -> Vec<(u64, f64)> {
+        self.timeline().into_iter()
+            .map(|second| (second.timestamp, second.started as f64 / self.covered_length(second.timestamp)))
+            .collect()
+    }
+    
+    /// Part of the given second (in seconds) the run covered, used to scale a final
+    /// partial second up to a rate
+    ///
+    /// A sliver of a second is counted as `MIN_COVERED_FRACTION`, so a handful of requests
+    /// in it is not scaled up to a huge rate. Seconds past the end of the run count in full.
+    fn covered_length(&self, second: u64) -> f64 {
+        let covered = (self.duration_secs - second as f64).min(1.0);
+        if covered > 0.0 {
+            covered.max(MIN_COVERED_FRACTION)
+        } else {
+            1.0
+        }
+    }
+    
+    /// Save the per-second time series as CSV (see `timeline`)
+    #[instrument(skip_all, fields(path = %path.as_ref().display()))]
+    pub async fn save_timeseries_csv<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path_ref = path.as_ref();
+        debug!("Saving time series to file: {}", path_ref.display());
+        
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        for second in self.timeline() {
+            writer.serialize(second)
+                .map_err(|e| Error::Other(format!("Failed to write time series: {}", e)))?;
+        }
+        let csv = writer.into_inner()
+            .map_err(|e| Error::Other(format!("Failed to write time series: {}", e)))?;
+        tokio::fs::write(path_ref, csv).await?;
+        
+        Ok(())
+    }
+    
+    /// Number of requests that completed: the requests sent, less those cancelled in flight
+    pub fn completed_requests(&self) -> usize {
+        self.total_requests - self.cancelled_requests
     }
-} 
\ No newline at end of file
+    
+    /// One-line summary of the run, e.g. "100 requests, 98.0% success, p95 42 ms, 250.3 req/s"
+    pub fn summary(&self) -> String {
+        let p95 = match self.percentiles {
+            Some(percentiles) => format!("{:.0} ms", percentiles.p95),
+            None => "-".to_string(),
+        };
+        
+        format!("{} requests, {:.1}% success, p95 {}, {:.1} req/s",
+            self.total_requests,
+            self.success_rate(),
+            p95,
+            self.throughput
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn request(response_time: u128) -> RequestResult {
+        RequestResult { status: Some(200), response_time, success: true, ..Default::default() }
+    }
+    
+    #[test]
+    fn p95_threshold_uses_the_reported_percentiles() {
+        let mut results = LoadTestResults::new((1..=100).map(request).collect(), Duration::from_secs(1));
+        let mut percentiles = results.percentiles.unwrap();
+        percentiles.p95 = 500.0;
+        results.percentiles = Some(percentiles);
+        let thresholds = Thresholds { max_p95_ms: Some(200.0), ..Default::default() };
+        
+        let violations = thresholds.evaluate(&results);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].actual, Some(500.0));
+        assert!(results.summary().contains("p95 500 ms"));
+    }
+    
+    #[test]
+    fn merging_saturates_huge_durations() {
+        let mut first = LoadTestResults::new(vec![request(10)], Duration::from_secs(1));
+        first.duration_secs = f64::MAX;
+        let mut second = first.clone();
+        second.requests[0].completed_at = Some(u128::MAX);
+        
+        let merged = first.merge(&second);
+        assert_eq!(merged.duration, Duration::MAX);
+        assert_eq!(merged.requests[1].completed_at, Some(u128::MAX));
+    }
+    
+    #[test]
+    fn timeline_fills_in_empty_seconds() {
+        let requests = [500, 2500].into_iter()
+            .map(|completed_at| RequestResult { completed_at: Some(completed_at), ..request(10) })
+            .collect();
+        let results = LoadTestResults::new(requests, Duration::from_millis(3500));
+        let completed: Vec<(u64, usize)> = results.timeline().iter()
+            .map(|second| (second.timestamp, second.completed))
+            .collect();
+        
+        assert_eq!(completed, [(0, 1), (1, 0), (2, 1), (3, 0)]);
+    }
+    
+    #[test]
+    fn timeline_is_bounded_for_bogus_loaded_results() {
+        let requests = [Some(u128::MAX), Some(1_000_000_000_000), None].into_iter()
+            .map(|completed_at| RequestResult { completed_at, ..request(10) })
+            .collect();
+        let mut results = LoadTestResults::new(requests, Duration::from_secs(1));
+        results.duration_secs = 1e300;
+        let timeline = results.timeline();
+        
+        assert_eq!(timeline.len(), MAX_TIMELINE_SECONDS as usize + 2);
+        assert_eq!(timeline.last().unwrap().timestamp, u64::MAX);
+    }
+    
+    #[test]
+    fn requests_per_second_scales_partial_seconds_up_to_a_bound() {
+        let requests = [100, 200, 1100, 1200, 2000, 2001].into_iter()
+            .map(|started_at| RequestResult { started_at: Some(started_at), ..request(10) })
+            .collect();
+        let results = LoadTestResults::new(requests, Duration::from_millis(2001));
+        let timeline = results.timeline();
+        let started: Vec<usize> = timeline.iter().map(|second| second.started).collect();
+        
+        assert_eq!(started, [2, 2, 2]);
+        assert_eq!(results.requests_per_second(), [(0, 2.0), (1, 2.0), (2, 2.0 / MIN_COVERED_FRACTION)]);
+        
+        let results = LoadTestResults { duration_secs: 2.5, ..results };
+        assert_eq!(results.requests_per_second()[2], (2, 4.0));
+    }
+    
+    #[test]
+    fn connection_setup_is_estimated_from_ttfb() {
+        let requests = [(true, 30), (true, 50), (false, 10), (false, 10), (false, 1000)].into_iter()
+            .map(|(new, ttfb)| RequestResult { new_connection: Some(new), ttfb: Some(ttfb), ..request(10) })
+            .collect::<Vec<_>>();
+        
+        let connections = ConnectionStats::from_requests(&requests[..4]).unwrap();
+        assert_eq!(connections.new_connections, 2);
+        assert_eq!(connections.average_setup, Some(30.0));
+        // Never negative, and unknown without reused connections
+        assert_eq!(ConnectionStats::from_requests(&requests).unwrap().average_setup, Some(0.0));
+        assert_eq!(ConnectionStats::from_requests(&requests[..2]).unwrap().average_setup, None);
+    }
+    
+    #[test]
+    fn status_runs_follow_the_dispatch_order() {
+        let failed = |index| RequestResult { index: Some(index), status: Some(500), success: false, ..request(10) };
+        let succeeded = |index| RequestResult { index: Some(index), ..request(10) };
+        // Completion order interleaves the two runs
+        let requests = vec![succeeded(0), failed(2), succeeded(1), failed(3)];
+        
+        let intermittency = Intermittency::from_requests(&requests).unwrap();
+        assert_eq!(intermittency.run_count, 2);
+        assert_eq!((intermittency.runs[1].first, intermittency.runs[1].last), (2, 3));
+        assert_eq!(intermittency.longest_failure_run, 2);
+    }
+    
+    #[test]
+    fn status_runs_are_capped() {
+        let requests: Vec<RequestResult> = (0..MAX_STATUS_RUNS + 10)
+            .map(|i| RequestResult { status: Some(if i % 2 == 0 { 200 } else { 500 }), success: i % 2 == 0, ..request(10) })
+            .collect();
+        
+        let intermittency = Intermittency::from_requests(&requests).unwrap();
+        assert_eq!(intermittency.run_count, MAX_STATUS_RUNS + 10);
+        assert_eq!(intermittency.runs.len(), MAX_STATUS_RUNS);
+        assert_eq!(intermittency.failure_runs, (MAX_STATUS_RUNS + 10) / 2);
+    }
+    
+    #[test]
+    fn p95_threshold_fails_without_successful_requests() {
+        let failed = RequestResult { error: Some("refused".to_string()), ..Default::default() };
+        let results = LoadTestResults::new(vec![failed], Duration::from_secs(1))
+            .with_thresholds(Thresholds { max_p95_ms: Some(200.0), ..Default::default() });
+        
+        assert!(!results.passed);
+        assert_eq!(results.threshold_violations[0].actual, None);
+    }
+    
+    #[test]
+    fn apdex_counts_satisfied_tolerating_and_frustrated_requests() {
+        let mut requests: Vec<RequestResult> = [50, 100, 150, 400, 401].into_iter().map(request).collect();
+        requests.push(RequestResult { status: Some(500), success: false, ..request(10) });
+        requests.push(RequestResult { cancelled: true, ..request(5000) });
+        
+        // T = 100 ms: 2 satisfied, 2 tolerating (up to 400 ms), 2 frustrated (slow or failed)
+        let apdex = Apdex::calculate(&requests, Duration::from_millis(100));
+        assert_eq!((apdex.satisfied, apdex.tolerating, apdex.frustrated), (2, 2, 2));
+        assert_eq!(apdex.score, (2.0 + 2.0 / 2.0) / 6.0);
+    }
+    
+    #[test]
+    fn v1_results_are_migrated_on_load() {
+        let v1 = serde_json::json!({
+            "total_requests": 2,
+            "successful_requests": 1,
+            "failed_requests": 1,
+            "average_response_time": 15.0,
+            "min_response_time": 10,
+            "max_response_time": 20,
+            "duration": 1.5,
+            "status_codes": { "200": 1, "500": 1 },
+            "errors": { "HTTP Error: 500 Internal Server Error": 1 },
+            "requests": [
+                { "status": 200, "response_time": 10, "success": true, "error": null, "response_size": 2 },
+                { "status": 500, "response_time": 20, "success": false,
+                  "error": "HTTP Error: 500 Internal Server Error", "response_size": null },
+            ],
+            "throughput": 1.33,
+            "total_data_transferred": 2,
+            "response_time_std_dev": 5.0,
+            "transfer_rate": 1.33,
+        });
+        let results = LoadTestResults::from_json_str(&v1.to_string()).unwrap();
+        
+        assert_eq!(results.schema_version, RESULTS_SCHEMA_VERSION);
+        assert_eq!(results.configured_requests, 2);
+        assert_eq!(results.attempted_requests, 2);
+        assert_eq!(results.duration, Duration::from_millis(1500));
+        assert_eq!(results.requests.len(), 2);
+        assert!(results.percentiles.is_some());
+        assert_eq!(results.completed_requests(), 2);
+    }
+    
+    #[test]
+    fn goodput_only_counts_successful_bytes() {
+        let success = RequestResult { response_size: Some(100), ..request(10) };
+        let error = RequestResult { status: Some(500), success: false, response_size: Some(10_000), ..request(10) };
+        let results = LoadTestResults::new(vec![success.clone(), success, error.clone(), error], Duration::from_secs(2));
+        
+        // 20.2 KB moved, of which only 200 bytes were useful
+        assert_eq!(results.transfer_rate, Some(20_200.0 / 2.0));
+        assert_eq!(results.goodput, Some(200.0 / 2.0));
+    }
+    
+    #[test]
+    fn error_tiers_split_connection_and_http_errors() {
+        let refused = RequestResult { error: Some("error trying to connect: Connection refused".to_string()), ..Default::default() };
+        let dropped = RequestResult {
+            status: Some(200),
+            error: Some(format!("{}: connection closed", BODY_READ_ERROR)),
+            ..Default::default()
+        };
+        let server_error = RequestResult {
+            status: Some(500),
+            error: Some(format!("{}: 500 Internal Server Error", HTTP_ERROR)),
+            ..Default::default()
+        };
+        let expected = RequestResult { status: Some(404), expected_failure: true, ..Default::default() };
+        let requests = vec![refused.clone(), refused, dropped, server_error.clone(), server_error, expected, request(10), request(10)];
+        
+        let tiers = ErrorTiers::from_requests(&requests);
+        assert_eq!((tiers.transport, tiers.application), (3, 2));
+        assert_eq!(tiers.transport_percent, 37.5);
+        assert_eq!(tiers.application_percent, 25.0);
+        assert_eq!(LoadTestResults::new(requests, Duration::from_secs(1)).failed_requests, 5);
+    }
+    
+    #[test]
+    fn latency_bands_split_successful_requests_at_the_boundaries() {
+        let mut requests: Vec<RequestResult> = [10, 99, 100, 150, 299, 300, 450, 1000].into_iter().map(request).collect();
+        requests.push(RequestResult { status: Some(500), success: false, ..request(5) });
+        let results = LoadTestResults::new(requests, Duration::from_secs(1)).with_latency_bands(&[300, 100]);
+        let bands: Vec<(u64, Option<u64>, usize, f64)> = results.latency_bands.iter()
+            .map(|band| (band.from_ms, band.to_ms, band.count, band.percent))
+            .collect();
+        
+        assert_eq!(bands, [(0, Some(100), 2, 25.0), (100, Some(300), 3, 37.5), (300, None, 3, 37.5)]);
+    }
+    
+    #[test]
+    fn classifiers_tell_failures_apart() {
+        let timeout = RequestResult { error: Some("error sending request: operation timed out".to_string()), ..Default::default() };
+        let refused = RequestResult { error: Some("error trying to connect: Connection refused".to_string()), ..Default::default() };
+        let server_error = RequestResult {
+            status: Some(503),
+            error: Some(format!("{}: 503 Service Unavailable", HTTP_ERROR)),
+            ..Default::default()
+        };
+        let not_found = RequestResult { status: Some(404), error: Some(format!("{}: 404 Not Found", HTTP_ERROR)), ..Default::default() };
+        let expected = RequestResult { status: Some(404), expected_failure: true, ..Default::default() };
+        let success = request(10);
+        let classes = |result: &RequestResult| (
+            result.is_timeout(),
+            result.is_connection_error(),
+            result.is_http_error(),
+            result.is_retryable(),
+            result.is_failure(),
+        );
+        
+        assert_eq!(classes(&timeout), (true, false, false, true, true));
+        assert_eq!(classes(&refused), (false, true, false, true, true));
+        assert_eq!(classes(&server_error), (false, false, true, true, true));
+        assert_eq!(classes(&not_found), (false, false, true, false, true));
+        assert_eq!(classes(&expected), (false, false, false, false, false));
+        assert_eq!(classes(&success), (false, false, false, false, false));
+        assert_eq!(server_error.http_status_class(), Some(5));
+        assert_eq!(refused.http_status_class(), None);
+        assert_eq!(server_error.error_message(), Some("503 Service Unavailable"));
+        assert_eq!(success.error_message(), None);
+    }
+    
+    #[test]
+    fn the_summary_line_holds_the_key_metrics() {
+        let mut requests: Vec<RequestResult> = (1..=3).map(|i| request(i * 100)).collect();
+        requests.push(RequestResult { status: Some(500), response_time: 50, ..Default::default() });
+        let exact = PercentileMethod { exact: true, ..Default::default() };
+        let results = LoadTestResults::new(requests, Duration::from_secs(2)).with_percentile_method(exact);
+        
+        assert_eq!(results.summary(), "4 requests, 75.0% success, p95 300 ms, 2.0 req/s");
+        assert_eq!(LoadTestResults::new(Vec::new(), Duration::from_secs(1)).summary(), "0 requests, 0.0% success, p95 -, 0.0 req/s");
+    }
+    
+    #[test]
+    fn host_stats_do_not_count_expected_failures_as_failed() {
+        let host = |result: RequestResult| RequestResult { host: Some("api.test".to_string()), ..result };
+        let requests = vec![
+            host(request(10)),
+            host(RequestResult { status: Some(404), expected_failure: true, ..Default::default() }),
+            host(RequestResult { status: Some(500), ..Default::default() }),
+        ];
+        let stats = &HostStats::by_host(&requests)["api.test"];
+        
+        assert_eq!((stats.requests, stats.successful, stats.failed), (3, 1, 1));
+    }
+}
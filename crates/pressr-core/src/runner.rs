@@ -1,10 +1,35 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
 use std::time::{Duration, Instant};
-use reqwest::{Client, Method, header::HeaderMap};
-use futures::{stream, StreamExt};
-use tracing::{debug, info, instrument, warn};
+use reqwest::{Client, Method, Request, RequestBuilder, Response, StatusCode, redirect::Policy, multipart::{Form, Part}, header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, IF_NONE_MATCH}};
+#[cfg(feature = "sni")]
+use reqwest::{dns::{Addrs, Resolve, Resolving}, header::HOST};
+use bytes::Bytes;
+use futures::{future, stream, Stream, StreamExt};
+use hyper::client::connect::HttpInfo;
+#[cfg(feature = "sni")]
+use hyper::client::connect::dns::Name;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use regex::Regex;
+use tokio::sync::{mpsc, watch, Semaphore};
+use tokio::task::JoinHandle;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{debug, info, instrument, warn, Instrument};
 
-use crate::data::RequestData;
-use crate::result::{RequestResult, LoadTestResults};
+use crate::conditional::{fetch_etag, ConditionalStats};
+use crate::keepalive::KeepAliveStats;
+use crate::data::{expand_env_value, substitute_path_variables, BodyType, RequestData, StepFailure};
+use crate::metrics::{CallbackExporter, MetricsExporter, NoopExporter, ResultCallback};
+use crate::percentile::PercentileMethod;
+use crate::result::{generate_run_id, ErrorTier, RequestResult, LoadTestResults, Thresholds, BODY_READ_ERROR, CANCELLED_ERROR, EXTRACT_ERROR, HTTP_ERROR};
+use crate::template::{request_seed, TemplateContext};
 use crate::error::{Error, Result};
 
 /// Configuration for the load test runner
@@ -27,11 +52,450 @@ pub struct Config {
     
     /// Request timeout in seconds
     pub timeout: u64,
+    
+    /// Satisfied threshold for the Apdex score (None to skip Apdex)
+    pub apdex_threshold: Option<Duration>,
+    
+    /// Latency band boundaries in milliseconds (empty to skip latency bands)
+    pub latency_bands: Vec<u64>,
+    
+    /// Pass/fail thresholds checked against the results (None to skip the check)
+    pub thresholds: Option<Thresholds>,
+    
+    /// How the response time percentiles of the results are computed
+    pub percentiles: PercentileMethod,
+    
+    /// Send requests in timed bursts instead of a continuous stream
+    pub burst: Option<BurstProfile>,
+    
+    /// Pause of each concurrency slot after a request completes, and between the steps of a
+    /// scenario, before sending the next request (None to send the next one right away)
+    ///
+    /// Each slot models a user that thinks before acting again, so the throughput of this
+    /// closed loop drops to at most `concurrency / (response time + think time)`. This is
+    /// not a rate limit: the throughput still grows with the concurrency and shrinks as the
+    /// responses slow down. Bursts follow their own schedule and ignore the think time.
+    ///
+    /// A slot thinks after reacquiring its permit, before its next request: the think time
+    /// is not part of any response time (nor of a scenario iteration's), a stop or the end
+    /// of a timed run cuts it short, and the requests a slot completed are never cancelled
+    /// because it is still thinking.
+    pub think_time: Option<Duration>,
+    
+    /// Each think time is picked at random within this much of `think_time`, either way
+    pub think_time_jitter: Duration,
+    
+    /// Status codes counted as successful in addition to 2xx
+    pub expected_statuses: Vec<u16>,
+    
+    /// Status codes counted as expected failures: neither successful nor failed
+    /// (e.g. 404 during a negative test)
+    pub expected_failures: Vec<u16>,
+    
+    /// Capture the target's ETag first, then send every request with `If-None-Match`
+    /// and count `304 Not Modified` responses as successful
+    pub etag_validation: bool,
+    
+    /// Whether to read response bodies. When disabled, responses are dropped as soon as
+    /// the headers arrive, so response sizes and transfer rates are unavailable.
+    /// Bodies of HEAD responses are never read: their size is the announced `Content-Length`.
+    pub read_body: bool,
+    
+    /// Seed for reproducible request randomness: template values, body pool picks and retry
+    /// and think time jitter (None for a fresh seed every run)
+    pub seed: Option<u64>,
+    
+    /// Index of the first request, when a run is split into several batches
+    pub first_index: usize,
+    
+    /// Maximum number of (decoded) body bytes read per response; larger bodies are
+    /// truncated instead of being loaded whole (None for no limit)
+    pub max_body_read: Option<usize>,
+    
+    /// Drain response bodies counting their bytes, never keeping them in memory
+    ///
+    /// Bodies are always read chunk by chunk, but whole bodies of unexpected responses
+    /// are otherwise kept to match against `retry_on`. With streaming, retry patterns
+    /// only match error messages, and memory stays bounded whatever the body sizes.
+    pub stream_body: bool,
+    
+    /// Record the values of templated headers on each result (e.g. generated idempotency
+    /// keys), for detailed reports. Values of sensitive headers such as `Authorization`,
+    /// cookies and API keys are recorded as "[redacted]".
+    pub record_headers: bool,
+    
+    /// Targets the requests are spread across in proportion to their weights (weighted
+    /// round-robin), with per-host and per-target statistics (empty to send every request
+    /// to `url`)
+    pub targets: Vec<Target>,
+    
+    /// Identifier recorded in the results (None to generate a new one for every run)
+    pub run_id: Option<String>,
+    
+    /// Maximum number of times a request failing with a transport error or retryable status
+    /// (see `retry_statuses`) is retried
+    pub retries: u32,
+    
+    /// Status codes of failed responses that are retried (empty for any 5xx)
+    pub retry_statuses: Vec<u16>,
+    
+    /// Patterns marking a failed request as retryable when they match its error message
+    /// or response body (unless `stream_body` is set), in addition to transport errors
+    /// and retryable statuses
+    pub retry_on: Vec<RetryMatcher>,
+    
+    /// Delay before the first retry, doubled for every further retry of the same request
+    pub retry_backoff: Duration,
+    
+    /// Randomize each retry delay between half and all of its backoff
+    pub retry_jitter: bool,
+    
+    /// Cap on the retries of a whole run, as a percentage of its request count
+    /// (None for no cap beyond `retries` per request)
+    pub retry_budget: Option<f64>,
+    
+    /// Time requests in flight get to complete after a stop is requested before they are cancelled
+    pub shutdown_grace: Duration,
+    
+    /// Stop the run early once more than this percentage (0-100) of the completed requests
+    /// failed (None to never abort)
+    pub abort_on_error_rate: Option<f64>,
+    
+    /// Number of requests that must complete before `abort_on_error_rate` is checked
+    pub abort_min_requests: usize,
+    
+    /// Keep sending requests for this long (None to send exactly `request_count` requests)
+    ///
+    /// `request_count` is then an upper bound (`usize::MAX` for none). Requests still in
+    /// flight at the deadline are allowed to finish.
+    pub duration: Option<Duration>,
+    
+    /// Time an idle pooled connection is kept open before it is closed (None for the
+    /// client default of 90 s)
+    pub idle_timeout: Option<Duration>,
+    
+    /// Grow the concurrency linearly from 1 to `concurrency` over this period (None to
+    /// start at full concurrency)
+    ///
+    /// The ramp-up counts towards `duration`: a timed run shorter than the ramp-up never
+    /// reaches full concurrency. Burst mode and batches after the first ignore it.
+    pub ramp_up: Option<Duration>,
+    
+    /// TLS server name (SNI) presented instead of the URL's host (None to use the URL's host)
+    ///
+    /// Requests are sent to this name, which the client resolves to the URL's host
+    /// (see `Runner::create_client`), with the target's `Host` header unless one is configured.
+    /// Only the host of `url` is resolved, so it cannot be combined with targets or a scenario.
+    #[cfg(feature = "sni")]
+    pub sni: Option<String>,
+    
+    /// Authentication sent with every request, replacing any configured `Authorization` header
+    pub auth: Option<Auth>,
+    
+    /// Keep cookies set by responses and send them with later requests (see `Runner::create_client`)
+    ///
+    /// The cookie jar belongs to the client, so all concurrent requests share one session:
+    /// a cookie set by any response (e.g. a login or warm-up request) is sent by every
+    /// request after it.
+    pub cookie_store: bool,
+    
+    /// Proxy all requests are sent through, e.g. "http://proxy:3128" or "socks5://proxy:1080"
+    /// (None to connect directly)
+    pub proxy: Option<String>,
+    
+    /// Follow redirects (when disabled, 3xx responses are recorded with their own status)
+    pub follow_redirects: bool,
+    
+    /// Maximum number of redirects followed per request; a request redirected more often fails
+    pub max_redirects: usize,
+    
+    /// How request indices are assigned to the concurrent workers (burst mode ignores it)
+    pub dispatch: DispatchMode,
+    
+    /// HTTP version the client speaks (see `Runner::create_client`)
+    pub http_version: HttpVersion,
+    
+    /// `User-Agent` the client sends (see `Runner::create_client`; None for `DEFAULT_USER_AGENT`)
+    ///
+    /// A `User-Agent` header in the configured headers takes precedence.
+    pub user_agent: Option<String>,
+}
+
+/// Assignment of request indices to the concurrent workers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DispatchMode {
+    /// Each request goes to whichever worker is free next
+    #[default]
+    Interleaved,
+    
+    /// Each worker sends a contiguous slice of the indices in order (worker 0 the first
+    /// slice, worker 1 the next, ...), for reproducible partitioning of index-dependent
+    /// templates and sequential variables
+    Partitioned,
+}
+
+/// HTTP version used to connect to the target
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HttpVersion {
+    /// HTTP/1.1, or HTTP/2 when negotiated with the server via TLS ALPN
+    #[default]
+    Auto,
+    
+    /// HTTP/1.1 only
+    Http1,
+    
+    /// HTTP/2 without negotiation ("prior knowledge"), also over plain-text connections;
+    /// requests fail against servers that do not speak HTTP/2
+    Http2PriorKnowledge,
+}
+
+/// Response header with the server's own processing durations
+const SERVER_TIMING: &str = "server-timing";
+
+/// Redirects followed per request by default (the reqwest default)
+pub const DEFAULT_MAX_REDIRECTS: usize = 10;
+
+/// `User-Agent` sent by default, so pressr traffic is identifiable in server logs
+pub const DEFAULT_USER_AGENT: &str = concat!("pressr/", env!("CARGO_PKG_VERSION"));
+
+/// Authentication scheme of the `Authorization` header
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Auth {
+    /// `Authorization: Bearer <token>`
+    Bearer(String),
+    
+    /// `Authorization: Basic <base64(username:password)>`
+    Basic { username: String, password: String },
+}
+
+impl Auth {
+    /// Add the `Authorization` header to a request
+    pub fn apply(&self, builder: RequestBuilder) -> RequestBuilder {
+        match self {
+            Self::Bearer(token) => builder.bearer_auth(token),
+            Self::Basic { username, password } => builder.basic_auth(username, Some(password)),
+        }
+    }
+}
+
+/// Pattern matched against the error message and response body of a failed request
+#[derive(Debug, Clone)]
+pub enum RetryMatcher {
+    /// Matches text containing the string
+    Substring(String),
+    
+    /// Matches text the regular expression matches
+    Regex(Regex),
+}
+
+impl RetryMatcher {
+    /// Create a matcher from a regular expression
+    pub fn regex(pattern: &str) -> Result<Self> {
+        Regex::new(pattern)
+            .map(Self::Regex)
+            .map_err(|e| Error::Other(format!("Invalid retry pattern '{}': {}", pattern, e)))
+    }
+    
+    /// Whether the matcher matches the text
+    pub fn is_match(&self, text: &str) -> bool {
+        match self {
+            Self::Substring(substring) => text.contains(substring.as_str()),
+            Self::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+/// An endpoint of a multi-target run
+#[derive(Debug, Clone)]
+pub struct Target {
+    /// Name of the target in reports (defaults to its method and URL, e.g. "GET https://example.com")
+    pub name: Option<String>,
+    
+    /// URL to send the target's requests to
+    pub url: String,
+    
+    /// HTTP method of the target's requests (None to use the configured method)
+    pub method: Option<Method>,
+    
+    /// JSON body of the target's requests, templated like the data file body
+    /// (None to use the body of the data file)
+    pub body: Option<Value>,
+    
+    /// Relative share of the requests sent to the target
+    pub weight: u32,
+}
+
+impl Target {
+    /// Create a target with weight 1, the configured method and the data file body
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            name: None,
+            url: url.into(),
+            method: None,
+            body: None,
+            weight: 1,
+        }
+    }
+    
+    /// Name of the target in reports, given the configured method
+    pub fn label(&self, default_method: &Method) -> String {
+        self.name.clone().unwrap_or_else(|| {
+            format!("{} {}", self.method.as_ref().unwrap_or(default_method), self.url)
+        })
+    }
+}
+
+/// Burst traffic profile: `burst_size` requests at once, every `burst_interval`
+#[derive(Debug, Clone, Copy)]
+pub struct BurstProfile {
+    /// Number of requests dispatched together in each burst
+    pub burst_size: usize,
+    
+    /// Time between the start of consecutive bursts
+    pub burst_interval: Duration,
+    
+    /// Requests of a burst starting more than this after its scheduled time are counted
+    /// as late, a sign the client cannot keep up with the schedule
+    pub late_tolerance: Duration,
+}
+
+impl Config {
+    /// Start a request with the given headers, sent to the SNI server name (with the
+    /// target's `Host` header unless `headers` has one) when one is configured
+    pub(crate) fn request(&self, client: &Client, method: Method, url: &str, headers: HeaderMap) -> RequestBuilder {
+        #[cfg(feature = "sni")]
+        if let Some(server_name) = &self.sni {
+            let (url, target_host) = with_server_name(url, server_name);
+            let target_host = target_host.filter(|_| !headers.contains_key(HOST));
+            let builder = client.request(method, url).headers(headers);
+            return match target_host {
+                Some(target_host) => builder.header(HOST, target_host),
+                None => builder,
+            };
+        }
+        
+        client.request(method, url).headers(headers)
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            method: Method::GET,
+            headers: HeaderMap::new(),
+            request_count: 100,
+            concurrency: 10,
+            timeout: 30,
+            apdex_threshold: None,
+            latency_bands: Vec::new(),
+            thresholds: None,
+            percentiles: PercentileMethod::default(),
+            burst: None,
+            think_time: None,
+            think_time_jitter: Duration::ZERO,
+            expected_statuses: Vec::new(),
+            expected_failures: Vec::new(),
+            etag_validation: false,
+            read_body: true,
+            seed: None,
+            first_index: 0,
+            max_body_read: None,
+            stream_body: false,
+            record_headers: false,
+            targets: Vec::new(),
+            run_id: None,
+            retries: 0,
+            retry_statuses: Vec::new(),
+            retry_on: Vec::new(),
+            retry_backoff: Duration::ZERO,
+            retry_jitter: false,
+            retry_budget: None,
+            abort_on_error_rate: None,
+            abort_min_requests: 20,
+            shutdown_grace: Duration::from_secs(5),
+            duration: None,
+            idle_timeout: None,
+            ramp_up: None,
+            #[cfg(feature = "sni")]
+            sni: None,
+            auth: None,
+            cookie_store: false,
+            proxy: None,
+            follow_redirects: true,
+            max_redirects: DEFAULT_MAX_REDIRECTS,
+            dispatch: DispatchMode::default(),
+            http_version: HttpVersion::default(),
+            user_agent: None,
+        }
+    }
+}
+
+/// Handle requesting a graceful stop of a running load test
+///
+/// After a stop no new requests are dispatched; requests in flight get the
+/// configured `shutdown_grace` to complete. Clones share the same stop state.
+#[derive(Debug, Clone)]
+pub struct StopHandle {
+    sender: Arc<watch::Sender<bool>>,
+}
+
+impl StopHandle {
+    /// Create a handle that has not been stopped
+    pub fn new() -> Self {
+        Self {
+            sender: Arc::new(watch::channel(false).0),
+        }
+    }
+    
+    /// Request a stop
+    pub fn stop(&self) {
+        self.sender.send_replace(true);
+    }
+    
+    /// Whether a stop was requested
+    pub fn is_stopped(&self) -> bool {
+        *self.sender.borrow()
+    }
+    
+    /// Wait until a stop is requested
+    pub async fn stopped(&self) {
+        let mut receiver = self.sender.subscribe();
+        let _ = receiver.wait_for(|stopped| *stopped).await;
+    }
+}
+
+impl Default for StopHandle {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
+/// Delay before the second attempt of a success probe
+const PROBE_INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Maximum delay between the attempts of a success probe
+const PROBE_MAX_BACKOFF: Duration = Duration::from_secs(5);
+
+/// Results `Runner::run_streaming` buffers for a consumer lagging behind the run
+pub const STREAM_CAPACITY: usize = 1024;
+
+/// Salts separating the random streams of a request (see `Runner::request_rng`) from
+/// the one of its template context
+const RETRY_JITTER_STREAM: u64 = 0x5245_5452_5900_0000;
+const THINK_JITTER_STREAM: u64 = 0x5448_494E_4B00_0000;
+
 /// Load test runner
 #[derive(Debug)]
 pub struct Runner {
+    /// State shared with the tasks of the requests in flight
+    state: Arc<RunnerState>,
+}
+
+/// State of a load test runner
+#[derive(Debug)]
+struct RunnerState {
     /// HTTP client to use for requests
     client: Client,
     
@@ -40,134 +504,1374 @@ pub struct Runner {
     
     /// Optional request data
     data: Option<RequestData>,
+    
+    /// Headers of every request: the configured headers merged over the data file headers
+    headers: HeaderMap,
+    
+    /// Number of requests dispatched so far (counted by the dispatchers, not by
+    /// `execute_request`, so that every dispatched request is accounted for)
+    attempted: AtomicUsize,
+    
+    /// Number of retries sent so far
+    retries_used: AtomicUsize,
+    
+    /// Whether a retry was refused because the retry budget ran out
+    retry_budget_exhausted: AtomicBool,
+    
+    /// Number of requests completed so far, to abort on the error rate
+    completed: AtomicUsize,
+    
+    /// Number of completed requests that failed
+    failed: AtomicUsize,
+    
+    /// Why the run was aborted early, if it was
+    abort_reason: Mutex<Option<String>>,
+    
+    /// Local addresses of the connections used so far, to tell new connections from reused ones
+    connections: Mutex<HashSet<SocketAddr>>,
+    
+    /// ETag sent as `If-None-Match` (ETag validation mode only)
+    etag: OnceLock<HeaderValue>,
+    
+    /// Part names, file names and contents of the files of a multipart body
+    files: OnceLock<Vec<(String, String, Bytes)>>,
+    
+    /// Body read from the body file of the request data
+    body_file: OnceLock<FileBody>,
+    
+    /// Exporter receiving each result as it completes
+    exporter: Arc<dyn MetricsExporter>,
+    
+    /// Callback invoked with each result as it completes
+    observer: Option<CallbackExporter>,
+    
+    /// Stop requests for the running test
+    stop: StopHandle,
+    
+    /// Sender of the results streamed by `run_streaming`
+    stream: Option<mpsc::Sender<RequestResult>>,
+    
+    /// Start of the current run, to timestamp request completions
+    run_start: Mutex<Instant>,
+    
+    /// Identifier of the current run, resolved by `runid` template tokens
+    run_id: Mutex<String>,
 }
 
 impl Runner {
     /// Create a new Runner
-    pub fn new(client: Client, config: Config, data: Option<RequestData>) -> Self {
-        Self {
+    ///
+    /// The config and data are checked up front, so that a test that cannot run fails
+    /// before sending anything.
+    pub fn new(client: Client, config: Config, data: Option<RequestData>) -> Result<Self> {
+        let headers = match &data {
+            Some(data) => data.merge_headers(&config.headers),
+            None => config.headers.clone(),
+        };
+        
+        let state = RunnerState {
             client,
             config,
             data,
+            headers,
+            attempted: AtomicUsize::new(0),
+            retries_used: AtomicUsize::new(0),
+            retry_budget_exhausted: AtomicBool::new(false),
+            completed: AtomicUsize::new(0),
+            failed: AtomicUsize::new(0),
+            abort_reason: Mutex::new(None),
+            connections: Mutex::new(HashSet::new()),
+            etag: OnceLock::new(),
+            files: OnceLock::new(),
+            body_file: OnceLock::new(),
+            exporter: Arc::new(NoopExporter),
+            observer: None,
+            stop: StopHandle::new(),
+            stream: None,
+            run_start: Mutex::new(Instant::now()),
+            run_id: Mutex::new(String::new()),
+        };
+        state.validate()?;
+        Ok(Self { state: Arc::new(state) })
+    }
+    
+    /// Set the exporter that receives each request result live
+    pub fn with_exporter(mut self, exporter: Arc<dyn MetricsExporter>) -> Self {
+        self.state_mut().exporter = exporter;
+        self
+    }
+    
+    /// Set a callback invoked with every request result
+    ///
+    /// The callback runs synchronously inside the request task, in completion order
+    /// (potentially from several tasks at once), so it should return quickly.
+    pub fn on_result(mut self, callback: ResultCallback) -> Self {
+        self.state_mut().observer = Some(CallbackExporter::new(callback));
+        self
+    }
+    
+    /// Use a shared stop handle, e.g. one triggered by Ctrl-C
+    pub fn with_stop(mut self, stop: StopHandle) -> Self {
+        self.state_mut().stop = stop;
+        self
+    }
+    
+    /// Handle to stop the running test gracefully
+    pub fn stop_handle(&self) -> StopHandle {
+        self.state.stop.clone()
+    }
+    
+    /// Create a client with the connection settings of a config: timeout, pool idle
+    /// timeout, cookie jar, proxy, redirects, HTTP version, `User-Agent` and SNI override
+    ///
+    /// The client asks for gzip and deflate compressed responses and decodes them.
+    pub fn create_client(config: &Config) -> Result<Client> {
+        debug!("Creating HTTP client with timeout: {}s", config.timeout);
+        let mut builder = Client::builder()
+            .timeout(Duration::from_secs(config.timeout))
+            .user_agent(config.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT))
+            .cookie_store(config.cookie_store)
+            .redirect(match (config.follow_redirects, config.max_redirects) {
+                (false, _) | (true, 0) => Policy::none(),
+                (true, max) => Policy::limited(max),
+            });
+        if let Some(idle_timeout) = config.idle_timeout {
+            debug!("Closing idle connections after {:?}", idle_timeout);
+            builder = builder.pool_idle_timeout(idle_timeout);
+        }
+        if let Some(proxy) = &config.proxy {
+            debug!("Sending requests through proxy {}", proxy);
+            let proxy = reqwest::Proxy::all(proxy)
+                .map_err(|source| Error::InvalidProxy { url: proxy.clone(), source })?;
+            builder = builder.proxy(proxy);
+        }
+        match config.http_version {
+            HttpVersion::Auto => {},
+            HttpVersion::Http1 => {
+                debug!("Only speaking HTTP/1.1");
+                builder = builder.http1_only();
+            },
+            HttpVersion::Http2PriorKnowledge => {
+                debug!("Speaking HTTP/2 with prior knowledge");
+                builder = builder.http2_prior_knowledge();
+            },
+        }
+        #[cfg(feature = "sni")]
+        if let Some(server_name) = &config.sni {
+            let target_host = reqwest::Url::parse(&config.url)
+                .ok()
+                .and_then(|url| url.host_str().map(|host| host.trim_matches(['[', ']']).to_string()))
+                .ok_or_else(|| Error::Other(format!("Invalid URL '{}'", config.url)))?;
+            
+            debug!("Presenting SNI {} for connections to {}", server_name, target_host);
+            builder = builder.dns_resolver(Arc::new(SniResolver {
+                server_name: server_name.clone(),
+                target_host,
+            }));
         }
+        
+        builder.build().map_err(Error::HttpClient)
     }
     
-    /// Create a new client with the specified timeout
-    pub fn create_client(timeout: u64) -> Result<Client> {
-        debug!("Creating HTTP client with timeout: {}s", timeout);
-        Client::builder()
-            .timeout(Duration::from_secs(timeout))
-            .build()
-            .map_err(Error::HttpClient)
+    /// Run the load test in a background task, streaming each request result as it completes
+    ///
+    /// The stream yields the results `run` aggregates (failed and cancelled requests included),
+    /// in completion order; it ends when the run is over and the task resolves to the aggregated
+    /// results. Up to `STREAM_CAPACITY` results are buffered: a consumer lagging further behind
+    /// holds up the collection of results, while dropping the stream lets the run go on.
+    pub fn run_streaming(mut self) -> (impl Stream<Item = RequestResult>, JoinHandle<Result<LoadTestResults>>) {
+        let (sender, mut receiver) = mpsc::channel(STREAM_CAPACITY);
+        self.state_mut().stream = Some(sender);
+        
+        // The sender is dropped with the runner, ending the stream
+        let task = tokio::spawn(async move { self.run().await });
+        
+        (stream::poll_fn(move |cx| receiver.poll_recv(cx)), task)
     }
     
     /// Run the load test
     #[instrument(skip_all, fields(
-        url = %self.config.url,
-        method = %self.config.method,
-        requests = self.config.request_count,
-        concurrency = self.config.concurrency
+        url = %self.state.config.url,
+        method = %self.state.config.method,
+        requests = self.state.config.request_count,
+        concurrency = self.state.config.concurrency
     ))]
     pub async fn run(&self) -> Result<LoadTestResults> {
-        info!("Starting load test: {} requests, {} concurrent", 
-              self.config.request_count, self.config.concurrency);
-              
-        let start = Instant::now();
+        self.state.run().await
+    }
+    
+    /// Probe the target until a request succeeds
+    ///
+    /// Requests are sent one at a time with exponential backoff between them (starting
+    /// at 100 ms, capped at 5 s). Probing stops at the first success, after
+    /// `request_count` attempts, or once `timeout` has elapsed.
+    pub async fn run_until_success(&self, timeout: Duration) -> Result<LoadTestResults> {
+        self.state.run_until_success(timeout).await
+    }
+    
+    /// Measure how long idle connections survive by pinging over them
+    ///
+    /// Each of the `concurrency` workers holds one connection (with a client of its own) and
+    /// sends a request over it every `ping_interval`, until `request_count` pings were sent,
+    /// the duration has elapsed or the run is stopped. A ping answered on a connection
+    /// other than the worker's previous one means the previous connection was dropped while
+    /// idle (see `KeepAliveStats`). The client's own idle timeout (`idle_timeout`, 90 s by
+    /// default) must exceed the interval, or the client closes the connections itself.
+    pub async fn run_keepalive(&self, ping_interval: Duration) -> Result<LoadTestResults> {
+        self.state.run_keepalive(ping_interval).await
+    }
+    
+    /// Concurrency used for a fixed-count run
+    ///
+    /// More concurrent slots than requests can never be filled, so the
+    /// concurrency is clamped to the request count (and to at least 1).
+    pub fn effective_concurrency(&self) -> usize {
+        self.state.effective_concurrency()
+    }
+    
+    /// Number of requests to send, limited by the variable sets with the `Once` strategy
+    pub fn request_count(&self) -> usize {
+        self.state.request_count()
+    }
+    
+    /// State of a runner that is not running, to configure it
+    fn state_mut(&mut self) -> &mut RunnerState {
+        Arc::get_mut(&mut self.state).expect("runner state is only shared while running")
+    }
+}
+
+impl RunnerState {
+    /// Reject targets, scenarios and bodies that cannot be sent
+    fn validate(&self) -> Result<()> {
+        // URL placeholders need a path variable
+        for target in std::iter::once(&self.config.url).chain(self.config.targets.iter().map(|target| &target.url)) {
+            self.apply_path_variables(target)?;
+        }
+        if !self.config.targets.is_empty() && self.config.targets.iter().all(|target| target.weight == 0) {
+            return Err(Error::Other("At least one target needs a positive weight".to_string()));
+        }
+        // The SNI resolver only connects the server name to the host of the URL
+        #[cfg(feature = "sni")]
+        if self.config.sni.is_some()
+            && (!self.config.targets.is_empty() || self.data.as_ref().is_some_and(|data| !data.scenario.is_empty())) {
+            return Err(Error::Other("An SNI override cannot be combined with targets or a scenario".to_string()));
+        }
+        if let Some(data) = self.data.as_ref().filter(|data| !data.scenario.is_empty()) {
+            if !self.config.targets.is_empty() {
+                return Err(Error::Other("A scenario cannot be combined with targets".to_string()));
+            }
+            for step in &data.scenario {
+                step.method(&self.config.method)?;
+                self.apply_path_variables(&step.url)?;
+            }
+        }
+        // Target bodies replace the data file body, so they have to be encodable as its body type
+        let body_type = self.data.as_ref().map(|data| data.body_type).unwrap_or_default();
+        if body_type != BodyType::Json {
+            if let Some(target) = self.config.targets.iter().find(|target| target.body.as_ref().is_some_and(|body| !body.is_object())) {
+                return Err(Error::Other(format!("The body of target {} must be an object of form fields", target.url)));
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Run the load test (see `Runner::run`)
+    async fn run(self: &Arc<Self>) -> Result<LoadTestResults> {
+        match self.config.duration {
+            Some(duration) => info!("Starting load test: {:?}, {} concurrent", duration, self.config.concurrency),
+            None => info!("Starting load test: {} requests, {} concurrent", 
+                          self.config.request_count, self.config.concurrency),
+        }
+        
+        // Prime the conditional-request flow with the current ETag
+        if self.config.etag_validation && self.etag.get().is_none() {
+            let etag = fetch_etag(&self.client, &self.config).await?;
+            let value = HeaderValue::from_str(&etag)
+                .map_err(|e| Error::Other(format!("Invalid ETag '{}': {}", etag, e)))?;
+            let _ = self.etag.set(value);
+        }
+        
+        // Read the files of a multipart body once, rather than for every request
+        if let Some(data) = self.data.as_ref().filter(|data| !data.files.is_empty()) {
+            if self.files.get().is_none() {
+                let mut files = Vec::with_capacity(data.files.len());
+                for (name, path) in &data.files {
+                    let contents = tokio::fs::read(path).await
+                        .map_err(|e| Error::Other(format!("Failed to read file '{}': {}", path.display(), e)))?;
+                    let file_name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+                    files.push((name.clone(), file_name, Bytes::from(contents)));
+                }
+                let _ = self.files.set(files);
+            }
+        }
         
-        // Create a stream of request indices
-        let indices: Vec<usize> = (0..self.config.request_count).collect();
+        // Read the body file once, rather than for every request
+        if let Some(data) = &self.data {
+            if let Some(path) = data.body_file.as_ref().filter(|_| self.body_file.get().is_none()) {
+                let _ = self.body_file.set(FileBody::read(path, data.body_type).await?);
+                if data.body.is_some() {
+                    warn!("Both a body and a body file are set, sending the body file {}", path.display());
+                }
+            }
+        }
+        
+        let request_count = self.request_count();
+        if request_count < self.config.request_count {
+            info!("Variable sets or CSV rows are exhausted after {} requests, stopping early", request_count);
+        }
+        
+        let start = Instant::now();
+        *self.run_start.lock().unwrap() = start;
+        *self.run_id.lock().unwrap() = self.config.run_id.clone().unwrap_or_else(generate_run_id);
+        let deadline = self.config.duration.map(|duration| start + duration);
+        self.attempted.store(0, Ordering::Relaxed);
+        self.retries_used.store(0, Ordering::Relaxed);
+        self.retry_budget_exhausted.store(false, Ordering::Relaxed);
+        self.completed.store(0, Ordering::Relaxed);
+        self.failed.store(0, Ordering::Relaxed);
+        self.abort_reason.lock().unwrap().take();
+        self.connections.lock().unwrap().clear();
         
-        // Convert the indices into a stream
-        let results = stream::iter(indices)
-            .map(|i| self.execute_request(i))
-            .buffer_unordered(self.config.concurrency)
-            .collect::<Vec<Result<RequestResult>>>()
-            .await;
+        let results = match &self.config.burst {
+            Some(profile) => self.run_bursts(profile, deadline).await,
+            None => {
+                let concurrency = self.effective_concurrency();
+                if concurrency != self.config.concurrency {
+                    info!("Concurrency {} exceeds the request count, clamping to {}",
+                          self.config.concurrency, concurrency);
+                }
+                
+                // During a ramp-up the permits start at 1 and are added over time (only in
+                // the first batch of a run split into several batches)
+                let ramp_up = self.config.ramp_up.filter(|_| concurrency > 1 && self.config.first_index == 0);
+                let initial = if ramp_up.is_some() { 1 } else { concurrency };
+                let permits = Arc::new(Semaphore::new(initial));
+                let slots = Arc::new(AtomicUsize::new(initial));
+                let ramp = ramp_up.map(|ramp_up| {
+                    info!("Ramping up to {} concurrent requests over {:?}", concurrency, ramp_up);
+                    tokio::spawn(ramp_concurrency(permits.clone(), slots.clone(), concurrency, ramp_up))
+                });
+                
+                let results = match self.config.dispatch {
+                    DispatchMode::Interleaved => {
+                        self.collect_results(self.dispatch(0..request_count, permits, slots, deadline)).await
+                    },
+                    DispatchMode::Partitioned => {
+                        let requests = self.dispatch_partitioned(request_count, concurrency, permits, deadline);
+                        self.collect_results(requests).await
+                    },
+                };
+                if let Some(ramp) = ramp {
+                    ramp.abort();
+                }
+                results
+            }
+        };
             
         let duration = start.elapsed();
+        self.exporter.flush();
         
         // Process results, filtering out errors
         let mut request_results = Vec::with_capacity(results.len());
         let mut errors = 0;
         
         for result in results {
-            match result {
-                Ok(result) => {
-                    if !result.success {
-                        errors += 1;
-                    }
-                    request_results.push(result);
-                },
-                Err(e) => {
-                    errors += 1;
-                    warn!("Error executing request: {}", e);
-                    request_results.push(RequestResult {
-                        status: None,
-                        response_time: 0,
-                        success: false,
-                        error: Some(e.to_string()),
-                        response_size: None,
-                    });
-                }
+            if result.is_failure() {
+                errors += 1;
             }
+            request_results.push(result);
         }
         
         info!("Load test completed: {} requests, {} errors, duration: {:.2}s",
-              self.config.request_count, errors, duration.as_secs_f64());
+              request_results.len(), errors, duration.as_secs_f64());
               
         // Create the load test results
-        Ok(LoadTestResults::new(request_results, duration))
+        let mut results = LoadTestResults::new(request_results, duration);
+        results.attempted_requests = self.attempted.load(Ordering::Relaxed);
+        // A timed run without a request limit was configured for whatever it could send
+        results.configured_requests = if self.config.duration.is_some() && self.config.request_count == usize::MAX {
+            results.attempted_requests
+        } else {
+            self.config.request_count
+        };
+        results.run_id = self.run_id.lock().unwrap().clone();
+        results.retry_budget_exhausted = self.retry_budget_exhausted.load(Ordering::Relaxed);
+        results.aborted = self.abort_reason.lock().unwrap().clone();
+        #[cfg(feature = "sni")]
+        {
+            results.sni = self.config.sni.clone();
+        }
+        results = results.with_percentile_method(self.config.percentiles);
+        if let Some(threshold) = self.config.apdex_threshold {
+            results = results.with_apdex(threshold);
+        }
+        if !self.config.latency_bands.is_empty() {
+            results = results.with_latency_bands(&self.config.latency_bands);
+        }
+        if let Some(thresholds) = self.config.thresholds {
+            results = results.with_thresholds(thresholds);
+        }
+        if let Some(etag) = self.etag.get() {
+            let etag = etag.to_str().unwrap_or_default();
+            results.conditional = Some(ConditionalStats::from_requests(etag, &results.requests));
+        }
+        
+        Ok(results)
     }
     
-    /// Execute a single request
-    #[instrument(skip_all, fields(index = index))]
-    async fn execute_request(&self, index: usize) -> Result<RequestResult> {
-        debug!("Executing request {}/{}", index + 1, self.config.request_count);
+    /// Probe the target until a request succeeds (see `Runner::run_until_success`)
+    async fn run_until_success(&self, timeout: Duration) -> Result<LoadTestResults> {
+        info!("Probing until success: up to {} attempts within {:?}", self.config.request_count, timeout);
         
         let start = Instant::now();
-        let mut builder = self.client
-            .request(self.config.method.clone(), &self.config.url)
-            .headers(self.config.headers.clone());
+        *self.run_start.lock().unwrap() = start;
+        *self.run_id.lock().unwrap() = self.config.run_id.clone().unwrap_or_else(generate_run_id);
+        let mut backoff = PROBE_INITIAL_BACKOFF;
+        let mut request_results = Vec::new();
+        self.attempted.store(0, Ordering::Relaxed);
         
-        // Add body if available and method is appropriate
-        if let Some(data) = &self.data {
-            if matches!(self.config.method, Method::POST | Method::PUT | Method::PATCH) {
-                if let Some(body) = &data.body {
-                    debug!("Adding JSON body to request");
-                    builder = builder.json(body);
+        for index in 0..self.config.request_count {
+            self.attempted.fetch_add(1, Ordering::Relaxed);
+            let result = self.execute_request(index).await?;
+            let success = result.success;
+            request_results.push(result);
+            
+            if success {
+                info!("Probe succeeded after {} attempts", index + 1);
+                break;
+            }
+            if index + 1 == self.config.request_count {
+                warn!("Probe gave up after {} attempts", index + 1);
+                break;
+            }
+            
+            let remaining = timeout.saturating_sub(start.elapsed());
+            if remaining.is_zero() {
+                warn!("Probe timed out after {} attempts", index + 1);
+                break;
+            }
+            
+            debug!("Probe attempt {} failed, retrying in {:?}", index + 1, backoff.min(remaining));
+            tokio::time::sleep(backoff.min(remaining)).await;
+            backoff = (backoff * 2).min(PROBE_MAX_BACKOFF);
+        }
+        
+        let mut results = LoadTestResults::new(request_results, start.elapsed())
+            .with_percentile_method(self.config.percentiles);
+        results.configured_requests = self.config.request_count;
+        results.attempted_requests = self.attempted.load(Ordering::Relaxed);
+        results.run_id = self.run_id.lock().unwrap().clone();
+        Ok(results)
+    }
+    
+    /// Ping over idle connections (see `Runner::run_keepalive`)
+    async fn run_keepalive(&self, ping_interval: Duration) -> Result<LoadTestResults> {
+        let workers = self.effective_concurrency();
+        info!("Pinging over {} connections every {:?}", workers, ping_interval);
+        if ping_interval >= self.config.idle_timeout.unwrap_or(Duration::from_secs(90)) {
+            warn!("The ping interval {:?} reaches the client's idle timeout, connections will be closed between pings", ping_interval);
+        }
+        
+        let url = self.apply_path_variables(&self.config.url)?;
+        let method = &self.config.method;
+        let mut headers = self.headers.clone();
+        if self.config.auth.is_some() {
+            headers.remove(AUTHORIZATION);
+        }
+        let clients = (0..workers)
+            .map(|_| Runner::create_client(&self.config))
+            .collect::<Result<Vec<_>>>()?;
+        
+        let request_count = self.request_count();
+        let start = Instant::now();
+        *self.run_start.lock().unwrap() = start;
+        *self.run_id.lock().unwrap() = self.config.run_id.clone().unwrap_or_else(generate_run_id);
+        let deadline = self.config.duration.map(|duration| start + duration);
+        self.attempted.store(0, Ordering::Relaxed);
+        self.completed.store(0, Ordering::Relaxed);
+        self.failed.store(0, Ordering::Relaxed);
+        self.abort_reason.lock().unwrap().take();
+        self.connections.lock().unwrap().clear();
+        
+        // Worker `w` sends the pings `w`, `w + workers`, `w + 2 * workers`, ...
+        let pings = clients.into_iter().enumerate().map(|(worker, client)| {
+            let (url, headers) = (&url, &headers);
+            async move {
+                let mut results = Vec::new();
+                let mut next = tokio::time::Instant::now();
+                for index in (worker..request_count).step_by(workers) {
+                    tokio::select! {
+                        _ = tokio::time::sleep_until(next) => {},
+                        _ = self.stop.stopped() => break,
+                    }
+                    if !self.dispatching(deadline) {
+                        break;
+                    }
+                    next += ping_interval;
+                    
+                    self.attempted.fetch_add(1, Ordering::Relaxed);
+                    let started_at = start.elapsed().as_millis();
+                    let mut builder = self.config.request(&client, method.clone(), url, headers.clone());
+                    if let Some(auth) = &self.config.auth {
+                        builder = auth.apply(builder);
+                    }
+                    let (mut result, _, _) = self.send_request(builder, method, false).await;
+                    result.index = Some(index);
+                    result.worker = Some(worker);
+                    result.started_at = Some(started_at);
+                    result.completed_at = Some(start.elapsed().as_millis());
+                    
+                    self.record_result(&result);
+                    results.push(result);
                 }
+                results
             }
+        });
+        let request_results: Vec<RequestResult> = future::join_all(pings).await.into_iter().flatten().collect();
+        self.exporter.flush();
+        
+        let mut results = LoadTestResults::new(request_results, start.elapsed())
+            .with_percentile_method(self.config.percentiles);
+        results.configured_requests = if self.config.duration.is_some() && self.config.request_count == usize::MAX {
+            self.attempted.load(Ordering::Relaxed)
+        } else {
+            self.config.request_count
+        };
+        results.attempted_requests = self.attempted.load(Ordering::Relaxed);
+        results.run_id = self.run_id.lock().unwrap().clone();
+        results.aborted = self.abort_reason.lock().unwrap().clone();
+        #[cfg(feature = "sni")]
+        {
+            results.sni = self.config.sni.clone();
         }
+        let keepalive = KeepAliveStats::from_requests(&results.requests);
+        info!("Keep-alive run completed: {} pings, {} connections dropped", keepalive.pings, keepalive.drops);
+        results.keepalive = Some(keepalive);
+        Ok(results)
+    }
+    
+    /// Whether a response status counts as a successful request
+    fn is_expected_status(&self, status: StatusCode) -> bool {
+        status.is_success()
+            || self.config.expected_statuses.contains(&status.as_u16())
+            || (self.config.etag_validation && status == StatusCode::NOT_MODIFIED)
+    }
+    
+    /// Concurrency used for a fixed-count run (see `Runner::effective_concurrency`)
+    fn effective_concurrency(&self) -> usize {
+        self.config.concurrency.min(self.request_count()).max(1)
+    }
+    
+    /// Number of requests to send (see `Runner::request_count`)
+    fn request_count(&self) -> usize {
+        let limit = self.data.as_ref()
+            .and_then(|data| data.request_limit())
+            .map(|limit| limit.saturating_sub(self.config.first_index));
         
-        // Execute the request
-        let result = match builder.send().await {
-            Ok(response) => {
-                let status = response.status();
-                let status_code = status.as_u16();
-                
-                // Read the response body
-                match response.text().await {
-                    Ok(body) => {
-                        let duration = start.elapsed();
+        match limit {
+            Some(limit) => self.config.request_count.min(limit),
+            None => self.config.request_count,
+        }
+    }
+    
+    /// Dispatch the requests in bursts, starting a new burst every `burst_interval`
+    ///
+    /// All requests of a burst are sent at once. If a burst takes longer than the
+    /// interval, the next one starts as soon as it has finished.
+    async fn run_bursts(self: &Arc<Self>, profile: &BurstProfile, deadline: Option<Instant>) -> Vec<RequestResult> {
+        let burst_size = profile.burst_size.max(1);
+        let total = self.request_count();
+        let start = tokio::time::Instant::now();
+        let run_start = *self.run_start.lock().unwrap();
+        let mut results = Vec::new();
+        // Accumulated burst by burst, so a huge interval ends the schedule instead of overflowing
+        let mut next_burst = Some(start);
+        
+        for (burst, first) in (0..total).step_by(burst_size).enumerate() {
+            let Some(scheduled) = next_burst else {
+                warn!("Burst {} is scheduled beyond the supported time range, no more bursts", burst + 1);
+                break;
+            };
+            next_burst = scheduled.checked_add(profile.burst_interval);
+            if deadline.is_some_and(|deadline| scheduled.into_std() >= deadline) {
+                info!("Test duration elapsed, no more bursts");
+                break;
+            }
+            tokio::select! {
+                _ = tokio::time::sleep_until(scheduled) => {},
+                _ = self.stop.stopped() => {},
+            }
+            if self.stop.is_stopped() {
+                info!("Stop requested, skipping the remaining bursts");
+                break;
+            }
+            
+            // A burst that starts after its scheduled time was queued behind the previous one
+            let lateness = tokio::time::Instant::now().saturating_duration_since(scheduled);
+            if lateness > profile.late_tolerance {
+                warn!("Burst {} started {} ms late (tolerance {} ms)",
+                      burst + 1, lateness.as_millis(), profile.late_tolerance.as_millis());
+            } else if !lateness.is_zero() {
+                debug!("Burst {} queued for {} ms", burst + 1, lateness.as_millis());
+            }
+            
+            let last = (first + burst_size).min(total);
+            info!("Dispatching burst {} ({} requests)", burst + 1, last - first);
+            
+            // Every request of a burst is scheduled at the start of the burst, and waits from
+            // then until it actually starts (e.g. behind a slow burst or an overloaded client)
+            let scheduled_at = scheduled.into_std().saturating_duration_since(run_start).as_millis();
+            let tolerance = profile.late_tolerance.as_millis();
+            
+            // Every request of a burst has a slot of its own, so none of them thinks
+            let permits = Arc::new(Semaphore::new(burst_size));
+            let slots = Arc::new(AtomicUsize::new(usize::MAX));
+            let burst_requests = self.dispatch(first..last, permits, slots, None)
+                .map(|result| result.map(|mut r| {
+                    let queue_wait = r.started_at.map_or(0, |started_at| started_at.saturating_sub(scheduled_at));
+                    r.burst = Some(burst);
+                    r.queue_wait = Some(queue_wait);
+                    r.late = queue_wait > tolerance;
+                    r
+                }));
+            let burst_results = self.collect_results(burst_requests).await;
+            
+            results.extend(burst_results);
+        }
+        
+        results
+    }
+    
+    /// Dispatch the requests with the given indices, each holding a permit while in flight
+    ///
+    /// At most as many requests as `permits` has permits are in flight at once, so adding
+    /// or forgetting permits changes the concurrency of a running test (`slots` counts the
+    /// permits added so far). No new requests are dispatched after a stop or past the
+    /// deadline (requests in flight still complete). Each request runs on a task of its own
+    /// (see `spawn_request`).
+    fn dispatch(
+        self: &Arc<Self>,
+        indices: std::ops::Range<usize>,
+        permits: Arc<Semaphore>,
+        slots: Arc<AtomicUsize>,
+        deadline: Option<Instant>,
+    ) -> impl Stream<Item = Result<RequestResult>> + '_ {
+        stream::iter(indices)
+            .then(move |i| {
+                let permits = permits.clone();
+                async move { (i, permits.acquire_owned().await) }
+            })
+            .take_while(move |_| future::ready(self.dispatching(deadline)))
+            .map(move |(i, permit)| {
+                // Requests after the first of every slot reuse a permit and think first
+                let thinks = i >= slots.load(Ordering::Relaxed);
+                let runner = self.clone();
+                spawn_request(async move {
+                    if thinks {
+                        runner.think(i, 0, deadline).await;
+                        if !runner.dispatching(deadline) {
+                            return None;
+                        }
+                    }
+                    runner.attempted.fetch_add(1, Ordering::Relaxed);
+                    let result = runner.execute_request(i).await;
+                    drop(permit);
+                    Some(result)
+                })
+            })
+            // The permits bound the requests in flight, not the buffer
+            .buffer_unordered(usize::MAX)
+            .filter_map(future::ready)
+    }
+    
+    /// Whether new requests are still dispatched: no stop was requested and the deadline
+    /// (if any) has not passed
+    fn dispatching(&self, deadline: Option<Instant>) -> bool {
+        !self.stop.is_stopped() && deadline.is_none_or(|deadline| Instant::now() < deadline)
+    }
+    
+    /// Dispatch the requests in contiguous slices, one slice per worker
+    ///
+    /// Worker `w` sends the requests `w * k..(w + 1) * k` one after another, where `k` is
+    /// the request count divided by the number of workers (rounded up). Each request still
+    /// holds a permit while in flight, so a ramp-up holds back whole workers, and runs on a
+    /// task of its own (see `spawn_request`).
+    fn dispatch_partitioned(
+        self: &Arc<Self>,
+        request_count: usize,
+        workers: usize,
+        permits: Arc<Semaphore>,
+        deadline: Option<Instant>,
+    ) -> impl Stream<Item = Result<RequestResult>> + '_ {
+        let slice = request_count.div_ceil(workers.max(1));
+        stream::select_all((0..workers).map(move |worker| {
+            let first = worker.saturating_mul(slice).min(request_count);
+            let last = first.saturating_add(slice).min(request_count);
+            let permits = permits.clone();
+            
+            let requests = stream::iter(first..last)
+                .then(move |i| {
+                    let permits = permits.clone();
+                    async move {
+                        let permit = permits.acquire_owned().await;
+                        // The worker thinks before each of its requests but the first
+                        if i > first {
+                            self.think(i, 0, deadline).await;
+                        }
+                        (i, permit)
+                    }
+                })
+                .take_while(move |_| future::ready(self.dispatching(deadline)))
+                .then(move |(i, permit)| {
+                    let runner = self.clone();
+                    spawn_request(async move {
+                        runner.attempted.fetch_add(1, Ordering::Relaxed);
+                        let result = runner.execute_request(i).await;
+                        drop(permit);
+                        Some(result.map(|mut result| {
+                            result.worker = Some(worker);
+                            result
+                        }))
+                    })
+                })
+                .filter_map(future::ready);
+            Box::pin(requests)
+        }))
+    }
+    
+    /// Collect the results of a stream of requests, passing each on to `run_streaming`
+    ///
+    /// Requests that could not be executed are recorded as failed. Once a stop is requested,
+    /// requests in flight get `shutdown_grace` to complete; those still running afterwards are
+    /// dropped and recorded as cancelled.
+    async fn collect_results<S>(&self, requests: S) -> Vec<RequestResult>
+    where
+        S: Stream<Item = Result<RequestResult>>,
+    {
+        let started_before = self.attempted.load(Ordering::Relaxed);
+        let mut requests = std::pin::pin!(requests);
+        let mut results = Vec::new();
+        
+        let stopped = self.stop.stopped();
+        tokio::pin!(stopped);
+        loop {
+            tokio::select! {
+                result = requests.next() => match result {
+                    Some(result) => self.collect(&mut results, result).await,
+                    None => return results,
+                },
+                _ = &mut stopped => break,
+            }
+        }
+        
+        info!("Stop requested, waiting up to {:?} for requests in flight", self.config.shutdown_grace);
+        let grace = tokio::time::sleep(self.config.shutdown_grace);
+        tokio::pin!(grace);
+        loop {
+            tokio::select! {
+                result = requests.next() => match result {
+                    Some(result) => self.collect(&mut results, result).await,
+                    None => return results,
+                },
+                _ = &mut grace => break,
+            }
+        }
+        
+        let cancelled = self.attempted.load(Ordering::Relaxed)
+            .saturating_sub(started_before)
+            .saturating_sub(results.len());
+        warn!("Cancelled {} requests still in flight after the grace period", cancelled);
+        for _ in 0..cancelled {
+            let result = RequestResult {
+                status: None,
+                response_time: 0,
+                success: false,
+                error: Some(CANCELLED_ERROR.to_string()),
+                response_size: None,
+                cancelled: true,
+                ..Default::default()
+            };
+            self.collect(&mut results, Ok(result)).await;
+        }
+        
+        results
+    }
+    
+    /// Add a result to the collected results and to the `run_streaming` stream
+    async fn collect(&self, results: &mut Vec<RequestResult>, result: Result<RequestResult>) {
+        let result = result.unwrap_or_else(|e| {
+            warn!("Error executing request: {}", e);
+            RequestResult {
+                status: None,
+                response_time: 0,
+                success: false,
+                error: Some(e.to_string()),
+                response_size: None,
+                ..Default::default()
+            }
+        });
+        if let Some(stream) = &self.stream {
+            // A dropped stream just stops receiving results
+            let _ = stream.send(result.clone()).await;
+        }
+        results.push(result);
+    }
+    
+    /// Replace `{name}` placeholders in a URL with the path variables of the request data
+    fn apply_path_variables(&self, url: &str) -> Result<String> {
+        match &self.data {
+            Some(data) => data.apply_path_variables(url),
+            None => substitute_path_variables(url, &HashMap::new()),
+        }
+    }
+    
+    /// Target of the request with the given index (None to send it to `url`)
+    ///
+    /// Each cycle of the summed weights sends `weight` consecutive requests to every
+    /// target in turn, so equal weights spread the requests round-robin.
+    fn target(&self, index: usize) -> Option<&Target> {
+        let total: u64 = self.config.targets.iter().map(|target| u64::from(target.weight)).sum();
+        if total == 0 {
+            return None;
+        }
+        
+        let mut position = (self.config.first_index + index) as u64 % total;
+        self.config.targets.iter().find(|target| {
+            let within = position < u64::from(target.weight);
+            position = position.saturating_sub(u64::from(target.weight));
+            within
+        })
+    }
+    
+    /// Execute a single request
+    #[instrument(skip_all, fields(index = index))]
+    async fn execute_request(&self, index: usize) -> Result<RequestResult> {
+        if let Some(data) = self.data.as_ref().filter(|data| !data.scenario.is_empty()) {
+            return self.execute_scenario(index, data).await;
+        }
+        debug!("Executing request {}/{}", index + 1, self.request_count());
+        let started_at = self.run_start.lock().unwrap().elapsed().as_millis();
+        
+        let target = self.target(index);
+        let target_url = target.map_or(self.config.url.as_str(), |target| target.url.as_str());
+        let method = target.and_then(|target| target.method.as_ref()).unwrap_or(&self.config.method);
+        let target_body = target.and_then(|target| target.body.as_ref());
+        
+        // Templates are only resolved when the URL, the target body or the data needs them
+        let needs_template = target_url.contains("{{") || target_body.is_some()
+            || self.headers.values().any(|value| value.to_str().is_ok_and(|value| value.contains("{{")))
+            || self.data.as_ref().is_some_and(|data| !data.transforms.is_empty() || !data.rows.is_empty()
+                || !data.body_pool.is_empty() || !data.variables.is_empty());
+        let context = needs_template.then(|| {
+            TemplateContext::new(self.config.first_index + index, self.data.as_ref(), self.config.seed)
+                .with_run_id(self.run_id.lock().unwrap().clone())
+        });
+        
+        let url = match &context {
+            Some(context) => context.render_url(target_url),
+            None => target_url.to_string(),
+        };
+        let url = self.apply_path_variables(&url)?;
+        
+        // Requests are only tagged with their host when spread across several targets
+        let host = if self.config.targets.is_empty() {
+            None
+        } else {
+            Some(host_key(&url))
+        };
+        
+        let (mut headers, rendered_headers) = match &context {
+            Some(context) => render_headers(&self.headers, context),
+            None => (self.headers.clone(), Vec::new()),
+        };
+        if self.config.auth.is_some() {
+            headers.remove(AUTHORIZATION);
+        }
+        let has_content_type = headers.contains_key(CONTENT_TYPE);
+        // With an SNI override the request goes to the server name, keeping the target's Host
+        let mut builder = self.config.request(&self.client, method.clone(), &url, headers);
+        if let Some(auth) = &self.config.auth {
+            builder = auth.apply(builder);
+        }
+        
+        // Query parameters from the data file are appended (URL-encoded) to any query
+        // string already in the URL, in a stable order
+        if let Some(data) = self.data.as_ref().filter(|data| !data.params.is_empty()) {
+            let mut params: Vec<(&str, String)> = data.params.iter()
+                .map(|(key, value)| (key.as_str(), match &context {
+                    Some(context) => context.render(value),
+                    None => value.clone(),
+                }))
+                .collect();
+            params.sort();
+            builder = builder.query(&params);
+        }
+        
+        if let Some(etag) = self.etag.get() {
+            builder = builder.header(IF_NONE_MATCH, etag.clone());
+        }
+        
+        // Add body if available and method is appropriate
+        let mut body_label = None;
+        if matches!(*method, Method::POST | Method::PUT | Method::PATCH) {
+            // The target's own body takes precedence over a body from the weighted pool,
+            // which takes precedence over the fixed body
+            let pooled = context.as_ref()
+                .filter(|_| target_body.is_none())
+                .and_then(|context| context.choose_body());
+            body_label = pooled.map(|(position, pooled)| pooled.label(position));
+            // A body file replaces the fixed body
+            let (file_json, file_raw) = match self.body_file.get() {
+                Some(FileBody::Json(body)) => (Some(body), None),
+                Some(FileBody::Raw(bytes)) => (None, Some(bytes)),
+                None => (None, None),
+            };
+            let body = target_body
+                .or(pooled.map(|(_, pooled)| &pooled.body))
+                .or(file_json)
+                .or(self.data.as_ref().and_then(|data| data.body.as_ref()).filter(|_| file_raw.is_none()));
+            let body = match (body, &context) {
+                (Some(body), Some(context)) => {
+                    let mut body = body.clone();
+                    context.render_value(&mut body);
+                    if let Some(data) = &self.data {
+                        context.apply_transforms(&mut body, &data.transforms);
+                    }
+                    Some(Cow::Owned(body))
+                },
+                (body, _) => body.map(Cow::Borrowed),
+            };
+            
+            let body_type = self.data.as_ref().map(|data| data.body_type).unwrap_or_default();
+            builder = self.add_body(builder, body_type, body, file_raw, has_content_type)?;
+        }
+        
+        let (mut result, retries, _) = self.send_with_retries(builder, index, method, false).await;
+        
+        result.retries = retries;
+        if self.config.record_headers {
+            result.rendered_headers = rendered_headers.into_iter()
+                .map(|(name, value)| if is_sensitive_header(&name) { (name, REDACTED.to_string()) } else { (name, value) })
+                .collect();
+        }
+        result.host = host;
+        result.target = target.map(|target| target.label(&self.config.method));
+        if target.is_some() {
+            result.method = Some(method.to_string());
+            result.url = Some(url);
+        }
+        result.body = body_label;
+        result.index = Some(index);
+        result.started_at = Some(started_at);
+        result.completed_at = Some(self.run_start.lock().unwrap().elapsed().as_millis());
+        
+        self.record_result(&result);
+        Ok(result)
+    }
+    
+    /// Run the scenario steps in order as the iteration with the given index
+    ///
+    /// Values extracted from the JSON response of a step resolve `{{name}}` tokens of the
+    /// later steps. A step that fails, or whose values cannot be extracted, ends the
+    /// iteration unless it continues on failure. The iteration is recorded as a single
+    /// result holding the results of its steps.
+    async fn execute_scenario(&self, index: usize, data: &RequestData) -> Result<RequestResult> {
+        debug!("Executing scenario iteration {}/{}", index + 1, self.request_count());
+        let started_at = self.run_start.lock().unwrap().elapsed().as_millis();
+        let start = Instant::now();
+        // Time spent thinking between the steps, which is not part of the iteration's response time
+        let mut think_time = Duration::ZERO;
+        
+        let context = TemplateContext::new(self.config.first_index + index, Some(data), self.config.seed)
+            .with_run_id(self.run_id.lock().unwrap().clone());
+        let mut steps = Vec::with_capacity(data.scenario.len());
+        for (position, step) in data.scenario.iter().enumerate() {
+            let method = step.method(&self.config.method)?;
+            let url = self.apply_path_variables(&context.render_url(&step.url))?;
+            let step_started_at = self.run_start.lock().unwrap().elapsed().as_millis();
+            
+            let (mut headers, _) = render_headers(&self.headers, &context);
+            for (name, value) in &step.headers {
+                let value = context.render(value);
+                match (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(&value)) {
+                    (Ok(name), Ok(value)) => {
+                        headers.insert(name, value);
+                    },
+                    _ => warn!("Invalid header '{}: {}' in scenario step", name, value),
+                }
+            }
+            if self.config.auth.is_some() {
+                headers.remove(AUTHORIZATION);
+            }
+            let has_content_type = headers.contains_key(CONTENT_TYPE);
+            let mut builder = self.client
+                .request(method.clone(), &url)
+                .headers(headers);
+            if let Some(auth) = &self.config.auth {
+                builder = auth.apply(builder);
+            }
+            
+            if let Some(body) = &step.body {
+                let mut body = body.clone();
+                context.render_value(&mut body);
+                builder = self.add_body(builder, step.body_type.unwrap_or(data.body_type), Some(Cow::Owned(body)), None, has_content_type)?;
+            }
+            
+            let keep_body = !step.extract.is_empty();
+            let (mut result, retries, body) = self.send_with_retries(builder, index, &method, keep_body).await;
+            if keep_body && !result.is_failure() {
+                match extract_values(body.as_deref(), &step.extract) {
+                    Ok(values) => values.into_iter().for_each(|(name, value)| context.set_value(&name, value)),
+                    Err(e) => {
+                        debug!("Scenario step {} of iteration {}: {}", position + 1, index + 1, e);
+                        result.success = false;
+                        result.expected_failure = false;
+                        result.error = Some(format!("{}: {}", EXTRACT_ERROR, e));
+                    },
+                }
+            }
+            
+            result.retries = retries;
+            result.step = Some(step.label(position));
+            result.method = Some(method.to_string());
+            result.url = Some(url);
+            result.started_at = Some(step_started_at);
+            result.completed_at = Some(self.run_start.lock().unwrap().elapsed().as_millis());
+            
+            let abort = result.is_failure() && step.on_failure == StepFailure::Abort;
+            steps.push(result);
+            if abort || self.stop.is_stopped() {
+                break;
+            }
+            if position + 1 < data.scenario.len() {
+                let thinking = Instant::now();
+                self.think(index, position + 1, None).await;
+                think_time += thinking.elapsed();
+            }
+        }
+        
+        // The iteration fails with the error of its first failed step
+        let failed = steps.iter().find(|step| step.is_failure());
+        let mut result = RequestResult {
+            status: steps.last().and_then(|step| step.status),
+            response_time: start.elapsed().saturating_sub(think_time).as_millis(),
+            success: failed.is_none(),
+            error: failed.and_then(|step| step.error.clone()),
+            response_size: steps.iter().filter_map(|step| step.response_size).reduce(|a, b| a + b),
+            request_size: steps.iter().filter_map(|step| step.request_size).reduce(|a, b| a + b),
+            retries: steps.iter().map(|step| step.retries).sum(),
+            index: Some(index),
+            started_at: Some(started_at),
+            completed_at: Some(self.run_start.lock().unwrap().elapsed().as_millis()),
+            ..Default::default()
+        };
+        // A transport failure keeps the iteration in the transport tier
+        if failed.is_some_and(|step| step.status.is_none()) {
+            result.status = None;
+        }
+        result.steps = steps;
+        
+        self.record_result(&result);
+        Ok(result)
+    }
+    
+    /// Pass a completed request to the exporter and the observer, and stop the run once the
+    /// error rate exceeds `abort_on_error_rate`
+    fn record_result(&self, result: &RequestResult) {
+        self.exporter.record(result);
+        if let Some(observer) = &self.observer {
+            observer.record(result);
+        }
+        
+        let Some(limit) = self.config.abort_on_error_rate else {
+            return;
+        };
+        let failure = usize::from(result.is_failure());
+        let completed = self.completed.fetch_add(1, Ordering::Relaxed) + 1;
+        let failed = self.failed.fetch_add(failure, Ordering::Relaxed) + failure;
+        let error_rate = failed as f64 / completed as f64 * 100.0;
+        if completed >= self.config.abort_min_requests.max(1) && error_rate > limit {
+            let mut reason = self.abort_reason.lock().unwrap();
+            if reason.is_none() {
+                let message = format!("error rate {:.1}% exceeded {:.1}% after {} requests", error_rate, limit, completed);
+                warn!("Aborting the run: {}", message);
+                *reason = Some(message);
+                self.stop.stop();
+            }
+        }
+    }
+    
+    /// Add a body encoded as the body type to a request, or the raw body (if any) when
+    /// there is no body to encode
+    ///
+    /// Bodies are encoded here rather than with `RequestBuilder::json` or `form` to control
+    /// the content type: a configured `Content-Type` header is kept.
+    fn add_body(&self, mut builder: RequestBuilder, body_type: BodyType, body: Option<Cow<'_, Value>>,
+                raw: Option<&Bytes>, has_content_type: bool) -> Result<RequestBuilder> {
+        match (body_type, body) {
+            (BodyType::Json, Some(body)) => {
+                debug!("Adding JSON body to request");
+                let bytes = serde_json::to_vec(&body).map_err(Error::Serialization)?;
+                if !has_content_type {
+                    builder = builder.header(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+                }
+                builder = builder.body(bytes);
+            },
+            (BodyType::Form, Some(body)) => {
+                debug!("Adding form body to request");
+                let encoded = form_urlencoded::Serializer::new(String::new())
+                    .extend_pairs(form_fields(&body)?)
+                    .finish();
+                if !has_content_type {
+                    builder = builder.header(CONTENT_TYPE, HeaderValue::from_static("application/x-www-form-urlencoded"));
+                }
+                builder = builder.body(encoded);
+            },
+            // Multipart bodies are streamed, so their requests are not retried
+            (BodyType::Multipart, body) if body.is_some() || self.files.get().is_some() => {
+                debug!("Adding multipart body to request");
+                let mut form = Form::new();
+                if let Some(body) = body {
+                    for (name, value) in form_fields(&body)? {
+                        form = form.text(name, value);
+                    }
+                }
+                // Parts share the file contents, and their known lengths give the form a Content-Length
+                for (name, file_name, contents) in self.files.get().into_iter().flatten() {
+                    let part = Part::stream_with_length(contents.clone(), contents.len() as u64);
+                    form = form.part(name.clone(), part.file_name(file_name.clone()));
+                }
+                builder = builder.multipart(form);
+            },
+            _ => if let Some(bytes) = raw {
+                debug!("Adding raw body to request");
+                if !has_content_type {
+                    builder = builder.header(CONTENT_TYPE, HeaderValue::from_static("application/octet-stream"));
+                }
+                builder = builder.body(bytes.clone());
+            },
+        }
+        
+        Ok(builder)
+    }
+    
+    /// Send a request, retrying transport errors and 5xx responses while retries remain
+    ///
+    /// Returns the result of the final attempt, the number of retries and, with `keep_body`,
+    /// the response body of the final attempt.
+    async fn send_with_retries(&self, mut builder: RequestBuilder, index: usize, method: &Method, keep_body: bool)
+        -> (RequestResult, u32, Option<String>)
+    {
+        let mut retries = 0;
+        loop {
+            // Keep a copy of the request in case it has to be retried
+            let next = if retries < self.config.retries { builder.try_clone() } else { None };
+            let (result, body_matched, body) = self.send_request(builder, method, keep_body).await;
+            let retryable = self.is_retryable(&result) || body_matched
+                || (!result.success && result.error.as_deref().is_some_and(|error| self.matches_retry_pattern(error)));
+            
+            match next {
+                Some(next) if retryable && self.acquire_retry() => {
+                    let backoff = self.retry_backoff(index, retries);
+                    retries += 1;
+                    debug!("Retrying request {} ({}/{}) in {:?} after: {}", index + 1, retries, self.config.retries,
+                           backoff, result.error.as_deref().unwrap_or("unknown error"));
+                    
+                    if !backoff.is_zero() {
+                        tokio::select! {
+                            _ = tokio::time::sleep(backoff) => {},
+                            _ = self.stop.stopped() => {},
+                        }
+                    }
+                    if self.stop.is_stopped() {
+                        debug!("Stop requested, not retrying request {}", index + 1);
+                        return (result, retries, body);
+                    }
+                    builder = next;
+                },
+                _ => return (result, retries, body),
+            }
+        }
+    }
+    
+    /// Whether a failed request is retried: transport errors and retryable statuses
+    fn is_retryable(&self, result: &RequestResult) -> bool {
+        match (self.config.retry_statuses.is_empty(), result.status) {
+            (false, Some(status)) if result.error_tier() == Some(ErrorTier::Application) => {
+                self.config.retry_statuses.contains(&status)
+            },
+            _ => result.is_retryable(),
+        }
+    }
+    
+    /// Random number generator for a random choice of the runner about a request
+    ///
+    /// With a run seed, it is derived from the seed, the request index and `stream`, so
+    /// jitter is as reproducible as the template values of the request.
+    fn request_rng(&self, index: usize, stream: u64) -> StdRng {
+        match self.config.seed {
+            Some(seed) => StdRng::seed_from_u64(request_seed(seed ^ stream, self.config.first_index + index)),
+            None => StdRng::from_entropy(),
+        }
+    }
+    
+    /// Delay before the retry of request `index` following `attempt` earlier retries:
+    /// `retry_backoff * 2^attempt`
+    fn retry_backoff(&self, index: usize, attempt: u32) -> Duration {
+        let backoff = self.config.retry_backoff.saturating_mul(1 << attempt.min(16));
+        if self.config.retry_jitter {
+            let mut rng = self.request_rng(index, RETRY_JITTER_STREAM.wrapping_add(attempt as u64));
+            backoff.mul_f64(rng.gen_range(0.5..=1.0))
+        } else {
+            backoff
+        }
+    }
+    
+    /// Pause for the think time between two requests of a concurrency slot, ending early
+    /// when the run is stopped
+    ///
+    /// `index` is the request (or scenario iteration) and `step` its scenario step the
+    /// jitter is drawn for. The pause also ends at the deadline, if any.
+    async fn think(&self, index: usize, step: usize, deadline: Option<Instant>) {
+        let Some(think_time) = self.config.think_time else {
+            return;
+        };
+        let jitter = self.config.think_time_jitter;
+        let pause = if jitter.is_zero() {
+            think_time
+        } else {
+            let mut rng = self.request_rng(index, THINK_JITTER_STREAM.wrapping_add(step as u64));
+            rng.gen_range(think_time.saturating_sub(jitter)..=think_time + jitter)
+        };
+        let pause = match deadline {
+            Some(deadline) => pause.min(deadline.saturating_duration_since(Instant::now())),
+            None => pause,
+        };
+        
+        tokio::select! {
+            _ = tokio::time::sleep(pause) => {},
+            _ = self.stop.stopped() => {},
+        }
+    }
+    
+    /// Take one retry from the retry budget, returning false once it is used up
+    fn acquire_retry(&self) -> bool {
+        let cap = match self.config.retry_budget {
+            Some(percent) => ((self.request_count() as f64 * percent / 100.0).floor().max(0.0)) as usize,
+            None => usize::MAX,
+        };
+        
+        let acquired = self.retries_used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| (used < cap).then_some(used + 1))
+            .is_ok();
+        if !acquired && !self.retry_budget_exhausted.swap(true, Ordering::Relaxed) {
+            warn!("Retry budget of {} retries exhausted, further failures are not retried", cap);
+        }
+        
+        acquired
+    }
+    
+    /// Whether a local address belongs to a connection not used by an earlier request
+    fn is_new_connection(&self, local_addr: SocketAddr) -> bool {
+        self.connections.lock().unwrap().insert(local_addr)
+    }
+    
+    /// Whether any of the retry patterns matches the text
+    fn matches_retry_pattern(&self, text: &str) -> bool {
+        self.config.retry_on.iter().any(|matcher| matcher.is_match(text))
+    }
+    
+    /// Send a single attempt of a request and measure its result
+    ///
+    /// Also returns whether the request failed with a response body matching a retry
+    /// pattern. Bodies are only matched when read whole (without a body read limit).
+    async fn send_request(&self, builder: RequestBuilder, method: &Method, keep_body: bool) -> (RequestResult, bool, Option<String>) {
+        let start = Instant::now();
+        let (client, request) = builder.build_split();
+        let request_size = request.as_ref().ok().and_then(request_body_size);
+        let response = match request {
+            Ok(request) => client.execute(request).await,
+            Err(e) => Err(e),
+        };
+        
+        let (mut result, body_matched, body) = match response {
+            Ok(response) => {
+                // `send` resolves once the response headers have arrived
+                let ttfb = start.elapsed().as_millis();
+                let status = response.status();
+                let status_code = status.as_u16();
+                // Read from the header: the body of a HEAD response is empty whatever it announces
+                let content_length = response.headers().get(CONTENT_LENGTH)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.parse::<u64>().ok());
+                let server_timing = parse_server_timing(response.headers());
+                // Connections are told apart by their local address (when the connection info is available)
+                let local_addr = response.extensions().get::<HttpInfo>().map(|info| info.local_addr());
+                let new_connection = local_addr.map(|local_addr| self.is_new_connection(local_addr));
+                let local_port = local_addr.map(|local_addr| local_addr.port());
+                let http_version = format!("{:?}", response.version());
+                
+                // Read the response body (or drop it unread when body reading is disabled;
+                // HEAD responses have no body)
+                let head = *method == Method::HEAD;
+                // Whole bodies of unexpected responses are kept to match against the retry patterns
+                let match_body = !self.config.stream_body
+                    && self.config.max_body_read.is_none()
+                    && !self.config.retry_on.is_empty()
+                    && !self.is_expected_status(status)
+                    && !self.config.expected_failures.contains(&status_code);
+                let body = if (self.config.read_body || keep_body) && !head {
+                    read_body(response, self.config.max_body_read, match_body || keep_body).await
+                } else {
+                    drop(response);
+                    Ok(BodyRead::default())
+                };
+                
+                match body {
+                    Ok(body) => {
+                        let body_matched = match_body && body.text.as_deref().is_some_and(|text| self.matches_retry_pattern(text));
+                        let response_size = if head {
+                            content_length.and_then(|length| usize::try_from(length).ok())
+                        } else {
+                            self.config.read_body.then_some(body.size)
+                        };
+                        let first_chunk = body.first_chunk.map(|at| at.duration_since(start).as_millis());
+                        let stream_duration = body.first_chunk.zip(body.last_chunk)
+                            .map(|(first, last)| last.duration_since(first).as_millis());
+                        let duration = start.elapsed();
                         let response_time = duration.as_millis();
                         
                         debug!("Request completed with status {} in {} ms",
                                status, response_time);
                         
-                        let success = status.is_success();
-                        let error = if !success {
-                            Some(format!("HTTP Error: {} {}", status_code, status.canonical_reason().unwrap_or("Unknown")))
+                        let success = self.is_expected_status(status);
+                        let expected_failure = !success && self.config.expected_failures.contains(&status_code);
+                        let error = if !success && !expected_failure {
+                            Some(format!("{}: {} {}", HTTP_ERROR, status_code, status.canonical_reason().unwrap_or("Unknown")))
                         } else {
                             None
                         };
                         
-                        RequestResult {
+                        let result = RequestResult {
                             status: Some(status_code),
                             response_time,
                             success,
+                            expected_failure,
                             error,
-                            response_size: Some(body.len()),
-                        }
+                            response_size,
+                            body_truncated: body.truncated,
+                            content_length,
+                            ttfb: Some(ttfb),
+                            first_chunk,
+                            stream_duration,
+                            server_timing,
+                            new_connection,
+                            local_port,
+                            http_version: Some(http_version),
+                            ..Default::default()
+                        };
+                        (result, body_matched, body.text.filter(|_| keep_body))
                     },
                     Err(e) => {
                         let duration = start.elapsed();
@@ -175,13 +1879,18 @@ impl Runner {
                         
                         warn!("Error reading response body: {}", e);
                         
-                        RequestResult {
+                        let result = RequestResult {
                             status: Some(status_code),
                             response_time,
                             success: false,
-                            error: Some(format!("Error reading response body: {}", e)),
+                            error: Some(format!("{}: {}", BODY_READ_ERROR, e)),
                             response_size: None,
-                        }
+                            new_connection,
+                            local_port,
+                            http_version: Some(http_version),
+                            ..Default::default()
+                        };
+                        (result, false, None)
                     }
                 }
             },
@@ -191,16 +1900,1713 @@ impl Runner {
                 
                 warn!("Request failed: {}", e);
                 
-                RequestResult {
+                let result = RequestResult {
                     status: None,
                     response_time,
                     success: false,
                     error: Some(e.to_string()),
                     response_size: None,
-                }
+                    ..Default::default()
+                };
+                (result, false, None)
             }
         };
         
-        Ok(result)
+        result.request_size = request_size;
+        (result, body_matched, body)
+    }
+}
+
+/// Add permits one at a time so that `concurrency` is reached after `ramp_up`, counting
+/// the opened concurrency slots in `slots`
+async fn ramp_concurrency(permits: Arc<Semaphore>, slots: Arc<AtomicUsize>, concurrency: usize, ramp_up: Duration) {
+    let start = tokio::time::Instant::now();
+    let steps = concurrency - 1;
+    
+    for step in 1..=steps {
+        tokio::time::sleep_until(start + ramp_up.mul_f64(step as f64 / steps as f64)).await;
+        slots.fetch_add(1, Ordering::Relaxed);
+        permits.add_permits(1);
+    }
+    debug!("Ramp-up complete at {} concurrent requests", concurrency);
+}
+
+/// Handle of a request task that aborts the task when dropped
+struct AbortOnDrop(JoinHandle<Option<Result<RequestResult>>>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Run a request on a task of its own, so the requests in flight are spread over the
+/// runtime's worker threads rather than all polled by the task collecting their results
+///
+/// Dropping the returned future aborts the request, like dropping the request itself would
+/// (e.g. for requests still in flight after the shutdown grace period).
+fn spawn_request(
+    request: impl Future<Output = Option<Result<RequestResult>>> + Send + 'static,
+) -> impl Future<Output = Option<Result<RequestResult>>> {
+    let mut task = AbortOnDrop(tokio::spawn(request.in_current_span()));
+    async move {
+        (&mut task.0).await
+            .unwrap_or_else(|e| Some(Err(Error::Other(format!("Request task failed: {}", e)))))
+    }
+}
+
+/// A response body read chunk by chunk
+#[derive(Debug, Default)]
+struct BodyRead {
+    /// Number of bytes read
+    size: usize,
+    
+    /// Whether reading stopped at the body read limit
+    truncated: bool,
+    
+    /// The body (lossily decoded as UTF-8), if it was kept
+    text: Option<String>,
+    
+    /// Arrival of the first body chunk (None for empty bodies)
+    first_chunk: Option<Instant>,
+    
+    /// Arrival of the last body chunk read (None for empty bodies)
+    last_chunk: Option<Instant>,
+}
+
+/// Read a response body chunk by chunk, stopping after `limit` bytes (if any)
+///
+/// Chunks are already decoded, so the limit also bounds decompressed bodies. The
+/// arrival of the first and last chunks is recorded to tell the time to the first
+/// chunk apart from the time spent streaming (e.g. chunked transfer encoding).
+async fn read_body(mut response: Response, limit: Option<usize>, keep: bool) -> reqwest::Result<BodyRead> {
+    let mut body = BodyRead::default();
+    let mut bytes = Vec::new();
+    
+    while let Some(chunk) = response.chunk().await? {
+        let now = Instant::now();
+        body.first_chunk.get_or_insert(now);
+        body.last_chunk = Some(now);
+        
+        body.size += chunk.len();
+        if let Some(limit) = limit.filter(|limit| body.size > *limit) {
+            debug!("Response body exceeds {} bytes, truncating", limit);
+            body.size = limit;
+            body.truncated = true;
+            return Ok(body);
+        }
+        if keep {
+            bytes.extend_from_slice(&chunk);
+        }
+    }
+    
+    if keep {
+        body.text = Some(String::from_utf8_lossy(&bytes).into_owned());
+    }
+    Ok(body)
+}
+
+/// Durations of the named metrics in `Server-Timing` headers (e.g. `db;dur=53, app;dur=47.2`)
+///
+/// Metrics without a valid `dur` parameter are skipped, so malformed headers yield
+/// fewer (or no) metrics rather than an error.
+fn parse_server_timing(headers: &HeaderMap) -> HashMap<String, f64> {
+    let mut metrics = HashMap::new();
+    for value in headers.get_all(SERVER_TIMING).iter().filter_map(|value| value.to_str().ok()) {
+        for metric in value.split(',') {
+            let mut parts = metric.split(';').map(str::trim);
+            let Some(name) = parts.next().filter(|name| !name.is_empty()) else {
+                continue;
+            };
+            let duration = parts
+                .filter_map(|param| param.split_once('='))
+                .find(|(key, _)| key.trim().eq_ignore_ascii_case("dur"))
+                .and_then(|(_, value)| value.trim().trim_matches('"').parse::<f64>().ok())
+                .filter(|duration| duration.is_finite() && *duration >= 0.0);
+            match duration {
+                Some(duration) => {
+                    metrics.insert(name.to_string(), duration);
+                },
+                None => debug!("Ignoring Server-Timing metric without a duration: {}", metric.trim()),
+            }
+        }
+    }
+    
+    metrics
+}
+
+/// Host (and port, if not the default) of a URL, used to group per-host statistics
+fn host_key(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) => match (parsed.host_str(), parsed.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => url.to_string(),
+        },
+        Err(_) => url.to_string(),
+    }
+}
+
+/// Render the `{{token}}` templates in header values
+///
+/// Also returns the names and rendered values of the templated headers. Headers whose
+/// rendered value is not a valid header value are sent unrendered.
+fn render_headers(headers: &HeaderMap, context: &TemplateContext) -> (HeaderMap, Vec<(String, String)>) {
+    let mut rendered = headers.clone();
+    let mut values = Vec::new();
+    for (name, value) in rendered.iter_mut() {
+        let Some(template) = value.to_str().ok().filter(|value| value.contains("{{")) else {
+            continue;
+        };
+        let text = context.render(template);
+        match HeaderValue::from_str(&text) {
+            Ok(header) => {
+                *value = header;
+                values.push((name.to_string(), text));
+            },
+            Err(e) => warn!("Invalid rendered header value for '{}': {}", template, e),
+        }
+    }
+    
+    (rendered, values)
+}
+
+/// Value recorded in place of a sensitive header value
+const REDACTED: &str = "[redacted]";
+
+/// Whether a header carries credentials whose value must not be recorded
+fn is_sensitive_header(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    matches!(name.as_str(), "authorization" | "proxy-authorization" | "cookie" | "set-cookie")
+        || ["api-key", "apikey", "api_key", "token", "secret", "password", "session"].iter().any(|word| name.contains(word))
+}
+
+/// Size in bytes of the body of a request (None without a body)
+///
+/// Multipart bodies are streamed, but the form sets their length as the `Content-Length`
+/// header when the lengths of all parts are known.
+fn request_body_size(request: &Request) -> Option<usize> {
+    let body = request.body()?;
+    body.as_bytes().map(<[u8]>::len).or_else(|| {
+        request.headers().get(CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse().ok())
+    })
+}
+
+/// Request body read from a body file
+#[derive(Debug)]
+enum FileBody {
+    /// JSON body, templated and encoded like the fixed body
+    Json(Value),
+    
+    /// Raw bytes, sent as they are (shared by the requests rather than copied)
+    Raw(Bytes),
+}
+
+impl FileBody {
+    /// Read a body file, parsing `.json` files (which have to be objects of fields for
+    /// form and multipart bodies) and expanding the environment variables in their strings
+    async fn read(path: &Path, body_type: BodyType) -> Result<Self> {
+        let data_load = |source: Box<dyn std::error::Error + Send + Sync>| Error::DataLoad {
+            path: path.to_path_buf(),
+            source,
+        };
+        
+        let contents = tokio::fs::read(path).await.map_err(|e| data_load(Box::new(e)))?;
+        if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("json")) {
+            let mut body: Value = serde_json::from_slice(&contents).map_err(|e| data_load(Box::new(e)))?;
+            expand_env_value(&mut body).map_err(|e| data_load(e.into()))?;
+            if body_type != BodyType::Json && !body.is_object() {
+                return Err(data_load("form and multipart bodies must be objects of fields".into()));
+            }
+            Ok(FileBody::Json(body))
+        } else {
+            Ok(FileBody::Raw(Bytes::from(contents)))
+        }
     }
-} 
\ No newline at end of file
+}
+
+/// Fields of a form or multipart body, with non-string values in their JSON form
+fn form_fields(body: &Value) -> Result<Vec<(String, String)>> {
+    let Value::Object(fields) = body else {
+        return Err(Error::Other("Form and multipart bodies must be objects of fields".to_string()));
+    };
+    
+    Ok(fields.iter()
+        .map(|(name, value)| match value {
+            Value::String(value) => (name.clone(), value.clone()),
+            value => (name.clone(), value.to_string()),
+        })
+        .collect())
+}
+
+/// Extract values from a JSON response body by JSON pointer
+fn extract_values(body: Option<&str>, extract: &HashMap<String, String>) -> std::result::Result<Vec<(String, String)>, String> {
+    let body: Value = serde_json::from_str(body.unwrap_or_default())
+        .map_err(|e| format!("response body is not JSON: {}", e))?;
+    
+    extract.iter()
+        .map(|(name, pointer)| match body.pointer(pointer) {
+            Some(Value::String(value)) => Ok((name.clone(), value.clone())),
+            Some(Value::Null) | None => Err(format!("no value for '{}' at {}", name, pointer)),
+            Some(value) => Ok((name.clone(), value.to_string())),
+        })
+        .collect()
+}
+
+/// Resolver connecting requests sent to a TLS server name to the target's host
+///
+/// The TLS connector of the client takes the server name from the URL's host, so
+/// requests are sent to the server name (see `with_server_name`) and connected to
+/// the target here. Names are resolved on the blocking pool as they are needed.
+#[cfg(feature = "sni")]
+struct SniResolver {
+    server_name: String,
+    target_host: String,
+}
+
+#[cfg(feature = "sni")]
+impl Resolve for SniResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = if name.as_str().eq_ignore_ascii_case(&self.server_name) {
+            self.target_host.clone()
+        } else {
+            name.as_str().to_string()
+        };
+        
+        Box::pin(async move {
+            // The connector sets the port of the URL on the resolved addresses
+            let addrs = tokio::net::lookup_host((host.as_str(), 0)).await?;
+            Ok(Box::new(addrs.collect::<Vec<_>>().into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Replace the host of a URL with a TLS server name, returning the new URL and the
+/// original host (with its port, if any) for the `Host` header
+#[cfg(feature = "sni")]
+fn with_server_name(url: &str, server_name: &str) -> (String, Option<String>) {
+    let Ok(mut parsed) = reqwest::Url::parse(url) else {
+        return (url.to_string(), None);
+    };
+    let target_host = host_key(url);
+    
+    match parsed.set_host(Some(server_name)) {
+        Ok(()) => (parsed.to_string(), Some(target_host)),
+        Err(e) => {
+            warn!("Cannot send requests to SNI name {}: {}", server_name, e);
+            (url.to_string(), None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::WeightedBody;
+    use crate::test_server::{header, response, TestServer};
+    
+    #[tokio::test]
+    async fn client_applies_connection_settings() {
+        let server = TestServer::start(|request| match request.lines().next() {
+            Some(line) if line.starts_with("GET /login") => response(302, &[("Location", "/home"), ("Set-Cookie", "session=abc")], ""),
+            _ => response(200, &[], "home"),
+        });
+        let config = Config {
+            follow_redirects: false,
+            cookie_store: true,
+            user_agent: Some("custom/1.0".to_string()),
+            ..Default::default()
+        };
+        let client = Runner::create_client(&config).unwrap();
+        
+        let login = client.get(format!("{}/login", server.url)).send().await.unwrap();
+        assert_eq!(login.status(), StatusCode::FOUND);
+        client.get(format!("{}/home", server.url)).send().await.unwrap();
+        
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(header(&requests[0], "user-agent"), Some("custom/1.0"));
+        assert_eq!(header(&requests[1], "cookie"), Some("session=abc"));
+    }
+    
+    #[tokio::test]
+    async fn client_follows_redirects_up_to_the_limit() {
+        let server = TestServer::start(|request| {
+            let hops: usize = request.split_whitespace().nth(1).unwrap()[1..].parse().unwrap_or(0);
+            response(302, &[("Location", &format!("/{}", hops + 1))], "")
+        });
+        let config = Config {
+            max_redirects: 3,
+            ..Default::default()
+        };
+        let client = Runner::create_client(&config).unwrap();
+        
+        assert!(client.get(format!("{}/0", server.url)).send().await.is_err());
+        assert_eq!(server.requests().len(), 3);
+        assert_eq!(header(&server.requests()[0], "user-agent"), Some(DEFAULT_USER_AGENT));
+    }
+    
+    #[tokio::test]
+    async fn runs_send_the_user_agent() {
+        let server = TestServer::start(|_| response(200, &[], "ok"));
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 1,
+            ..Default::default()
+        };
+        let client = Runner::create_client(&config).unwrap();
+        Runner::new(client, config, None).unwrap().run().await.unwrap();
+        
+        let mut headers = HeaderMap::new();
+        headers.insert(reqwest::header::USER_AGENT, HeaderValue::from_static("header/2.0"));
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 1,
+            headers,
+            user_agent: Some("custom/1.0".to_string()),
+            ..Default::default()
+        };
+        let client = Runner::create_client(&config).unwrap();
+        Runner::new(client, config, None).unwrap().run().await.unwrap();
+        
+        let requests = server.requests();
+        assert_eq!(header(&requests[0], "user-agent"), Some(DEFAULT_USER_AGENT));
+        assert_eq!(header(&requests[1], "user-agent"), Some("header/2.0"));
+    }
+    
+    #[tokio::test]
+    async fn templated_headers_are_recorded_only_on_request_and_redacted() {
+        let server = TestServer::start(|_| response(200, &[], "ok"));
+        let mut headers = HeaderMap::new();
+        headers.insert("idempotency-key", HeaderValue::from_static("run-{{runid}}-{{index}}"));
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer {{uuid}}"));
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 3,
+            concurrency: 1,
+            headers,
+            run_id: Some("r1".to_string()),
+            ..Default::default()
+        };
+        
+        let results = runner(config.clone()).run().await.unwrap();
+        assert!(results.requests.iter().all(|r| r.rendered_headers.is_empty()));
+        let requests = server.requests();
+        let keys: HashSet<&str> = requests.iter()
+            .filter_map(|request| header(request, "idempotency-key"))
+            .collect();
+        assert_eq!(keys, HashSet::from(["run-r1-0", "run-r1-1", "run-r1-2"]));
+        
+        let results = runner(Config { record_headers: true, ..config }).run().await.unwrap();
+        let mut recorded: Vec<Vec<(String, String)>> = results.requests.iter()
+            .map(|r| r.rendered_headers.clone())
+            .collect();
+        recorded.sort();
+        for (index, headers) in recorded.iter().enumerate() {
+            assert!(headers.contains(&("idempotency-key".to_string(), format!("run-r1-{}", index))));
+            assert!(headers.contains(&("authorization".to_string(), REDACTED.to_string())));
+        }
+    }
+    
+    #[test]
+    fn credential_headers_are_sensitive() {
+        for name in ["Authorization", "Cookie", "X-Api-Key", "X-Auth-Token", "Client-Secret"] {
+            assert!(is_sensitive_header(name), "{}", name);
+        }
+        for name in ["Idempotency-Key", "X-Request-Id", "Accept"] {
+            assert!(!is_sensitive_header(name), "{}", name);
+        }
+    }
+    
+    #[tokio::test]
+    async fn runs_record_the_configured_http_version() {
+        let server = TestServer::start(|_| response(200, &[], "ok"));
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 2,
+            http_version: HttpVersion::Http1,
+            ..Default::default()
+        };
+        let client = Runner::create_client(&config).unwrap();
+        let results = Runner::new(client, config, None).unwrap().run().await.unwrap();
+        assert_eq!(results.http_versions.get("HTTP/1.1"), Some(&2));
+        
+        // The HTTP/1.1 server cannot answer HTTP/2 with prior knowledge
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 1,
+            http_version: HttpVersion::Http2PriorKnowledge,
+            ..Default::default()
+        };
+        let client = Runner::create_client(&config).unwrap();
+        let results = Runner::new(client, config, None).unwrap().run().await.unwrap();
+        assert_eq!(results.failed_requests, 1);
+        assert!(results.http_versions.is_empty());
+    }
+    
+    #[cfg(feature = "sni")]
+    #[tokio::test]
+    async fn sni_requests_connect_to_the_target() {
+        let server = TestServer::start(|_| response(200, &[], "ok"));
+        let config = Config {
+            url: format!("{}/path", server.url),
+            request_count: 2,
+            concurrency: 1,
+            sni: Some("edge.pressr.test".to_string()),
+            ..Default::default()
+        };
+        let client = Runner::create_client(&config).unwrap();
+        let results = Runner::new(client, config, None).unwrap().run().await.unwrap();
+        
+        assert_eq!(results.successful_requests, 2);
+        let requests = server.requests();
+        assert!(requests[0].starts_with("GET /path "));
+        assert_eq!(header(&requests[0], "host"), server.url.strip_prefix("http://"));
+    }
+    
+    #[cfg(feature = "sni")]
+    #[test]
+    fn sni_cannot_be_combined_with_targets_or_a_scenario() {
+        let sni = Some("edge.pressr.test".to_string());
+        let targets = Config { targets: vec![Target::new("http://a.test".to_string())], sni: sni.clone(), ..Default::default() };
+        assert!(Runner::new(Client::new(), targets, None).is_err());
+        
+        let data = RequestData {
+            scenario: vec![serde_json::from_value(serde_json::json!({ "url": "http://b.test/login" })).unwrap()],
+            ..Default::default()
+        };
+        assert!(Runner::new(Client::new(), Config { sni, ..Default::default() }, Some(data)).is_err());
+    }
+    
+    #[cfg(feature = "sni")]
+    #[tokio::test]
+    async fn sni_applies_to_the_etag_fetch_and_keepalive_pings() {
+        let server = TestServer::start(|_| response(200, &[("ETag", "\"v1\"")], "ok"));
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 2,
+            concurrency: 1,
+            etag_validation: true,
+            sni: Some("edge.pressr.test".to_string()),
+            ..Default::default()
+        };
+        let client = Runner::create_client(&config).unwrap();
+        let validated = Runner::new(client, config.clone(), None).unwrap().run().await.unwrap();
+        assert_eq!(validated.successful_requests, 2);
+        
+        let config = Config { etag_validation: false, ..config };
+        let client = Runner::create_client(&config).unwrap();
+        let pinged = Runner::new(client, config.clone(), None).unwrap().run_keepalive(Duration::from_millis(10)).await.unwrap();
+        assert_eq!(pinged.successful_requests, 2);
+        assert_eq!(pinged.sni.as_deref(), Some("edge.pressr.test"));
+        
+        let requests = server.requests();
+        assert_eq!(requests.len(), 5);
+        assert!(requests.iter().all(|request| header(request, "host") == server.url.strip_prefix("http://")));
+        
+        // A client that cannot resolve the server name shows the ETag fetch was sent to it
+        let unresolved = Runner::new(Client::new(), Config { etag_validation: true, ..config }, None).unwrap();
+        assert!(unresolved.run().await.is_err());
+        assert_eq!(server.requests().len(), 5);
+    }
+    
+    fn runner(config: Config) -> Runner {
+        Runner::new(Client::new(), config, None).unwrap()
+    }
+    
+    #[tokio::test]
+    async fn csv_values_are_percent_encoded_in_the_url_path() {
+        let server = TestServer::start(|_| response(200, &[], "ok"));
+        let data = RequestData {
+            rows: vec![HashMap::from([
+                ("host".to_string(), server.url.clone()),
+                ("user".to_string(), "jane doe/admin".to_string()),
+            ])],
+            ..Default::default()
+        };
+        let config = Config {
+            url: "{{host}}/users/{{user}}?tag={{user}}".to_string(),
+            request_count: 1,
+            ..Default::default()
+        };
+        
+        let results = Runner::new(Client::new(), config, Some(data)).unwrap().run().await.unwrap();
+        assert!(results.requests[0].success);
+        assert!(server.requests()[0].starts_with("GET /users/jane%20doe%2Fadmin?tag=jane%20doe%2Fadmin HTTP/1.1"));
+    }
+    
+    #[tokio::test]
+    async fn decompressed_bodies_are_truncated_at_the_read_limit() {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+        
+        // 8 MB of zeros compress to about 8 KB
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+        let zeros = vec![0; 1024 * 1024];
+        for _ in 0..8 {
+            encoder.write_all(&zeros).unwrap();
+        }
+        let body = encoder.finish().unwrap();
+        let server = TestServer::start_bytes(move |_| {
+            let mut response = response(200, &[("Content-Encoding", "gzip")], "")
+                .replace("Content-Length: 0", &format!("Content-Length: {}", body.len()))
+                .into_bytes();
+            response.extend_from_slice(&body);
+            response
+        });
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 1,
+            max_body_read: Some(4096),
+            ..Default::default()
+        };
+        let client = Runner::create_client(&config).unwrap();
+        
+        let results = Runner::new(client, config, None).unwrap().run().await.unwrap();
+        let result = &results.requests[0];
+        assert!(result.success);
+        assert!(result.body_truncated);
+        assert_eq!(result.response_size, Some(4096));
+        assert!(header(&server.requests()[0], "accept-encoding").is_some_and(|value| value.contains("gzip")));
+    }
+    
+    #[tokio::test]
+    async fn streamed_results_are_the_aggregated_results() {
+        let sent = Arc::new(AtomicUsize::new(0));
+        let server = TestServer::start(move |_| match sent.fetch_add(1, Ordering::Relaxed) % 3 {
+            0 => response(500, &[], "oops"),
+            _ => response(200, &[], "ok"),
+        });
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 30,
+            concurrency: 4,
+            ..Default::default()
+        };
+        
+        let (stream, task) = runner(config.clone()).run_streaming();
+        let mut streamed: Vec<_> = stream.map(|result| (result.index, result.status)).collect().await;
+        let results = task.await.unwrap().unwrap();
+        let mut aggregated: Vec<_> = results.requests.iter().map(|result| (result.index, result.status)).collect();
+        streamed.sort();
+        aggregated.sort();
+        assert_eq!(streamed, aggregated);
+        assert_eq!(streamed.iter().filter(|(_, status)| *status == Some(500)).count(), 10);
+        
+        // Nobody listening to the stream does not hold up the run
+        let (stream, task) = runner(config).run_streaming();
+        drop(stream);
+        assert_eq!(task.await.unwrap().unwrap().requests.len(), 30);
+    }
+    
+    #[test]
+    fn seeded_jitter_is_reproducible() {
+        let config = Config {
+            seed: Some(42),
+            retry_backoff: Duration::from_millis(1000),
+            retry_jitter: true,
+            ..Default::default()
+        };
+        let first = runner(config.clone());
+        let second = runner(config.clone());
+        
+        let backoffs: Vec<_> = (0..20).map(|index| first.state.retry_backoff(index, 0)).collect();
+        assert_eq!(backoffs, (0..20).map(|index| second.state.retry_backoff(index, 0)).collect::<Vec<_>>());
+        assert!(backoffs.iter().all(|backoff| (500..=1000).contains(&backoff.as_millis())));
+        assert!(backoffs.windows(2).any(|pair| pair[0] != pair[1]));
+        
+        // Batches continue the request numbering, so their jitter matches an unbatched run
+        let batch = runner(Config { first_index: 10, ..config });
+        assert_eq!(batch.state.retry_backoff(0, 0), backoffs[10]);
+    }
+    
+    fn think_config(url: &str, think_time: Duration) -> Config {
+        Config {
+            url: url.to_string(),
+            concurrency: 1,
+            think_time: Some(think_time),
+            ..Default::default()
+        }
+    }
+    
+    #[tokio::test]
+    async fn think_time_paces_each_slot() {
+        let server = TestServer::start(|_| response(200, &[], "ok"));
+        for dispatch in [DispatchMode::Interleaved, DispatchMode::Partitioned] {
+            let config = Config {
+                request_count: 3,
+                dispatch,
+                ..think_config(&server.url, Duration::from_millis(100))
+            };
+            let results = runner(config).run().await.unwrap();
+            
+            assert_eq!(results.successful_requests, 3);
+            assert!(results.duration_secs >= 0.2, "{:?}: {}", dispatch, results.duration_secs);
+            assert!(results.requests.iter().all(|r| r.response_time < 100));
+        }
+    }
+    
+    #[tokio::test]
+    async fn think_time_ends_with_a_timed_run() {
+        let server = TestServer::start(|_| response(200, &[], "ok"));
+        let config = Config {
+            request_count: usize::MAX,
+            concurrency: 2,
+            duration: Some(Duration::from_millis(200)),
+            ..think_config(&server.url, Duration::from_secs(5))
+        };
+        let results = runner(config).run().await.unwrap();
+        
+        assert_eq!(results.total_requests, 2);
+        assert_eq!(results.successful_requests, 2);
+        assert!(results.duration_secs < 1.0);
+    }
+    
+    #[tokio::test]
+    async fn stopping_while_thinking_cancels_nothing() {
+        let server = TestServer::start(|_| response(200, &[], "ok"));
+        let config = Config {
+            request_count: 10,
+            ..think_config(&server.url, Duration::from_secs(5))
+        };
+        let runner = runner(config);
+        let stop = runner.stop_handle();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            stop.stop();
+        });
+        let results = runner.run().await.unwrap();
+        
+        assert_eq!(results.total_requests, 1);
+        assert!(results.requests.iter().all(|r| !r.is_cancelled()));
+        assert!(results.duration_secs < 1.0);
+    }
+    
+    #[tokio::test]
+    async fn bursts_end_when_the_schedule_overflows() {
+        let server = TestServer::start(|_| response(200, &[], "ok"));
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 4,
+            burst: Some(BurstProfile {
+                burst_size: 2,
+                burst_interval: Duration::MAX,
+                late_tolerance: Duration::from_secs(1),
+            }),
+            ..Default::default()
+        };
+        let results = runner(config).run().await.unwrap();
+        
+        assert_eq!(results.total_requests, 2);
+        assert!(results.requests.iter().all(|r| r.burst == Some(0)));
+    }
+    
+    #[tokio::test]
+    async fn bursts_are_dispatched_on_their_schedule() {
+        let server = TestServer::start(|_| response(200, &[], "ok"));
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 6,
+            burst: Some(BurstProfile {
+                burst_size: 2,
+                burst_interval: Duration::from_millis(100),
+                late_tolerance: Duration::from_secs(1),
+            }),
+            ..Default::default()
+        };
+        let results = runner(config).run().await.unwrap();
+        
+        assert_eq!(results.total_requests, 6);
+        for burst in 0..3 {
+            let requests: Vec<_> = results.requests.iter().filter(|r| r.burst == Some(burst)).collect();
+            assert_eq!(requests.len(), 2);
+            for request in requests {
+                let started_at = request.started_at.unwrap();
+                let scheduled = burst as u128 * 100;
+                assert!(started_at >= scheduled && started_at < scheduled + 80, "burst {} started at {} ms", burst, started_at);
+            }
+        }
+        assert!(results.duration_secs >= 0.2);
+    }
+    
+    #[tokio::test]
+    async fn queue_wait_grows_behind_a_slow_server() {
+        let server = TestServer::start(|_| {
+            std::thread::sleep(Duration::from_millis(100));
+            response(200, &[], "ok")
+        });
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 4,
+            burst: Some(BurstProfile {
+                burst_size: 1,
+                burst_interval: Duration::from_millis(20),
+                late_tolerance: Duration::from_secs(1),
+            }),
+            ..Default::default()
+        };
+        let results = runner(config).run().await.unwrap();
+        
+        // Burst k is scheduled at 20k ms but starts after the k bursts before it, at about 100k ms
+        let mut waits: Vec<(usize, u128)> = results.requests.iter()
+            .map(|r| (r.burst.unwrap(), r.queue_wait.unwrap()))
+            .collect();
+        waits.sort();
+        assert!(waits.windows(2).all(|pair| pair[1].1 >= pair[0].1 + 50), "queue waits {:?}", waits);
+        
+        let queue_wait = results.queue_wait.unwrap();
+        assert_eq!(queue_wait.requests, 4);
+        assert!(queue_wait.delayed >= 3);
+        assert!(queue_wait.max >= 200 && queue_wait.p50 >= 80);
+    }
+    
+    #[tokio::test]
+    async fn requests_of_a_burst_queued_behind_a_slow_one_are_late() {
+        let server = TestServer::start(|_| {
+            std::thread::sleep(Duration::from_millis(150));
+            response(200, &[], "ok")
+        });
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 4,
+            burst: Some(BurstProfile {
+                burst_size: 2,
+                burst_interval: Duration::from_millis(30),
+                late_tolerance: Duration::from_millis(20),
+            }),
+            ..Default::default()
+        };
+        let results = runner(config).run().await.unwrap();
+        
+        // The second burst is scheduled at 30 ms but waits for the first to complete
+        for request in &results.requests {
+            let queue_wait = request.queue_wait.unwrap();
+            match request.burst {
+                Some(0) => assert!(queue_wait < 20 && !request.late, "queue wait {}", queue_wait),
+                _ => assert!(queue_wait >= 100 && request.late, "queue wait {}", queue_wait),
+            }
+        }
+        let queue_wait = results.queue_wait.unwrap();
+        assert_eq!(queue_wait.late, 2);
+        assert_eq!(queue_wait.late_percent, 50.0);
+        assert!(queue_wait.late_average_response_time.is_some());
+    }
+    
+    #[tokio::test]
+    async fn stopping_without_grace_abandons_requests_in_flight() {
+        let served = Arc::new(AtomicUsize::new(0));
+        let server = TestServer::start(move |_| {
+            // Answer the first two requests, keep the next ones in flight well past the stop
+            if served.fetch_add(1, Ordering::SeqCst) >= 2 {
+                std::thread::sleep(Duration::from_secs(2));
+            }
+            response(200, &[], "ok")
+        });
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 1000,
+            concurrency: 2,
+            shutdown_grace: Duration::ZERO,
+            ..Default::default()
+        };
+        let runner = runner(config);
+        let stop = runner.stop_handle();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            stop.stop();
+        });
+        let started = Instant::now();
+        let results = runner.run().await.unwrap();
+        
+        // The two requests in flight at the stop are cancelled rather than awaited
+        assert!(started.elapsed() < Duration::from_secs(1), "stopped after {:?}", started.elapsed());
+        assert_eq!(results.successful_requests, 2);
+        assert_eq!(results.cancelled_requests, 2);
+        assert_eq!(results.total_requests, results.attempted_requests);
+    }
+    
+    #[tokio::test]
+    async fn keepalive_pings_detect_connections_dropped_while_idle() {
+        // The server closes connections idle for 50 ms, the workers ping every 200 ms
+        let server = TestServer::start_closing_idle(Duration::from_millis(50), |_| response(200, &[], "ok"));
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 6,
+            concurrency: 2,
+            ..Default::default()
+        };
+        let results = runner(config).run_keepalive(Duration::from_millis(200)).await.unwrap();
+        let keepalive = results.keepalive.unwrap();
+        
+        assert_eq!(results.successful_requests, 6);
+        assert_eq!(keepalive.pings, 6);
+        assert_eq!(keepalive.connections.len(), 6);
+        assert_eq!(keepalive.drops, 4);
+        for worker in 0..2 {
+            let dropped: Vec<_> = keepalive.connections.iter()
+                .filter(|connection| connection.worker == worker && connection.dropped_at.is_some())
+                .collect();
+            assert_eq!(dropped.len(), 2);
+            assert!(dropped.iter().all(|connection| connection.pings == 1));
+        }
+        assert!(keepalive.min_dropped_lifetime.is_some());
+    }
+    
+    #[tokio::test]
+    async fn keepalive_pings_reuse_connections_the_server_keeps_open() {
+        let server = TestServer::start(|_| response(200, &[], "ok"));
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 6,
+            concurrency: 2,
+            ..Default::default()
+        };
+        let results = runner(config).run_keepalive(Duration::from_millis(50)).await.unwrap();
+        let keepalive = results.keepalive.unwrap();
+        
+        assert_eq!(keepalive.pings, 6);
+        assert_eq!(keepalive.drops, 0);
+        assert_eq!(keepalive.connections.len(), 2);
+        assert!(keepalive.connections.iter().all(|connection| connection.pings == 3 && connection.dropped_at.is_none()));
+        assert_eq!(keepalive.average_dropped_lifetime, None);
+    }
+    
+    #[tokio::test]
+    async fn cancelled_requests_are_left_out_of_latency_statistics() {
+        let served = Arc::new(AtomicUsize::new(0));
+        let server = TestServer::start(move |_| {
+            // Answer the first request, keep the other one in flight past the grace period
+            if served.fetch_add(1, Ordering::SeqCst) > 0 {
+                std::thread::sleep(Duration::from_secs(2));
+            }
+            response(200, &[], "ok")
+        });
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 2,
+            concurrency: 2,
+            shutdown_grace: Duration::from_millis(100),
+            ..Default::default()
+        };
+        let runner = runner(config);
+        let stop = runner.stop_handle();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            stop.stop();
+        });
+        let results = runner.run().await.unwrap();
+        
+        assert_eq!(results.total_requests, 2);
+        assert_eq!(results.attempted_requests, 2);
+        assert_eq!(results.cancelled_requests, 1);
+        assert_eq!(results.failed_requests, 1);
+        assert_eq!(results.errors.get(CANCELLED_ERROR), Some(&1));
+        let cancelled = results.requests.iter().find(|r| r.is_cancelled()).unwrap();
+        assert!(cancelled.cancelled);
+        let measured = results.requests.iter().find(|r| r.success).unwrap().response_time;
+        assert_eq!(results.min_response_time, measured);
+        assert_eq!(results.max_response_time, measured);
+        assert_eq!(results.average_response_time, measured as f64);
+        assert_eq!(results.response_time_std_dev, 0.0);
+        assert_eq!(results.response_time_distribution.values().sum::<usize>(), 1);
+    }
+    
+    #[tokio::test]
+    async fn streamed_bodies_record_the_first_chunk_and_stream_duration() {
+        use std::io::{Read, Write};
+        
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let _ = stream.read(&mut [0; 1024]).unwrap();
+            stream.write_all(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n").unwrap();
+            // Think before the first chunk, then stream the rest slowly
+            for (delay, chunk) in [(100, "5\r\nfirst\r\n"), (200, "6\r\nsecond\r\n"), (0, "0\r\n\r\n")] {
+                std::thread::sleep(Duration::from_millis(delay));
+                stream.write_all(chunk.as_bytes()).unwrap();
+                stream.flush().unwrap();
+            }
+        });
+        let config = Config {
+            url,
+            request_count: 1,
+            ..Default::default()
+        };
+        let results = runner(config).run().await.unwrap();
+        
+        let request = &results.requests[0];
+        let ttfb = request.ttfb.unwrap();
+        let first_chunk = request.first_chunk.unwrap();
+        // Durations are truncated to milliseconds, and reading a chunk can lag behind its write
+        let stream_duration = request.stream_duration.unwrap();
+        assert!(first_chunk >= 100 && first_chunk >= ttfb, "first chunk after {} ms", first_chunk);
+        assert!(stream_duration >= 150, "streamed for {} ms", stream_duration);
+        assert!(request.response_time + 1 >= first_chunk + stream_duration);
+        assert_eq!(request.response_size, Some(11));
+        let streaming = results.streaming.unwrap();
+        assert_eq!(streaming.average_first_chunk, first_chunk as f64);
+    }
+    
+    #[tokio::test]
+    async fn think_time_is_not_part_of_a_scenario_iteration() {
+        let server = TestServer::start(|_| response(200, &[], "ok"));
+        let data: RequestData = serde_json::from_value(serde_json::json!({
+            "scenario": [{ "url": format!("{}/a", server.url) }, { "url": format!("{}/b", server.url) }]
+        })).unwrap();
+        let config = Config {
+            request_count: 1,
+            ..think_config(&server.url, Duration::from_millis(200))
+        };
+        let results = Runner::new(Client::new(), config, Some(data)).unwrap().run().await.unwrap();
+        
+        assert_eq!(results.successful_requests, 1);
+        assert_eq!(results.requests[0].steps.len(), 2);
+        assert!(results.requests[0].response_time < 200);
+        assert!(results.duration_secs >= 0.2);
+    }
+    
+    #[tokio::test]
+    async fn multipart_requests_record_their_size() {
+        let server = TestServer::start(|_| response(200, &[], "ok"));
+        let path = std::env::temp_dir().join(format!("pressr-upload-{}.txt", std::process::id()));
+        std::fs::write(&path, "file contents").unwrap();
+        let data: RequestData = serde_json::from_value(serde_json::json!({
+            "body_type": "multipart",
+            "body": { "name": "upload" },
+            "files": { "file": path },
+        })).unwrap();
+        let config = Config {
+            url: server.url.clone(),
+            method: Method::POST,
+            request_count: 2,
+            ..Default::default()
+        };
+        let results = Runner::new(Client::new(), config, Some(data)).unwrap().run().await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+        
+        let requests = server.requests();
+        let (head, body) = requests[0].split_once("\r\n\r\n").unwrap();
+        assert!(body.contains("file contents") && body.contains("upload"));
+        assert_eq!(header(head, "content-length"), Some(body.len().to_string().as_str()));
+        assert!(results.requests.iter().all(|r| r.request_size == Some(body.len())));
+    }
+    
+    #[tokio::test]
+    async fn body_files_are_checked_before_sending() {
+        let server = TestServer::start(|_| response(200, &[], "ok"));
+        let config = Config {
+            url: server.url.clone(),
+            method: Method::POST,
+            request_count: 1,
+            ..Default::default()
+        };
+        let missing: RequestData = serde_json::from_value(serde_json::json!({ "body_file": "missing-body.json" })).unwrap();
+        let result = Runner::new(Client::new(), config.clone(), Some(missing)).unwrap().run().await;
+        assert!(matches!(result, Err(Error::DataLoad { path, .. }) if path.ends_with("missing-body.json")));
+        
+        let path = std::env::temp_dir().join(format!("pressr-body-{}.json", std::process::id()));
+        std::fs::write(&path, "[1, 2]").unwrap();
+        let list: RequestData = serde_json::from_value(serde_json::json!({ "body_file": path, "body_type": "form" })).unwrap();
+        let result = Runner::new(Client::new(), config, Some(list)).unwrap().run().await;
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(Error::DataLoad { .. })));
+        assert!(server.requests().is_empty());
+    }
+    
+    #[tokio::test]
+    async fn form_target_bodies_must_be_objects() {
+        let server = TestServer::start(|_| response(200, &[], "ok"));
+        let data: RequestData = serde_json::from_value(serde_json::json!({ "body_type": "form" })).unwrap();
+        let config = Config {
+            url: server.url.clone(),
+            method: Method::POST,
+            request_count: 1,
+            targets: vec![Target { body: Some(serde_json::json!("text")), ..Target::new(server.url.clone()) }],
+            ..Default::default()
+        };
+        
+        assert!(Runner::new(Client::new(), config, Some(data)).is_err());
+        assert!(server.requests().is_empty());
+    }
+    
+    #[test]
+    fn scenarios_are_checked_on_creation() {
+        let data: RequestData = serde_json::from_value(serde_json::json!({
+            "scenario": [{ "url": "http://localhost/users/{id}" }]
+        })).unwrap();
+        
+        assert!(Runner::new(Client::new(), Config::default(), Some(data)).is_err());
+    }
+    
+    #[tokio::test]
+    async fn scenario_steps_encode_their_body_type() {
+        let server = TestServer::start(|_| response(200, &[], "ok"));
+        let data: RequestData = serde_json::from_value(serde_json::json!({
+            "body_type": "form",
+            "scenario": [
+                { "url": format!("{}/login", server.url), "method": "POST", "body": { "user": "a b" } },
+                { "url": format!("{}/items", server.url), "method": "POST", "body": { "id": 1 }, "body_type": "json" },
+            ]
+        })).unwrap();
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 1,
+            ..Default::default()
+        };
+        let results = Runner::new(Client::new(), config, Some(data)).unwrap().run().await.unwrap();
+        
+        let steps: Vec<_> = results.requests[0].steps.iter()
+            .map(|step| (step.method.as_deref().unwrap(), step.url.clone().unwrap()))
+            .collect();
+        assert_eq!(steps, [("POST", format!("{}/login", server.url)), ("POST", format!("{}/items", server.url))]);
+        let requests = server.requests();
+        assert_eq!(header(&requests[0], "content-type"), Some("application/x-www-form-urlencoded"));
+        assert!(requests[0].ends_with("user=a+b"));
+        assert_eq!(header(&requests[1], "content-type"), Some("application/json"));
+        assert!(requests[1].ends_with("{\"id\":1}"));
+    }
+    
+    #[test]
+    fn client_rejects_an_invalid_proxy() {
+        let config = Config {
+            proxy: Some("not a proxy".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(Runner::create_client(&config), Err(Error::InvalidProxy { .. })));
+    }
+    
+    #[tokio::test]
+    async fn error_rate_aborts_report_distinct_configured_attempted_and_completed_counts() {
+        let served = Arc::new(AtomicUsize::new(0));
+        let server = TestServer::start(move |_| {
+            // The second failure aborts the run while the third request is still in flight
+            match served.fetch_add(1, Ordering::SeqCst) {
+                0 => {},
+                1 => std::thread::sleep(Duration::from_millis(200)),
+                _ => std::thread::sleep(Duration::from_secs(2)),
+            }
+            response(500, &[], "error")
+        });
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 100,
+            concurrency: 2,
+            abort_on_error_rate: Some(50.0),
+            abort_min_requests: 2,
+            shutdown_grace: Duration::ZERO,
+            ..Default::default()
+        };
+        let results = runner(config).run().await.unwrap();
+        
+        assert!(results.aborted.is_some());
+        assert_eq!(results.configured_requests, 100);
+        assert_eq!(results.attempted_requests, 3);
+        assert_eq!(results.completed_requests(), 2);
+        assert_eq!(results.cancelled_requests, 1);
+    }
+    
+    /// Log lines written while the subscriber is the default
+    #[derive(Clone, Default)]
+    struct CapturedLog(Arc<Mutex<Vec<u8>>>);
+    
+    impl std::io::Write for CapturedLog {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    
+    #[tokio::test]
+    async fn concurrency_is_clamped_to_the_request_count_with_a_note() {
+        let server = TestServer::start(|_| response(200, &[], "ok"));
+        let log = CapturedLog::default();
+        let writer = log.clone();
+        let subscriber = tracing_subscriber::fmt().with_ansi(false).with_writer(move || writer.clone()).finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+        
+        let config = Config { url: server.url.clone(), request_count: 3, concurrency: 100, ..Default::default() };
+        let closed = runner(config);
+        assert_eq!(closed.effective_concurrency(), 3);
+        let results = closed.run().await.unwrap();
+        assert_eq!(results.successful_requests, 3);
+        let log = String::from_utf8(log.0.lock().unwrap().clone()).unwrap();
+        assert!(log.contains("Concurrency 100 exceeds the request count, clamping to 3"), "{}", log);
+        
+        // Timed runs without a request limit keep every slot
+        let timed = Config { duration: Some(Duration::from_secs(1)), request_count: usize::MAX, concurrency: 100, ..Default::default() };
+        assert_eq!(runner(timed).effective_concurrency(), 100);
+    }
+    
+    #[tokio::test]
+    async fn ignored_bodies_are_never_read() {
+        // The body announced by the headers never arrives: reading it would wait out the timeout
+        let server = TestServer::start(|_| "HTTP/1.1 200 OK\r\nContent-Length: 100000000\r\n\r\npartial".to_string());
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 2,
+            timeout: 5,
+            read_body: false,
+            ..Default::default()
+        };
+        let started = Instant::now();
+        let results = runner(config).run().await.unwrap();
+        
+        assert!(started.elapsed() < Duration::from_secs(1), "took {:?}", started.elapsed());
+        assert_eq!(results.successful_requests, 2);
+        assert!(results.requests.iter().all(|request| request.response_size.is_none() && request.response_time < 1000));
+        assert_eq!(results.transfer_rate, None);
+    }
+    
+    #[tokio::test]
+    async fn result_callbacks_run_once_per_request() {
+        let server = TestServer::start(|_| response(200, &[], "ok"));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let config = Config { url: server.url.clone(), request_count: 25, concurrency: 5, ..Default::default() };
+        
+        let results = runner(config)
+            .on_result(Arc::new(move |_: &RequestResult| { counter.fetch_add(1, Ordering::SeqCst); }))
+            .run().await.unwrap();
+        
+        assert_eq!(results.total_requests, 25);
+        assert_eq!(calls.load(Ordering::SeqCst), 25);
+    }
+    
+    #[tokio::test]
+    async fn hosts_get_independent_stats() {
+        let fast = TestServer::start(|_| response(200, &[], "ok"));
+        let slow = TestServer::start(|_| {
+            std::thread::sleep(Duration::from_millis(50));
+            response(503, &[], "busy")
+        });
+        let config = Config {
+            url: fast.url.clone(),
+            request_count: 8,
+            concurrency: 2,
+            targets: vec![Target::new(fast.url.clone()), Target { weight: 3, ..Target::new(slow.url.clone()) }],
+            ..Default::default()
+        };
+        let results = runner(config).run().await.unwrap();
+        let host = |server: &TestServer| &results.by_host[&host_key(&server.url)];
+        
+        assert_eq!(results.by_host.len(), 2);
+        assert_eq!((host(&fast).requests, host(&fast).successful, host(&fast).failed), (2, 2, 0));
+        assert_eq!((host(&slow).requests, host(&slow).successful, host(&slow).failed), (6, 0, 6));
+        assert!(host(&slow).min_response_time >= 50);
+        assert!(host(&fast).max_response_time < host(&slow).min_response_time);
+        assert_eq!(host(&slow).errors.values().sum::<usize>(), 6);
+        assert!(host(&fast).errors.is_empty());
+        assert!(results.requests.iter().all(|result| result.method.as_deref() == Some("GET")));
+        assert_eq!(results.requests.iter().filter(|result| result.url.as_ref() == Some(&slow.url)).count(), 6);
+    }
+    
+    #[tokio::test]
+    async fn probes_stop_at_the_first_success() {
+        let served = Arc::new(AtomicUsize::new(0));
+        let server = TestServer::start(move |_| {
+            // Down for the first 3 probes
+            if served.fetch_add(1, Ordering::SeqCst) < 3 {
+                response(503, &[], "starting")
+            } else {
+                response(200, &[], "up")
+            }
+        });
+        let config = Config { url: server.url.clone(), request_count: 10, ..Default::default() };
+        let results = runner(config).run_until_success(Duration::from_secs(10)).await.unwrap();
+        
+        assert_eq!(results.total_requests, 4);
+        assert_eq!(results.successful_requests, 1);
+        assert!(results.requests.last().unwrap().success);
+        assert_eq!(server.requests().len(), 4);
+        // Backing off 100, 200 and 400 ms between the attempts
+        assert!(results.duration_secs >= 0.7, "{}", results.duration_secs);
+    }
+    
+    #[tokio::test]
+    async fn the_retry_budget_caps_the_retries_of_a_run() {
+        let server = TestServer::start(|_| response(503, &[], "busy"));
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 20,
+            concurrency: 2,
+            retries: 3,
+            retry_backoff: Duration::ZERO,
+            retry_budget: Some(10.0),
+            ..Default::default()
+        };
+        
+        let results = runner(config).run().await.unwrap();
+        assert_eq!(results.retries, 2);
+        assert!(results.retry_budget_exhausted);
+        assert_eq!(results.requests.iter().map(|r| r.retries as usize).sum::<usize>(), 2);
+        assert_eq!(server.requests().len(), 22);
+    }
+    
+    #[tokio::test]
+    async fn pooled_bodies_are_picked_by_weight() {
+        let server = TestServer::start(|_| response(200, &[], "ok"));
+        let pooled = |name: &str, weight: f64| WeightedBody {
+            name: Some(name.to_string()),
+            weight,
+            body: serde_json::json!({ "kind": name }),
+        };
+        let data = RequestData {
+            body_pool: vec![pooled("small", 1.0), pooled("medium", 3.0), pooled("large", 6.0)],
+            ..Default::default()
+        };
+        let config = Config {
+            url: server.url.clone(),
+            method: Method::POST,
+            request_count: 1000,
+            concurrency: 4,
+            seed: Some(7),
+            ..Default::default()
+        };
+        
+        let results = Runner::new(Client::new(), config, Some(data)).unwrap().run().await.unwrap();
+        let share = |name: &str| results.body_usage.get(name).copied().unwrap_or_default() as f64 / 1000.0;
+        assert!((share("small") - 0.1).abs() < 0.04, "{:?}", results.body_usage);
+        assert!((share("medium") - 0.3).abs() < 0.05, "{:?}", results.body_usage);
+        assert!((share("large") - 0.6).abs() < 0.05, "{:?}", results.body_usage);
+        
+        let sent_small = server.requests().iter().filter(|request| request.ends_with(r#"{"kind":"small"}"#)).count();
+        assert_eq!(sent_small, results.body_usage["small"]);
+    }
+    
+    #[tokio::test]
+    async fn timed_runs_finish_the_requests_in_flight_at_the_deadline() {
+        let server = TestServer::start(|_| {
+            std::thread::sleep(Duration::from_millis(80));
+            response(200, &[], "ok")
+        });
+        let config = Config {
+            url: server.url.clone(),
+            request_count: usize::MAX,
+            concurrency: 2,
+            duration: Some(Duration::from_millis(300)),
+            ..Default::default()
+        };
+        
+        let results = runner(config.clone()).run().await.unwrap();
+        assert!((4..=10).contains(&results.total_requests), "{}", results.total_requests);
+        assert_eq!(results.successful_requests, results.total_requests);
+        assert_eq!(results.cancelled_requests, 0);
+        assert_eq!(server.requests().len(), results.total_requests);
+        assert!(results.duration_secs >= 0.3);
+        
+        // The request count stays an upper bound
+        let capped = runner(Config { request_count: 3, duration: Some(Duration::from_secs(10)), ..config }).run().await.unwrap();
+        assert_eq!(capped.total_requests, 3);
+        assert!(capped.duration_secs < 1.0);
+    }
+    
+    #[tokio::test]
+    async fn a_short_idle_timeout_opens_a_connection_per_request() {
+        let server = TestServer::start(|_| response(200, &[], "ok"));
+        let config = Config {
+            request_count: 5,
+            ..think_config(&server.url, Duration::from_millis(100))
+        };
+        
+        let short = Config { idle_timeout: Some(Duration::from_millis(10)), ..config.clone() };
+        let client = Runner::create_client(&short).unwrap();
+        let recycled = Runner::new(client, short, None).unwrap().run().await.unwrap();
+        let connections = recycled.connections.unwrap();
+        assert_eq!(connections.new_connections, 5);
+        assert_eq!(connections.new_connection_percent, 100.0);
+        
+        let pooled = runner(config).run().await.unwrap();
+        assert_eq!(pooled.connections.unwrap().new_connections, 1);
+    }
+    
+    #[tokio::test]
+    async fn requests_in_flight_never_exceed_the_permits() {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let server = {
+            let (in_flight, peak) = (in_flight.clone(), peak.clone());
+            TestServer::start(move |_| {
+                let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(current, Ordering::SeqCst);
+                std::thread::sleep(Duration::from_millis(20));
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                response(200, &[], "ok")
+            })
+        };
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 40,
+            concurrency: 4,
+            ..Default::default()
+        };
+        
+        let results = runner(config).run().await.unwrap();
+        assert_eq!(results.successful_requests, 40);
+        assert_eq!(peak.load(Ordering::SeqCst), 4);
+    }
+    
+    #[tokio::test]
+    async fn ramping_up_starts_with_fewer_requests_in_flight() {
+        let start = Instant::now();
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let observed = Arc::new(Mutex::new(Vec::new()));
+        let server = {
+            let (in_flight, observed) = (in_flight.clone(), observed.clone());
+            TestServer::start(move |_| {
+                let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                observed.lock().unwrap().push((start.elapsed(), current));
+                std::thread::sleep(Duration::from_millis(50));
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                response(200, &[], "ok")
+            })
+        };
+        let config = Config {
+            url: server.url.clone(),
+            request_count: usize::MAX,
+            concurrency: 8,
+            duration: Some(Duration::from_millis(800)),
+            ramp_up: Some(Duration::from_millis(400)),
+            ..Default::default()
+        };
+        
+        let results = runner(config).run().await.unwrap();
+        assert_eq!(results.failed_requests, 0);
+        let observed = observed.lock().unwrap();
+        let peak = |from: u64, to: u64| observed.iter()
+            .filter(|(at, _)| (Duration::from_millis(from)..Duration::from_millis(to)).contains(at))
+            .map(|(_, current)| *current)
+            .max()
+            .unwrap_or_default();
+        assert!(peak(0, 100) <= 3, "{:?}", observed);
+        assert_eq!(peak(500, 800), 8, "{:?}", observed);
+    }
+    
+    #[tokio::test]
+    async fn only_failures_matching_a_retry_pattern_are_retried() {
+        let server = TestServer::start(|request| if request.starts_with("GET /busy ") {
+            response(400, &[], r#"{"error":"upstream busy, try again"}"#)
+        } else {
+            response(400, &[], r#"{"error":"invalid id"}"#)
+        });
+        let config = |path: &str| Config {
+            url: format!("{}{}", server.url, path),
+            request_count: 1,
+            retries: 2,
+            retry_backoff: Duration::ZERO,
+            retry_on: vec![RetryMatcher::Substring("try again".to_string()), RetryMatcher::regex("timed? ?out").unwrap()],
+            ..Default::default()
+        };
+        
+        let busy = runner(config("/busy")).run().await.unwrap();
+        assert_eq!(busy.retries, 2);
+        let invalid = runner(config("/invalid")).run().await.unwrap();
+        assert_eq!(invalid.retries, 0);
+        
+        let requests = server.requests();
+        assert_eq!(requests.iter().filter(|request| request.starts_with("GET /busy ")).count(), 3);
+        assert_eq!(requests.iter().filter(|request| request.starts_with("GET /invalid ")).count(), 1);
+    }
+    
+    #[tokio::test]
+    async fn distinct_source_ports_reflect_connection_pooling() {
+        let pooled = TestServer::start(|_| response(200, &[], "ok"));
+        let closing = TestServer::start(|_| response(200, &[("Connection", "close")], "ok"));
+        let config = |url: &str| Config {
+            url: url.to_string(),
+            request_count: 20,
+            concurrency: 2,
+            ..Default::default()
+        };
+        
+        let reused = runner(config(&pooled.url)).run().await.unwrap();
+        assert!(reused.requests.iter().all(|r| r.local_port.is_some()));
+        let ports = reused.connections.unwrap().distinct_ports;
+        assert!((1..=2).contains(&ports), "{}", ports);
+        
+        let fresh = runner(config(&closing.url)).run().await.unwrap();
+        assert_eq!(fresh.connections.unwrap().distinct_ports, 20);
+    }
+    
+    #[tokio::test]
+    async fn data_params_are_encoded_after_the_url_query() {
+        let server = TestServer::start(|_| response(200, &[], "ok"));
+        let data = RequestData {
+            params: HashMap::from([
+                ("q".to_string(), "rust & tokio".to_string()),
+                ("lang".to_string(), "en/GB".to_string()),
+            ]),
+            ..Default::default()
+        };
+        let config = Config {
+            url: format!("{}/search?page=2", server.url),
+            request_count: 1,
+            ..Default::default()
+        };
+        
+        let results = Runner::new(Client::new(), config, Some(data)).unwrap().run().await.unwrap();
+        assert!(results.requests[0].success);
+        let request_line = server.requests()[0].lines().next().unwrap().to_string();
+        assert_eq!(request_line, "GET /search?page=2&lang=en%2FGB&q=rust+%26+tokio HTTP/1.1");
+    }
+    
+    #[tokio::test]
+    async fn config_headers_override_data_file_headers() {
+        let server = TestServer::start(|_| response(200, &[], "ok"));
+        let data = RequestData {
+            headers: HashMap::from([
+                ("X-Tenant".to_string(), "data".to_string()),
+                ("X-Source".to_string(), "data-file".to_string()),
+            ]),
+            ..Default::default()
+        };
+        let mut headers = HeaderMap::new();
+        headers.insert("x-tenant", HeaderValue::from_static("cli"));
+        headers.insert("x-trace", HeaderValue::from_static("on"));
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 1,
+            headers,
+            ..Default::default()
+        };
+        
+        let merged = Runner::new(Client::new(), config, Some(data)).unwrap();
+        assert_eq!(merged.state.headers.len(), 3);
+        assert_eq!(merged.state.headers["x-tenant"], "cli");
+        assert_eq!(merged.state.headers["x-source"], "data-file");
+        assert_eq!(merged.state.headers["x-trace"], "on");
+        
+        merged.run().await.unwrap();
+        let request = &server.requests()[0];
+        assert_eq!(header(request, "x-tenant"), Some("cli"));
+        assert_eq!(header(request, "x-source"), Some("data-file"));
+        assert_eq!(header(request, "x-trace"), Some("on"));
+    }
+    
+    #[tokio::test]
+    async fn expected_404s_do_not_count_against_the_error_rate() {
+        let served = AtomicUsize::new(0);
+        let server = TestServer::start(move |_| match served.fetch_add(1, Ordering::SeqCst) % 2 {
+            0 => response(200, &[], "ok"),
+            _ => response(404, &[], "not found"),
+        });
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 10,
+            concurrency: 1,
+            expected_failures: vec![404],
+            ..Default::default()
+        };
+        
+        let results = runner(config).run().await.unwrap();
+        assert_eq!(results.successful_requests, 5);
+        assert_eq!(results.expected_failures, 5);
+        assert_eq!(results.failed_requests, 0);
+        let thresholds = Thresholds { max_error_rate: Some(1.0), ..Default::default() };
+        assert_eq!(thresholds.evaluate(&results), Vec::new());
+    }
+    
+    #[tokio::test]
+    async fn the_cookie_store_shares_the_session_across_requests() {
+        let server = TestServer::start(|request| match header(request, "cookie") {
+            Some(cookie) => response(200, &[], &format!("echo {}", cookie)),
+            None => response(200, &[("Set-Cookie", "session=s1; Path=/")], "new session"),
+        });
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 4,
+            concurrency: 1,
+            cookie_store: true,
+            ..Default::default()
+        };
+        
+        let client = Runner::create_client(&config).unwrap();
+        Runner::new(client, config.clone(), None).unwrap().run().await.unwrap();
+        let cookies: Vec<Option<String>> = server.requests().iter()
+            .map(|request| header(request, "cookie").map(str::to_string))
+            .collect();
+        assert_eq!(cookies, [None, Some("session=s1".to_string()), Some("session=s1".to_string()), Some("session=s1".to_string())]);
+        
+        // Without the store, every request starts a new session
+        let without = Config { cookie_store: false, ..config };
+        let client = Runner::create_client(&without).unwrap();
+        Runner::new(client, without, None).unwrap().run().await.unwrap();
+        assert!(server.requests()[4..].iter().all(|request| header(request, "cookie").is_none()));
+    }
+    
+    #[tokio::test]
+    async fn unfollowed_redirects_record_their_status() {
+        let server = TestServer::start(|request| match request.lines().next() {
+            Some(line) if line.starts_with("GET /old") => response(301, &[("Location", "/new")], ""),
+            _ => response(200, &[], "new"),
+        });
+        let config = |follow_redirects| Config {
+            url: format!("{}/old", server.url),
+            request_count: 2,
+            follow_redirects,
+            ..Default::default()
+        };
+        let run = |config: Config| async move {
+            let client = Runner::create_client(&config).unwrap();
+            Runner::new(client, config, None).unwrap().run().await.unwrap()
+        };
+        
+        let unfollowed = run(config(false)).await;
+        assert_eq!(unfollowed.status_codes.get(&301), Some(&2));
+        assert!(unfollowed.requests.iter().all(|r| r.status == Some(301)));
+        assert_eq!(server.requests().len(), 2);
+        
+        let followed = run(config(true)).await;
+        assert_eq!(followed.status_codes.get(&200), Some(&2));
+        assert!(!followed.status_codes.contains_key(&301));
+    }
+    
+    #[tokio::test]
+    async fn head_responses_take_their_size_from_the_content_length() {
+        let server = TestServer::start(|_| "HTTP/1.1 200 OK\r\nContent-Length: 1234\r\n\r\n".to_string());
+        let config = Config {
+            url: server.url.clone(),
+            method: Method::HEAD,
+            request_count: 3,
+            concurrency: 1,
+            ..Default::default()
+        };
+        
+        let results = runner(config).run().await.unwrap();
+        assert_eq!(results.successful_requests, 3);
+        assert!(results.requests.iter().all(|r| r.response_size == Some(1234)));
+        assert!(server.requests().iter().all(|request| request.starts_with("HEAD / HTTP/1.1")));
+    }
+    
+    #[tokio::test]
+    async fn streamed_bodies_count_every_byte() {
+        let server = TestServer::start(|request| match request.lines().next() {
+            Some(line) if line.starts_with("GET /chunked") => {
+                let chunk = "x".repeat(4096);
+                let mut body = String::new();
+                for _ in 0..5 {
+                    body.push_str(&format!("{:x}\r\n{}\r\n", chunk.len(), chunk));
+                }
+                format!("HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n{}0\r\n\r\n", body)
+            },
+            _ => response(200, &[], &"y".repeat(3 * 1024 * 1024)),
+        });
+        for (path, size) in [("/large", 3 * 1024 * 1024), ("/chunked", 5 * 4096)] {
+            let config = Config {
+                url: format!("{}{}", server.url, path),
+                request_count: 2,
+                stream_body: true,
+                ..Default::default()
+            };
+            
+            let results = runner(config).run().await.unwrap();
+            assert_eq!(results.successful_requests, 2, "{}", path);
+            assert!(results.requests.iter().all(|r| r.response_size == Some(size)), "{}", path);
+        }
+    }
+    
+    #[tokio::test]
+    async fn server_timing_durations_are_parsed_and_averaged() {
+        let served = AtomicUsize::new(0);
+        let server = TestServer::start(move |_| match served.fetch_add(1, Ordering::SeqCst) {
+            0 => response(200, &[("Server-Timing", "app;dur=42")], "ok"),
+            1 => response(200, &[("Server-Timing", "db;dur=10, app;desc=\"render\";dur=58")], "ok"),
+            _ => response(200, &[("Server-Timing", "cache;desc=hit, app;dur=soon")], "ok"),
+        });
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 3,
+            concurrency: 1,
+            ..Default::default()
+        };
+        
+        let results = runner(config).run().await.unwrap();
+        assert_eq!(results.requests.iter().find(|r| r.index == Some(0)).unwrap().server_timing,
+                   HashMap::from([("app".to_string(), 42.0)]));
+        let stats = results.server_timing.unwrap();
+        assert_eq!(stats.requests, 2);
+        assert_eq!(stats.average_server_time, 50.0);
+        assert_eq!(stats.metrics, HashMap::from([("app".to_string(), 50.0), ("db".to_string(), 10.0)]));
+    }
+    
+    #[tokio::test]
+    async fn partitioned_workers_send_contiguous_index_ranges() {
+        let server = TestServer::start(|_| response(200, &[], "ok"));
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 10,
+            concurrency: 3,
+            dispatch: DispatchMode::Partitioned,
+            ..Default::default()
+        };
+        
+        let results = runner(config).run().await.unwrap();
+        let mut by_worker: HashMap<usize, Vec<(u128, usize)>> = HashMap::new();
+        for request in &results.requests {
+            by_worker.entry(request.worker.unwrap()).or_default().push((request.started_at.unwrap(), request.index.unwrap()));
+        }
+        assert_eq!(by_worker.len(), 3);
+        
+        let mut ranges: Vec<(usize, usize)> = Vec::new();
+        for (worker, mut requests) in by_worker {
+            requests.sort();
+            let indices: Vec<usize> = requests.iter().map(|(_, index)| *index).collect();
+            let (first, last) = (indices[0], indices[indices.len() - 1]);
+            assert_eq!(indices, (first..=last).collect::<Vec<_>>(), "worker {}", worker);
+            ranges.push((worker, first));
+        }
+        ranges.sort();
+        assert_eq!(ranges[0].1, 0);
+        assert!(ranges.windows(2).all(|pair| pair[0].1 < pair[1].1));
+    }
+    
+    #[tokio::test]
+    async fn failing_servers_end_the_run_early() {
+        let failing = TestServer::start(|_| response(500, &[], "error"));
+        let healthy = TestServer::start(|_| response(200, &[], "ok"));
+        let config = |url: &str| Config {
+            url: url.to_string(),
+            request_count: 1000,
+            concurrency: 1,
+            abort_on_error_rate: Some(20.0),
+            ..Default::default()
+        };
+        
+        let results = runner(config(&failing.url)).run().await.unwrap();
+        assert_eq!(results.aborted.as_deref(), Some("error rate 100.0% exceeded 20.0% after 20 requests"));
+        assert_eq!(results.total_requests, 20);
+        assert_eq!(failing.requests().len(), 20);
+        
+        let results = runner(config(&healthy.url)).run().await.unwrap();
+        assert_eq!(results.aborted, None);
+        assert_eq!(results.total_requests, 1000);
+    }
+    
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn requests_in_flight_run_on_several_worker_threads() {
+        let server = TestServer::start(|_| response(200, &[], "ok"));
+        let threads = Arc::new(Mutex::new(HashSet::new()));
+        let config = Config {
+            url: server.url.clone(),
+            request_count: 16,
+            concurrency: 8,
+            ..Default::default()
+        };
+        // The callback runs in the request's task and blocks its thread for a while
+        let recorded = threads.clone();
+        let runner = runner(config).on_result(Arc::new(move |_: &RequestResult| {
+            recorded.lock().unwrap().insert(std::thread::current().id());
+            std::thread::sleep(Duration::from_millis(50));
+        }));
+        
+        let results = runner.run().await.unwrap();
+        assert_eq!(results.successful_requests, 16);
+        assert!(threads.lock().unwrap().len() > 1);
+    }
+}
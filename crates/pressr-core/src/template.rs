@@ -0,0 +1,293 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use serde_json::Value;
+use tracing::debug;
+
+use crate::data::{encode_url_value, url_path_start, BodyTransform, RequestData, WeightedBody};
+
+/// Derive the seed of the request with the given index from a run seed
+///
+/// Seeds are spaced evenly, so `request_seed(request_seed(seed, a), b)` equals
+/// `request_seed(seed, a + b)` and a run split into batches stays reproducible.
+pub fn request_seed(seed: u64, index: usize) -> u64 {
+    seed.wrapping_add((index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+}
+
+/// Context used to resolve template tokens for a single request
+#[derive(Debug)]
+pub struct TemplateContext<'a> {
+    /// Sequence number of the request (starting at 1)
+    pub seq: usize,
+
+    /// Identifier of the run, for `runid` tokens (None leaves them unresolved)
+    pub run_id: Option<String>,
+
+    /// Request data providing variable sets
+    pub data: Option<&'a RequestData>,
+
+    /// CSV row used by this request (if the data has rows)
+    pub row: Option<&'a HashMap<String, String>>,
+
+    /// Values extracted by earlier scenario steps
+    values: RefCell<HashMap<String, String>>,
+
+    /// Random number generator for `uuid` tokens and variable picks
+    rng: RefCell<StdRng>,
+}
+
+impl<'a> TemplateContext<'a> {
+    /// Create a template context for the request with the given index
+    ///
+    /// With a run seed, all random values of the request are derived from it
+    /// and the request index, independent of the order requests are executed in.
+    pub fn new(index: usize, data: Option<&'a RequestData>, seed: Option<u64>) -> Self {
+        let mut rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(request_seed(seed, index)),
+            None => StdRng::from_entropy(),
+        };
+        let row = data.and_then(|data| data.select_row(index, &mut rng));
+
+        Self {
+            seq: index + 1,
+            run_id: None,
+            data,
+            row,
+            values: RefCell::new(HashMap::new()),
+            rng: RefCell::new(rng),
+        }
+    }
+
+    /// Set the run identifier resolved by `runid` tokens
+    pub fn with_run_id(mut self, run_id: String) -> Self {
+        self.run_id = Some(run_id);
+        self
+    }
+
+    /// Set a value resolved by `{{name}}` tokens, e.g. one extracted by a scenario step
+    pub fn set_value(&self, name: &str, value: String) {
+        self.values.borrow_mut().insert(name.to_string(), value);
+    }
+
+    /// Resolve a single token name to its value
+    ///
+    /// Built-in tokens are `seq`, `index` (the 0-based request index), `runid`, `uuid`
+    /// and `now`; any other name is looked up in the values set with `set_value`, then
+    /// in the columns of the CSV row, then in the variable sets of the request data.
+    pub fn resolve(&self, token: &str) -> Option<String> {
+        match token {
+            "seq" => Some(self.seq.to_string()),
+            "index" => Some((self.seq - 1).to_string()),
+            "runid" => self.run_id.clone(),
+            "uuid" => {
+                let bytes = self.rng.borrow_mut().gen();
+                Some(uuid::Builder::from_random_bytes(bytes).into_uuid().to_string())
+            },
+            "now" => Some(chrono::Utc::now().to_rfc3339()),
+            name => self.values.borrow().get(name).cloned()
+                .or_else(|| self.row.and_then(|row| row.get(name).cloned()))
+                .or_else(|| self.data
+                    .and_then(|data| data.select_variable(name, self.seq - 1, &mut *self.rng.borrow_mut()))
+                    .map(|value| value.to_string())),
+        }
+    }
+
+    /// Pick the body of the request from the body pool of the request data
+    pub fn choose_body(&self) -> Option<(usize, &'a WeightedBody)> {
+        self.data?.choose_body(&mut *self.rng.borrow_mut())
+    }
+
+    /// Replace all `{{token}}` occurrences in a string
+    ///
+    /// Unknown tokens are left untouched.
+    pub fn render(&self, template: &str) -> String {
+        self.render_from(template, usize::MAX)
+    }
+
+    /// Replace all `{{token}}` occurrences in a URL, percent-encoding the values in its path
+    /// and query (e.g. a CSV value with a `/` stays a single path segment)
+    pub fn render_url(&self, url: &str) -> String {
+        self.render_from(url, url_path_start(url))
+    }
+
+    /// Replace all `{{token}}` occurrences, percent-encoding the values of the tokens at or
+    /// after the offset `encode_from`
+    fn render_from(&self, template: &str, encode_from: usize) -> String {
+        let mut output = String::with_capacity(template.len());
+        let mut rest = template;
+
+        while let Some(start) = rest.find("{{") {
+            output.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+
+            match after.find("}}") {
+                Some(end) => {
+                    let token = after[..end].trim();
+                    match self.resolve(token) {
+                        Some(value) if template.len() - rest.len() + start >= encode_from => {
+                            output.push_str(&encode_url_value(&value));
+                        },
+                        Some(value) => output.push_str(&value),
+                        None => output.push_str(&rest[start..start + 2 + end + 2]),
+                    }
+                    rest = &after[end + 2..];
+                },
+                None => {
+                    output.push_str(&rest[start..]);
+                    rest = "";
+                }
+            }
+        }
+
+        output.push_str(rest);
+        output
+    }
+
+    /// Render the tokens in every string of a JSON value
+    pub fn render_value(&self, value: &mut Value) {
+        match value {
+            Value::String(text) if text.contains("{{") => *text = self.render(text),
+            Value::Array(items) => items.iter_mut().for_each(|item| self.render_value(item)),
+            Value::Object(object) => object.values_mut().for_each(|item| self.render_value(item)),
+            _ => {}
+        }
+    }
+
+    /// Apply a transform pipeline to a JSON body
+    pub fn apply_transforms(&self, body: &mut Value, transforms: &[BodyTransform]) {
+        for transform in transforms {
+            match transform {
+                BodyTransform::Set { set, value } => {
+                    let value = self.render(value);
+                    let value = serde_json::from_str(&value).unwrap_or(Value::String(value));
+                    debug!("Setting body field '{}'", set);
+                    set_field(body, set, value);
+                },
+                BodyTransform::Remove { remove } => {
+                    debug!("Removing body field '{}'", remove);
+                    remove_field(body, remove);
+                },
+            }
+        }
+    }
+}
+
+/// Field names along a dot-separated path or a JSON pointer (with `~1` and `~0` unescaped)
+fn field_names(path: &str) -> Vec<String> {
+    match path.strip_prefix('/') {
+        Some(pointer) => pointer.split('/').map(|name| name.replace("~1", "/").replace("~0", "~")).collect(),
+        None => path.split('.').map(str::to_string).collect(),
+    }
+}
+
+/// Set a field in a JSON object, creating intermediate objects as needed
+fn set_field(body: &mut Value, path: &str, value: Value) {
+    let mut current = body;
+    let mut names = field_names(path).into_iter().peekable();
+
+    while let Some(name) = names.next() {
+        if !current.is_object() {
+            *current = Value::Object(serde_json::Map::new());
+        }
+        let object = current.as_object_mut().expect("value was just made an object");
+
+        if names.peek().is_none() {
+            object.insert(name, value);
+            return;
+        }
+
+        current = object.entry(name).or_insert(Value::Null);
+    }
+}
+
+/// Remove a field from a JSON object, if present
+fn remove_field(body: &mut Value, path: &str) {
+    let mut names = field_names(path);
+    let Some(field) = names.pop() else {
+        return;
+    };
+    let parent = names.iter().try_fold(body, |current, name| match current {
+        Value::Array(items) => name.parse().ok().and_then(|index: usize| items.get_mut(index)),
+        current => current.get_mut(name.as_str()),
+    });
+
+    if let Some(Value::Object(object)) = parent {
+        object.remove(&field);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn transform(body: Value, transforms: Value) -> Value {
+        let transforms: Vec<BodyTransform> = serde_json::from_value(transforms).unwrap();
+        let mut body = body;
+        TemplateContext::new(6, None, Some(1)).apply_transforms(&mut body, &transforms);
+        body
+    }
+
+    #[test]
+    fn set_transforms_parse_json_values() {
+        let body = transform(json!({}), json!([
+            { "set": "id", "value": "{{seq}}" },
+            { "set": "label", "value": "\"{{seq}}\"" },
+            { "set": "meta.tags", "value": "[\"a\", {{index}}]" },
+            { "set": "ts", "value": "{{now}}" },
+            { "set": "request", "value": "{{uuid}}" },
+        ]));
+
+        assert_eq!(body["id"], 7);
+        assert_eq!(body["label"], "7");
+        assert_eq!(body["meta"]["tags"], json!(["a", 6]));
+        assert!(chrono::DateTime::parse_from_rfc3339(body["ts"].as_str().unwrap()).is_ok());
+        assert!(uuid::Uuid::parse_str(body["request"].as_str().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn remove_transforms_follow_dotted_paths_and_pointers() {
+        let body = json!({ "a": { "b": 1, "c": 2 }, "items": [{ "x": 1, "y": 2 }], "d/e": { "f~g": 3, "h": 4 } });
+        let body = transform(body, json!([
+            { "remove": "a.b" },
+            { "remove": "items.0.x" },
+            { "remove": "/d~1e/f~0g" },
+            { "remove": "missing.field" },
+        ]));
+
+        assert_eq!(body, json!({ "a": { "c": 2 }, "items": [{ "y": 2 }], "d/e": { "h": 4 } }));
+    }
+
+    #[test]
+    fn set_transforms_unescape_pointer_tokens() {
+        let body = transform(json!({ "a": 1 }), json!([{ "set": "/x~1y/z~0", "value": "true" }]));
+
+        assert_eq!(body, json!({ "a": 1, "x/y": { "z~": true } }));
+    }
+
+    #[test]
+    fn variables_are_substituted_in_nested_json_bodies() {
+        let data = RequestData {
+            variables: HashMap::from([
+                ("user".to_string(), vec!["alice".to_string(), "bob".to_string()]),
+                ("sku".to_string(), vec!["A-1".to_string()]),
+            ]),
+            ..Default::default()
+        };
+        let mut body = json!({
+            "order": {
+                "customer": { "name": "{{user}}", "note": "for {{user}} #{{seq}}" },
+                "items": [{ "sku": "{{sku}}", "qty": 2 }, ["{{sku}}", "{{missing}}"]],
+            },
+            "count": 1,
+        });
+        TemplateContext::new(0, Some(&data), Some(3)).render_value(&mut body);
+
+        let name = body["order"]["customer"]["name"].as_str().unwrap();
+        assert!(name == "alice" || name == "bob", "{}", name);
+        assert!(body["order"]["customer"]["note"].as_str().unwrap().ends_with(" #1"));
+        assert_eq!(body["order"]["items"], json!([{ "sku": "A-1", "qty": 2 }, ["A-1", "{{missing}}"]]));
+        assert_eq!(body["count"], 1);
+    }
+}
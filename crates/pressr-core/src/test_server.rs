@@ -0,0 +1,118 @@
+//! Minimal HTTP/1.1 server for tests
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Builds the raw response to a raw request (head and body)
+type Responder = dyn Fn(&str) -> Vec<u8> + Send + Sync;
+
+/// Server answering every request on keep-alive connections, recording the requests
+pub(crate) struct TestServer {
+    /// Base URL of the server, e.g. "http://127.0.0.1:41234"
+    pub url: String,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl TestServer {
+    /// Serve every request with the response `respond` builds from it
+    pub fn start(respond: impl Fn(&str) -> String + Send + Sync + 'static) -> Self {
+        Self::start_bytes(move |request| respond(request).into_bytes())
+    }
+    
+    /// Serve every request with the raw bytes `respond` builds from it, e.g. a compressed body
+    pub fn start_bytes(respond: impl Fn(&str) -> Vec<u8> + Send + Sync + 'static) -> Self {
+        Self::spawn(Arc::new(respond), None)
+    }
+    
+    /// Serve every request with the response `respond` builds from it, closing connections
+    /// that stay idle for `idle_timeout`
+    pub fn start_closing_idle(idle_timeout: Duration, respond: impl Fn(&str) -> String + Send + Sync + 'static) -> Self {
+        Self::spawn(Arc::new(move |request: &str| respond(request).into_bytes()), Some(idle_timeout))
+    }
+    
+    fn spawn(respond: Arc<Responder>, idle_timeout: Option<Duration>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        
+        {
+            let requests = requests.clone();
+            thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    // A read timing out ends `serve`, closing the connection
+                    stream.set_read_timeout(idle_timeout).unwrap();
+                    let requests = requests.clone();
+                    let respond = respond.clone();
+                    thread::spawn(move || serve(stream, &requests, &*respond));
+                }
+            });
+        }
+        
+        Self { url, requests }
+    }
+    
+    /// Raw requests received so far, in arrival order
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+
+}
+
+/// Raw response with a status, extra headers and a body
+pub(crate) fn response(status: u16, headers: &[(&str, &str)], body: &str) -> String {
+    let mut response = format!("HTTP/1.1 {} Test\r\nContent-Length: {}\r\n", status, body.len());
+    for (name, value) in headers {
+        response.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    response.push_str("\r\n");
+    response.push_str(body);
+    response
+}
+
+/// Header value of a raw request (names are matched case-insensitively)
+pub(crate) fn header<'a>(request: &'a str, name: &str) -> Option<&'a str> {
+    request.lines()
+        .skip(1)
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.split_once(':'))
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.trim())
+}
+
+/// Answer the requests of one connection until the client closes it
+fn serve(stream: TcpStream, requests: &Mutex<Vec<String>>, respond: &Responder) {
+    let mut writer = stream.try_clone().unwrap();
+    let mut reader = BufReader::new(stream);
+    loop {
+        let mut request = String::new();
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                return;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap_or(0);
+                }
+            }
+            request.push_str(&line);
+            if line == "\r\n" {
+                break;
+            }
+        }
+        let mut body = vec![0; content_length];
+        if reader.read_exact(&mut body).is_err() {
+            return;
+        }
+        request.push_str(&String::from_utf8_lossy(&body));
+        
+        requests.lock().unwrap().push(request.clone());
+        if writer.write_all(&respond(&request)).is_err() {
+            return;
+        }
+    }
+}
@@ -1,13 +1,15 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 use pressr_core::{
-    Runner, Config, Error as PressrError, LoadTestResults
+    Runner, Config, Auth, Error as PressrError, HttpVersion, LoadTestResults, StopHandle
 };
 use reqwest::Method;
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, BTreeMap};
 use std::str::FromStr;
+use std::sync::Mutex;
 use std::time::Duration;
+use tauri::State;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -42,8 +44,18 @@ struct LoadTestParams {
     concurrency: u64,
     timeout_ms: Option<u64>,
     headers: Option<HashMap<String, String>>,
+    auth: Option<Auth>,
+    cookie_store: Option<bool>,
+    /// HTTP version to speak: "auto", "http1" or "http2" (HTTP/2 with prior knowledge)
+    http_version: Option<String>,
+    /// Drop requests in flight as soon as the test is stopped instead of letting them finish
+    abandon_on_stop: Option<bool>,
 }
 
+/// Stop handle of the load test currently running, if any
+#[derive(Default)]
+struct RunningTest(Mutex<Option<StopHandle>>);
+
 #[derive(Debug, Serialize)]
 struct LoadTestResponse {
     results: TestResults,
@@ -65,7 +77,7 @@ struct TestResults {
 }
 
 #[tauri::command]
-async fn run_load_test(params: LoadTestParams) -> Result<LoadTestResponse, GuiError> {
+async fn run_load_test(params: LoadTestParams, running: State<'_, RunningTest>) -> Result<LoadTestResponse, GuiError> {
     println!("Received request to test URL: {}", params.url);
     
     // Parse HTTP method
@@ -89,11 +101,12 @@ async fn run_load_test(params: LoadTestParams) -> Result<LoadTestResponse, GuiEr
         }
     }
     
-    // Create the client
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_millis(timeout))
-        .build()
-        .map_err(|e| GuiError::Core(PressrError::HttpClient(e)))?;
+    let http_version = match params.http_version.as_deref() {
+        None | Some("auto") => HttpVersion::Auto,
+        Some("http1") => HttpVersion::Http1,
+        Some("http2") => HttpVersion::Http2PriorKnowledge,
+        Some(other) => return Err(GuiError::InvalidParameter(format!("Unknown HTTP version: {}", other))),
+    };
     
     // Create the config
     let config = Config {
@@ -102,14 +115,31 @@ async fn run_load_test(params: LoadTestParams) -> Result<LoadTestResponse, GuiEr
         headers,
         request_count: params.requests as usize,
         concurrency: params.concurrency as usize,
-        timeout: timeout / 1000, // Convert to seconds for the Config
+        timeout: timeout.div_ceil(1000), // Convert to whole seconds for the Config
+        auth: params.auth,
+        cookie_store: params.cookie_store.unwrap_or(false),
+        http_version,
+        ..Default::default()
     };
+    let config = if params.abandon_on_stop.unwrap_or(false) {
+        Config { shutdown_grace: Duration::ZERO, ..config }
+    } else {
+        // Requests in flight get the full timeout to finish
+        Config { shutdown_grace: Duration::from_millis(timeout), ..config }
+    };
+    
+    // Create the client with the connection settings of the config
+    let client = Runner::create_client(&config)?;
     
     // Create the runner
-    let runner = Runner::new(client, config, None);
+    let runner = Runner::new(client, config, None)?;
+    *running.0.lock().unwrap() = Some(runner.stop_handle());
     
-    // Run the load test
-    let result = runner.run().await.map_err(GuiError::Core)?;
+    // Run the load test; a stopped test returns the results of the requests completed so far
+    let result = runner.run().await;
+    running.0.lock().unwrap().take();
+    let result = result.map_err(GuiError::Core)?;
+    println!("Load test completed: {}", result.summary());
     
     // Convert the result to our response format
     let response = convert_result_to_response(result);
@@ -117,6 +147,19 @@ async fn run_load_test(params: LoadTestParams) -> Result<LoadTestResponse, GuiEr
     Ok(response)
 }
 
+/// Stop the running load test, if any
+#[tauri::command]
+fn stop_load_test(running: State<'_, RunningTest>) -> bool {
+    match running.0.lock().unwrap().as_ref() {
+        Some(stop) => {
+            println!("Stopping the load test");
+            stop.stop();
+            true
+        },
+        None => false,
+    }
+}
+
 // Helper function to convert core result to GUI response
 fn convert_result_to_response(result: LoadTestResults) -> LoadTestResponse {
     // Convert status counts map
@@ -156,7 +199,8 @@ fn convert_result_to_response(result: LoadTestResults) -> LoadTestResponse {
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .invoke_handler(tauri::generate_handler![run_load_test])
+        .manage(RunningTest::default())
+        .invoke_handler(tauri::generate_handler![run_load_test, stop_load_test])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -8,6 +8,9 @@ interface FormData {
   requests: number;
   concurrency: number;
   timeout?: number;
+  httpVersion: string;
+  cookieStore: boolean;
+  abandonOnStop: boolean;
   headers: { key: string; value: string }[];
 }
 
@@ -49,10 +52,14 @@ function App() {
     requests: 100,
     concurrency: 10,
     timeout: 30000,
+    httpVersion: "auto",
+    cookieStore: false,
+    abandonOnStop: false,
     headers: [],
   });
   
   const [isLoading, setIsLoading] = useState(false);
+  const [isStopping, setIsStopping] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [testResults, setTestResults] = useState<TestResult | null>(null);
   const [rawResults, setRawResults] = useState<BackendResponse | null>(null);
@@ -61,9 +68,11 @@ function App() {
     const { name, value } = e.target;
     setFormData({
       ...formData,
-      [name]: name === "requests" || name === "concurrency" || name === "timeout" 
-        ? Number(value) 
-        : value,
+      [name]: e.target instanceof HTMLInputElement && e.target.type === "checkbox"
+        ? e.target.checked
+        : name === "requests" || name === "concurrency" || name === "timeout" 
+          ? Number(value) 
+          : value,
     });
   };
   
@@ -92,6 +101,17 @@ function App() {
     });
   };
   
+  // Stop dispatching requests; the test then returns the results completed so far
+  const handleStop = async () => {
+    setIsStopping(true);
+    try {
+      await invoke<boolean>("stop_load_test");
+    } catch (err) {
+      console.error("Stop error:", err);
+      setIsStopping(false);
+    }
+  };
+  
   const handleSubmit = async (e: React.FormEvent) => {
     e.preventDefault();
     setIsLoading(true);
@@ -115,6 +135,9 @@ function App() {
         requests: formData.requests,
         concurrency: formData.concurrency,
         timeout_ms: formData.timeout,
+        http_version: formData.httpVersion,
+        cookie_store: formData.cookieStore,
+        abandon_on_stop: formData.abandonOnStop,
         headers: Object.keys(headersObject).length > 0 ? headersObject : null,
       };
       console.log("Sending params:", params);
@@ -148,6 +171,7 @@ function App() {
       console.error("Load test error:", err);
       setError(err instanceof Error ? err.message : String(err));
     } finally {
+      setIsStopping(false);
       setIsLoading(false);
     }
   };
@@ -262,6 +286,49 @@ function App() {
               </div>
             </div>
             
+            <div className="form-group">
+              <label htmlFor="httpVersion">HTTP Version:</label>
+              <select
+                id="httpVersion"
+                name="httpVersion"
+                value={formData.httpVersion}
+                onChange={handleChange}
+                className="form-control"
+              >
+                <option value="auto">Auto (negotiated)</option>
+                <option value="http1">HTTP/1.1</option>
+                <option value="http2">HTTP/2 (prior knowledge)</option>
+              </select>
+            </div>
+            
+            <div className="form-row">
+              <div className="form-group">
+                <label htmlFor="cookieStore" className="checkbox-label">
+                  <input
+                    type="checkbox"
+                    id="cookieStore"
+                    name="cookieStore"
+                    checked={formData.cookieStore}
+                    onChange={handleChange}
+                  />
+                  Keep cookies (shared by all requests)
+                </label>
+              </div>
+              
+              <div className="form-group">
+                <label htmlFor="abandonOnStop" className="checkbox-label">
+                  <input
+                    type="checkbox"
+                    id="abandonOnStop"
+                    name="abandonOnStop"
+                    checked={formData.abandonOnStop}
+                    onChange={handleChange}
+                  />
+                  Abandon requests in flight on stop
+                </label>
+              </div>
+            </div>
+            
             <div className="form-group">
               <label>HTTP Headers:</label>
               {formData.headers.map((header, index) => (
@@ -299,6 +366,16 @@ function App() {
             </div>
             
             <div className="form-actions">
+              {isLoading && (
+                <button
+                  type="button"
+                  onClick={handleStop}
+                  disabled={isStopping}
+                  className="btn btn-stop"
+                >
+                  {isStopping ? "Stopping..." : "Stop"}
+                </button>
+              )}
               <button
                 type="submit"
                 disabled={isLoading}